@@ -0,0 +1,29 @@
+//! Thin wrapper over the system clipboard, compiled out without the `clipboard` feature.
+
+use anyhow::Result;
+
+#[cfg(feature = "clipboard")]
+pub fn copy_text(text: &str) -> Result<()> {
+    use std::sync::Mutex;
+
+    use anyhow::Context;
+    use once_cell::sync::OnceCell;
+
+    // On X11/Wayland the owning process serves the clipboard, so keep one handle alive
+    // for the lifetime of the app instead of dropping it after each copy.
+    static CLIPBOARD: OnceCell<Mutex<arboard::Clipboard>> = OnceCell::new();
+    let clipboard = CLIPBOARD
+        .get_or_try_init(|| arboard::Clipboard::new().map(Mutex::new))
+        .context("System clipboard is unavailable")?;
+    let mut clipboard = clipboard
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Clipboard handle is poisoned"))?;
+    clipboard
+        .set_text(text)
+        .context("Failed to copy to the clipboard")
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_text(_text: &str) -> Result<()> {
+    anyhow::bail!("Clipboard support is not enabled in this build")
+}
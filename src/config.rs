@@ -2,18 +2,162 @@ use std::env;
 use std::fs;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 
+use crate::history::DEFAULT_MAX_FRAMES;
+use crate::model::WS_URL_DEFAULT;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AppConfig {
     #[serde(default = "default_idle_seconds")]
     pub idle_seconds: u64,
     #[serde(default = "default_decoration")]
     pub default_decoration: String,
+    #[serde(default = "default_bar_scale")]
+    pub bar_scale: String,
+    #[serde(default = "default_bar_metric")]
+    pub bar_metric: String,
     #[serde(default = "default_mode")]
     pub default_mode: String,
+    #[serde(default = "default_view_layout")]
+    pub view_layout: String,
+    #[serde(default = "default_sort")]
+    pub default_sort: String,
+    #[serde(default = "default_ws_url")]
+    pub ws_url: String,
+    #[serde(default)]
+    pub character_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_auth_token: Option<String>,
+    #[serde(default)]
+    pub ws_auth_query: bool,
+    #[serde(default = "default_search_url_template")]
+    pub search_url_template: String,
+    #[serde(default = "default_number_locale")]
+    pub number_locale: String,
+    #[serde(default = "default_stale_seconds")]
+    pub stale_seconds: u64,
+    #[serde(default = "default_idle_rotate_seconds")]
+    pub idle_rotate_seconds: u64,
+    #[serde(default = "default_input_delays_idle")]
+    pub input_delays_idle: bool,
+    #[serde(default = "default_input_grace_seconds")]
+    pub input_grace_seconds: u64,
+    #[serde(default = "default_redraw_ms")]
+    pub redraw_ms: u64,
+    #[serde(default)]
+    pub min_record_seconds: u64,
+    #[serde(default)]
+    pub min_record_damage: u64,
+    #[serde(default)]
+    pub retention_days: u64,
+    #[serde(default = "default_max_frames")]
+    pub max_frames: u64,
+    #[serde(default)]
+    pub store_raw_frames: bool,
+    #[serde(default = "default_json_export_frames")]
+    pub json_export_frames: bool,
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default)]
+    pub abbreviate_numbers: bool,
+    #[serde(default)]
+    pub role_glyphs: bool,
+    #[serde(default = "default_show_trends")]
+    pub show_trends: bool,
+    #[serde(default)]
+    pub show_totals: bool,
+    #[serde(default)]
+    pub show_total_row: bool,
+    #[serde(default)]
+    pub show_quality: bool,
+    #[serde(default)]
+    pub show_cdh: bool,
+    #[serde(default)]
+    pub show_overheal: bool,
+    #[serde(default)]
+    pub show_rolling_dps: bool,
+    #[serde(default)]
+    pub show_active: bool,
+    #[serde(default)]
+    pub live_timer: bool,
+    #[serde(default)]
+    pub compact: bool,
+    #[serde(default = "default_show_header")]
+    pub show_header: bool,
+    #[serde(default = "default_show_footer")]
+    pub show_footer: bool,
+    #[serde(default)]
+    pub anonymize: bool,
+    #[serde(default = "default_hide_pets")]
+    pub hide_pets: bool,
+    #[serde(default)]
+    pub party_only: bool,
+    #[serde(default)]
+    pub hide_zero_rows: bool,
+    #[serde(default = "default_keep_self_row")]
+    pub keep_self_row: bool,
+    #[serde(default = "default_pet_names")]
+    pub pet_names: Vec<String>,
+    #[serde(default)]
+    pub keybindings: KeybindingsConfig,
+    #[serde(default)]
+    pub columns: ColumnsConfig,
+    #[serde(default = "default_subscribe_events")]
+    pub subscribe_events: Vec<String>,
+}
+
+/// Live table columns per view mode, by name (see `model::TableColumn`), in display order.
+/// An empty list keeps the built-in layouts for that mode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnsConfig {
+    pub dps: Vec<String>,
+    pub heal: Vec<String>,
+    pub tank: Vec<String>,
+}
+
+/// Single-character keys for the global actions; see `keymap::Keymap`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeybindingsConfig {
+    pub quit: char,
+    pub toggle_history: char,
+    pub toggle_idle: char,
+    pub cycle_decoration: char,
+    pub cycle_mode: char,
+    pub settings: char,
+    pub toggle_compact: char,
+    pub toggle_anonymize: char,
+    pub toggle_header: char,
+    pub toggle_footer: char,
+    pub help: char,
+    pub about: char,
+    pub logs: char,
+}
+
+impl Default for KeybindingsConfig {
+    fn default() -> Self {
+        Self {
+            quit: 'q',
+            toggle_history: 'h',
+            toggle_idle: 'i',
+            cycle_decoration: 'd',
+            cycle_mode: 'm',
+            settings: 's',
+            toggle_compact: 'c',
+            toggle_anonymize: 'n',
+            toggle_header: 'H',
+            toggle_footer: 'F',
+            help: '?',
+            about: 'v',
+            logs: 'l',
+        }
+    }
 }
 
 impl Default for AppConfig {
@@ -21,7 +165,52 @@ impl Default for AppConfig {
         Self {
             idle_seconds: default_idle_seconds(),
             default_decoration: default_decoration(),
+            bar_scale: default_bar_scale(),
+            bar_metric: default_bar_metric(),
             default_mode: default_mode(),
+            view_layout: default_view_layout(),
+            default_sort: default_sort(),
+            ws_url: default_ws_url(),
+            character_name: String::new(),
+            ws_auth_token: None,
+            ws_auth_query: false,
+            search_url_template: default_search_url_template(),
+            number_locale: default_number_locale(),
+            stale_seconds: default_stale_seconds(),
+            idle_rotate_seconds: default_idle_rotate_seconds(),
+            input_delays_idle: default_input_delays_idle(),
+            input_grace_seconds: default_input_grace_seconds(),
+            redraw_ms: default_redraw_ms(),
+            min_record_seconds: 0,
+            min_record_damage: 0,
+            retention_days: 0,
+            max_frames: default_max_frames(),
+            store_raw_frames: false,
+            json_export_frames: default_json_export_frames(),
+            theme: default_theme(),
+            abbreviate_numbers: false,
+            role_glyphs: false,
+            show_trends: default_show_trends(),
+            show_totals: false,
+            show_total_row: false,
+            show_quality: false,
+            show_cdh: false,
+            show_overheal: false,
+            show_rolling_dps: false,
+            show_active: false,
+            live_timer: false,
+            compact: false,
+            show_header: default_show_header(),
+            show_footer: default_show_footer(),
+            anonymize: false,
+            hide_pets: default_hide_pets(),
+            party_only: false,
+            hide_zero_rows: false,
+            keep_self_row: default_keep_self_row(),
+            pet_names: default_pet_names(),
+            keybindings: KeybindingsConfig::default(),
+            columns: ColumnsConfig::default(),
+            subscribe_events: default_subscribe_events(),
         }
     }
 }
@@ -34,26 +223,174 @@ fn default_decoration() -> String {
     "underline".to_string()
 }
 
+fn default_bar_scale() -> String {
+    "max".to_string()
+}
+
+fn default_view_layout() -> String {
+    "single".to_string()
+}
+
+fn default_number_locale() -> String {
+    "auto".to_string()
+}
+
+fn default_bar_metric() -> String {
+    "mode".to_string()
+}
+
 fn default_mode() -> String {
     "dps".to_string()
 }
 
+fn default_sort() -> String {
+    "metric".to_string()
+}
+
+fn default_max_frames() -> u64 {
+    DEFAULT_MAX_FRAMES as u64
+}
+
+fn default_stale_seconds() -> u64 {
+    10
+}
+
+fn default_idle_rotate_seconds() -> u64 {
+    15
+}
+
+fn default_redraw_ms() -> u64 {
+    100
+}
+
+fn default_json_export_frames() -> bool {
+    true
+}
+
+fn default_show_trends() -> bool {
+    true
+}
+
+fn default_input_delays_idle() -> bool {
+    true
+}
+
+fn default_input_grace_seconds() -> u64 {
+    30
+}
+
+fn default_keep_self_row() -> bool {
+    true
+}
+
+fn default_show_header() -> bool {
+    true
+}
+
+fn default_show_footer() -> bool {
+    true
+}
+
+fn default_hide_pets() -> bool {
+    true
+}
+
+/// OverlayPlugin events requested on connect; the app handles CombatData and LogLine.
+pub fn default_subscribe_events() -> Vec<String> {
+    vec!["CombatData".to_string(), "LogLine".to_string()]
+}
+
+/// Pets and summons that ACT can report as their own combatants ("Eos (Alice)" matches "Eos").
+pub fn default_pet_names() -> Vec<String> {
+    [
+        "Carbuncle",
+        "Emerald Carbuncle",
+        "Topaz Carbuncle",
+        "Ruby Carbuncle",
+        "Ifrit-Egi",
+        "Titan-Egi",
+        "Garuda-Egi",
+        "Demi-Bahamut",
+        "Demi-Phoenix",
+        "Solar Bahamut",
+        "Eos",
+        "Selene",
+        "Seraph",
+        "Rook Autoturret",
+        "Automaton Queen",
+        "Esteem",
+        "Living Shadow",
+        "Earthly Star",
+        "Liturgic Bell",
+        "Bunshin",
+        "Limit Break",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_theme() -> String {
+    "cyberpunk".to_string()
+}
+
+fn default_ws_url() -> String {
+    WS_URL_DEFAULT.to_string()
+}
+
+pub fn default_search_url_template() -> String {
+    "https://duckduckgo.com/?q=fflogs+{title}+{zone}+{date}".to_string()
+}
+
+/// Reads the config file; a missing file yields defaults. A file that fails to parse is moved
+/// to `iinact-tui.config.bak` so the next save doesn't overwrite it, and the error says where.
 pub fn load() -> Result<AppConfig> {
-    let path = config_path();
-    match fs::read(&path) {
-        Ok(bytes) => {
-            let cfg: AppConfig = serde_json::from_slice(&bytes)
-                .with_context(|| format!("Failed to parse config at {}", path.display()))?;
-            Ok(cfg)
-        }
+    load_from(&config_path())
+}
+
+/// Reads the config at `path`. An unparsable file is moved aside to a fresh backup next to it
+/// and an error describing the move is returned, so the caller can fall back to defaults.
+/// Whenever the file exists but couldn't be read or moved aside, saving is blocked for the
+/// rest of the run so the defaults never overwrite the user's only copy.
+fn load_from(path: &Path) -> Result<AppConfig> {
+    match fs::read(path) {
+        Ok(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(cfg) => Ok(cfg),
+            Err(err) => {
+                let backup = free_backup_path(path);
+                if let Err(rename_err) = fs::rename(path, &backup) {
+                    SAVE_BLOCKED.store(true, Ordering::Relaxed);
+                    bail!(
+                        "config {} invalid ({err}) and not backed up ({rename_err}); using defaults, changes won't be saved",
+                        path.display()
+                    );
+                }
+                bail!(
+                    "config invalid ({err}); moved to {}, using defaults",
+                    backup.display()
+                )
+            }
+        },
         Err(err) if err.kind() == ErrorKind::NotFound => Ok(AppConfig::default()),
         Err(err) => {
-            Err(err).with_context(|| format!("Failed to read config at {}", path.display()))
+            SAVE_BLOCKED.store(true, Ordering::Relaxed);
+            Err(err).with_context(|| {
+                format!(
+                    "config {} unreadable; using defaults, changes won't be saved",
+                    path.display()
+                )
+            })
         }
     }
 }
 
+/// Set when the config file couldn't be read or backed up; `save` then refuses to write.
+static SAVE_BLOCKED: AtomicBool = AtomicBool::new(false);
+
 pub fn save(cfg: &AppConfig) -> Result<()> {
+    if SAVE_BLOCKED.load(Ordering::Relaxed) {
+        bail!("Not saving config: the existing file could not be read or backed up");
+    }
     let path = config_path();
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
@@ -65,8 +402,53 @@ pub fn save(cfg: &AppConfig) -> Result<()> {
     Ok(())
 }
 
+/// Name given with `--profile`; unset means the default profile stored directly in `config_dir()`.
+static PROFILE: OnceCell<String> = OnceCell::new();
+
+/// Selects a named profile for the rest of the run. Must be called before anything reads a path.
+pub fn set_profile(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        bail!("profile name `{name}` may only use letters, digits, `-`, `_` and `.`");
+    }
+    if PROFILE.set(name.to_string()).is_err() {
+        bail!("profile already selected");
+    }
+    Ok(())
+}
+
+/// Directory holding the active profile's config, session, history and exports.
+pub fn profile_dir() -> PathBuf {
+    match PROFILE.get() {
+        Some(name) => config_dir().join("profiles").join(name),
+        None => config_dir(),
+    }
+}
+
 pub fn config_path() -> PathBuf {
-    config_dir().join("iinact-tui.config")
+    profile_dir().join("iinact-tui.config")
+}
+
+/// `<config>.<timestamp>.bak` next to `path`, with a counter added until the name is unused so
+/// an earlier backup is never replaced.
+fn free_backup_path(path: &Path) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    (1..)
+        .map(|n| {
+            let mut name = path.as_os_str().to_os_string();
+            if n == 1 {
+                name.push(format!(".{stamp}.bak"));
+            } else {
+                name.push(format!(".{stamp}-{n}.bak"));
+            }
+            PathBuf::from(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("an unused backup name")
 }
 
 pub fn config_dir() -> PathBuf {
@@ -84,9 +466,45 @@ pub fn config_dir() -> PathBuf {
 }
 
 pub fn history_dir() -> PathBuf {
-    config_dir().join("history")
+    profile_dir().join("history")
+}
+
+pub fn exports_dir() -> PathBuf {
+    profile_dir().join("exports")
 }
 
 pub fn history_db_path() -> PathBuf {
     history_dir().join("encounters.sled")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_config_is_moved_to_a_fresh_backup() {
+        let dir = std::env::temp_dir().join(format!("iinact-tui-config-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("iinact-tui.config");
+
+        fs::write(&path, "{ not json").unwrap();
+        let err = load_from(&path).unwrap_err().to_string();
+        assert!(err.contains("using defaults"), "{err}");
+        assert!(!path.exists());
+
+        fs::write(&path, "{ still not json").unwrap();
+        assert!(load_from(&path).is_err());
+
+        let mut backups: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        backups.sort();
+        assert_eq!(backups, ["{ not json", "{ still not json"]);
+        assert!(!SAVE_BLOCKED.load(Ordering::Relaxed));
+
+        assert!(load_from(&path).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
@@ -13,11 +13,25 @@ use tokio::task;
 
 use crate::config;
 use crate::model::{CombatantRow, EncounterSummary};
+use crate::parse::{mark_self, to_f64_any, EncounterBoundary, SELF_ALIAS};
+use crate::util::encode_url_component;
 
 const ENCOUNTER_NAMESPACE: &str = "enc";
 const KEY_SEPARATOR: u8 = 0x1F;
 const SCHEMA_VERSION: u32 = 2;
 const META_SCHEMA_VERSION_KEY: &[u8] = b"schema/version";
+const CHECKPOINT_KEY: &[u8] = b"active";
+/// Checkpointed frames are stored one per key under this prefix, by big-endian index.
+const CHECKPOINT_FRAME_PREFIX: &[u8] = b"frame/";
+/// How often the encounter in progress is checkpointed for crash recovery.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+/// A resumed encounter continues only if the next update arrives within this window;
+/// otherwise it is stored as finished first.
+const CHECKPOINT_RESUME_WINDOW_MS: u64 = 5 * 60 * 1000;
+/// Checkpoints older than this are stored as finished at startup without asking to resume.
+const CHECKPOINT_MAX_AGE_MS: u64 = 60 * 60 * 1000;
+/// Frames kept per encounter before they are thinned out; generous enough for most pulls.
+pub const DEFAULT_MAX_FRAMES: usize = 600;
 
 /// Snapshot prepared for persistence; keeps the raw payload around for future use.
 #[derive(Debug, Clone)]
@@ -103,13 +117,10 @@ impl EncounterRecord {
             last_raw,
             saw_active,
             frames,
+            snapshots,
+            ..
         } = active;
-        let snapshots = frames.len() as u32;
-        let raw_last = if let Some(frame) = frames.last() {
-            Some(frame.raw.clone())
-        } else {
-            Some(last_raw)
-        };
+        let raw_last = Some(last_raw);
 
         Self {
             version: SCHEMA_VERSION,
@@ -124,6 +135,58 @@ impl EncounterRecord {
             frames,
         }
     }
+
+    /// Real time between the first and last update we received, independent of ACT's timer.
+    pub fn wall_duration_secs(&self) -> u64 {
+        self.last_seen_ms.saturating_sub(self.first_seen_ms) / 1000
+    }
+
+    /// ENCDPS sampled once per elapsed second of the encounter, as `(elapsed_seconds, encdps)`.
+    /// When several frames land in the same second, the latest one wins.
+    pub fn dps_timeline(&self) -> Vec<(u64, f64)> {
+        let Some(first) = self.frames.first() else {
+            return Vec::new();
+        };
+        let start_ms = first.received_ms;
+        let mut points: Vec<(u64, f64)> = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let elapsed = frame.received_ms.saturating_sub(start_ms) / 1000;
+            let encdps = to_f64_any(&frame.encounter.encdps);
+            match points.last_mut() {
+                Some(last) if last.0 == elapsed => last.1 = encdps,
+                _ => points.push((elapsed, encdps)),
+            }
+        }
+        points
+    }
+
+    /// Seconds between the first and last stored frame in which each combatant's damage or
+    /// healing grew; a stand-in for ACT's per-combatant duration on older records.
+    pub fn frame_active_secs(&self) -> HashMap<String, u64> {
+        let mut spans: HashMap<&str, (u64, u64, f64)> = HashMap::new();
+        for frame in &self.frames {
+            for row in &frame.rows {
+                let total = row.damage + row.healed;
+                match spans.get_mut(row.name.as_str()) {
+                    Some((_, last_ms, seen)) if total > *seen => {
+                        *last_ms = frame.received_ms;
+                        *seen = total;
+                    }
+                    Some(_) => {}
+                    None if total > 0.0 => {
+                        spans.insert(&row.name, (frame.received_ms, frame.received_ms, total));
+                    }
+                    None => {}
+                }
+            }
+        }
+        spans
+            .into_iter()
+            .map(|(name, (first_ms, last_ms, _))| {
+                (name.to_string(), last_ms.saturating_sub(first_ms) / 1000)
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -131,7 +194,10 @@ pub struct EncounterFrame {
     pub received_ms: u64,
     pub encounter: EncounterSummary,
     pub rows: Vec<CombatantRow>,
-    pub raw: Value,
+    /// Only kept when raw frame storage is enabled; `EncounterRecord::raw_last` always has
+    /// the final payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<Value>,
 }
 
 impl EncounterFrame {
@@ -139,7 +205,7 @@ impl EncounterFrame {
         received_ms: u64,
         encounter: EncounterSummary,
         rows: Vec<CombatantRow>,
-        raw: Value,
+        raw: Option<Value>,
     ) -> Self {
         Self {
             received_ms,
@@ -160,9 +226,30 @@ pub struct HistoryEncounterItem {
     pub last_seen_ms: u64,
     pub timestamp_label: String,
     #[serde(default)]
+    pub pinned: bool,
+    /// Short user annotation; `#word` tags in it are picked out by `note_tags`.
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub zone: String,
+    /// The local player's ENCDPS in this encounter, when a "YOU" row was recorded.
+    #[serde(default)]
+    pub self_encdps: Option<f64>,
+    /// Highest `self_encdps` among the loaded pulls sharing this `base_title`.
+    #[serde(default)]
+    pub personal_best: bool,
+    #[serde(default)]
     pub record: Option<EncounterRecord>,
 }
 
+/// A zone seen in history: how many stored encounters took place there, and on which dates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneSummary {
+    pub zone: String,
+    pub encounter_count: usize,
+    pub date_ids: Vec<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HistoryDay {
     pub iso_date: String,
@@ -174,6 +261,35 @@ pub struct HistoryDay {
     pub encounter_ids: Vec<Vec<u8>>,
     #[serde(default)]
     pub encounters_loaded: bool,
+    #[serde(default)]
+    pub aggregate: Option<DayAggregate>,
+}
+
+/// Party-wide totals for every encounter stored under one date.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DayAggregate {
+    pub encounter_count: usize,
+    pub total_combat_secs: u64,
+    pub top_encdps: f64,
+    pub top_encdps_title: String,
+    /// Sorted by appearances, most frequent first.
+    pub players: Vec<PlayerAppearance>,
+}
+
+/// The local player's numbers on one job, across every stored encounter.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JobStat {
+    pub job: String,
+    pub encounters: u32,
+    pub average_encdps: f64,
+    pub best_encdps: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerAppearance {
+    pub name: String,
+    pub job: String,
+    pub appearances: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -191,6 +307,12 @@ pub struct EncounterSummaryRecord {
     pub zone: String,
     pub snapshots: u32,
     pub frames: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub self_encdps: Option<f64>,
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -206,8 +328,13 @@ pub struct HistoryStore {
     encounter_summaries: sled::Tree,
     date_index: sled::Tree,
     meta: sled::Tree,
+    checkpoint: sled::Tree,
     db: sled::Db,
     root: PathBuf,
+    // Serializes multi-tree writes so recorder appends and deletes never interleave.
+    write_lock: StdMutex<()>,
+    // Last `job_stats` result and the character name it was computed for; cleared on writes.
+    job_stats_cache: StdMutex<Option<(String, Vec<JobStat>, usize)>>,
 }
 
 impl HistoryStore {
@@ -215,10 +342,38 @@ impl HistoryStore {
     pub const ENCOUNTER_SUMMARIES_TREE: &'static str = "enc_summaries";
     pub const DATES_TREE: &'static str = "dates";
     pub const META_TREE: &'static str = "meta";
+    pub const CHECKPOINT_TREE: &'static str = "checkpoint";
 
     pub fn open(path: &Path) -> Result<Self> {
-        let db = sled::open(path)
-            .with_context(|| format!("Failed to open history database at {}", path.display()))?;
+        let db = match sled::open(path) {
+            Ok(db) => db,
+            Err(err) if is_lock_error(&err) => {
+                return Err(err).with_context(|| {
+                    format!(
+                        "History database at {} is in use by another iinact-tui instance",
+                        path.display()
+                    )
+                });
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("Failed to open history database at {}", path.display())
+                });
+            }
+        };
+        Self::from_db(db, path)
+    }
+
+    /// Throwaway store for when the on-disk history can't be opened; nothing survives exit.
+    pub fn open_in_memory() -> Result<Self> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .context("Failed to open in-memory history database")?;
+        Self::from_db(db, Path::new(":memory:"))
+    }
+
+    fn from_db(db: sled::Db, path: &Path) -> Result<Self> {
         let encounters = db
             .open_tree(Self::ENCOUNTERS_TREE)
             .context("Unable to open encounters history tree")?;
@@ -231,13 +386,19 @@ impl HistoryStore {
         let meta = db
             .open_tree(Self::META_TREE)
             .context("Unable to open history metadata tree")?;
+        let checkpoint = db
+            .open_tree(Self::CHECKPOINT_TREE)
+            .context("Unable to open history checkpoint tree")?;
         let store = Self {
             encounters,
             encounter_summaries,
             date_index,
             meta,
+            checkpoint,
             db,
             root: path.to_path_buf(),
+            write_lock: StdMutex::new(()),
+            job_stats_cache: StdMutex::new(None),
         };
         store.init_schema()?;
         Ok(store)
@@ -254,6 +415,7 @@ impl HistoryStore {
     }
 
     pub fn append(&self, record: &EncounterRecord) -> Result<HistoryKey> {
+        let _guard = self.lock_writes();
         let timestamp = record.last_seen_ms;
         let discriminator = self
             .db
@@ -275,17 +437,299 @@ impl HistoryStore {
 
         self.update_date_summary(&summary)
             .context("Failed to update date summary")?;
+        self.invalidate_job_stats();
         Ok(key)
     }
 
+    /// Updates the pin flag on an encounter's summary; the full record is left untouched.
+    pub fn set_pinned(&self, key: &[u8], pinned: bool) -> Result<()> {
+        self.update_summary(key, |summary| summary.pinned = pinned)
+    }
+
+    /// Stores `note` on the encounter's summary; `None` removes it.
+    pub fn set_note(&self, key: &[u8], note: Option<String>) -> Result<()> {
+        self.update_summary(key, |summary| summary.note = note)
+    }
+
+    /// Reads, changes and writes back one encounter summary; the record itself is untouched.
+    fn update_summary(
+        &self,
+        key: &[u8],
+        change: impl FnOnce(&mut EncounterSummaryRecord),
+    ) -> Result<()> {
+        let _guard = self.lock_writes();
+        let Some(bytes) = self
+            .encounter_summaries
+            .get(key)
+            .context("Failed to read encounter summary")?
+        else {
+            anyhow::bail!("Encounter summary not found");
+        };
+        let mut summary: EncounterSummaryRecord = serde_cbor::from_slice(bytes.as_ref())
+            .context("Failed to deserialize encounter summary")?;
+        change(&mut summary);
+        let bytes =
+            serde_cbor::to_vec(&summary).context("Failed to serialize encounter summary")?;
+        self.encounter_summaries
+            .insert(key, bytes)
+            .context("Failed to persist encounter summary")?;
+        Ok(())
+    }
+
+    /// Reads a record written by `export_record_json` and stores it like a freshly recorded
+    /// encounter, so it gets a new key plus its summary and date index entries.
+    pub fn import_record_json(&self, path: &Path) -> Result<HistoryKey> {
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read encounter JSON at {}", path.display()))?;
+        let record: EncounterRecord = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse encounter JSON at {}", path.display()))?;
+        let record = upgrade_record(record)?;
+        self.append(&record)
+    }
+
+    /// Updates the crash-recovery copy of the encounter in progress: `header` is the record
+    /// without its frames, and `frames` are the ones from index `first_frame` on, the earlier
+    /// ones being stored already. A `first_frame` of 0 replaces every stored frame. Sled flushes
+    /// on its own shortly after.
+    pub fn save_checkpoint(
+        &self,
+        header: &EncounterRecord,
+        frames: &[EncounterFrame],
+        first_frame: usize,
+    ) -> Result<()> {
+        let mut batch = sled::Batch::default();
+        if first_frame == 0 {
+            for key in self.checkpoint.scan_prefix(CHECKPOINT_FRAME_PREFIX).keys() {
+                batch.remove(key.context("Failed to read checkpoint frames")?);
+            }
+        }
+        for (idx, frame) in frames.iter().enumerate() {
+            let bytes = serde_cbor::to_vec(frame).context("Failed to serialize checkpoint")?;
+            batch.insert(checkpoint_frame_key(first_frame + idx), bytes);
+        }
+        let bytes = serde_cbor::to_vec(header).context("Failed to serialize checkpoint")?;
+        batch.insert(CHECKPOINT_KEY, bytes);
+        self.checkpoint
+            .apply_batch(batch)
+            .context("Failed to persist checkpoint")?;
+        Ok(())
+    }
+
+    /// The encounter that was in progress when the last session ended without flushing, if any.
+    pub fn load_checkpoint(&self) -> Result<Option<EncounterRecord>> {
+        let Some(bytes) = self
+            .checkpoint
+            .get(CHECKPOINT_KEY)
+            .context("Failed to read checkpoint")?
+        else {
+            return Ok(None);
+        };
+        let mut record: EncounterRecord =
+            serde_cbor::from_slice(bytes.as_ref()).context("Failed to deserialize checkpoint")?;
+        for entry in self
+            .checkpoint
+            .scan_prefix(CHECKPOINT_FRAME_PREFIX)
+            .values()
+        {
+            let bytes = entry.context("Failed to read checkpoint frames")?;
+            record.frames.push(
+                serde_cbor::from_slice(bytes.as_ref())
+                    .context("Failed to deserialize checkpoint")?,
+            );
+        }
+        Ok(Some(record))
+    }
+
+    pub fn clear_checkpoint(&self) -> Result<()> {
+        self.checkpoint
+            .clear()
+            .context("Failed to clear checkpoint")?;
+        Ok(())
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        self.db
+            .flush()
+            .context("Failed to flush history database")?;
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn remove(&self, key: &HistoryKey) -> Result<()> {
+        self.delete_encounter(&key.as_bytes())
+    }
+
+    /// Number of stored encounters, counted from their summaries without decoding them.
+    pub fn encounter_count(&self) -> usize {
+        self.encounter_summaries.len()
+    }
+
+    /// Removes an encounter record, its summary, and its entry in the date index.
+    /// Dates left without encounters are dropped from the index entirely.
+    pub fn delete_encounter(&self, key: &[u8]) -> Result<()> {
+        let _guard = self.lock_writes();
+        let date_id = match self
+            .encounter_summaries
+            .get(key)
+            .context("Failed to read encounter summary")?
+        {
+            Some(bytes) => {
+                let summary: EncounterSummaryRecord = serde_cbor::from_slice(bytes.as_ref())
+                    .context("Failed to deserialize encounter summary")?;
+                Some(summary.date_id)
+            }
+            None => decode_key(key)
+                .and_then(|decoded| millis_to_local(decoded.timestamp_ms))
+                .map(|dt| dt.date_naive().to_string()),
+        };
+
         self.encounters
-            .remove(key.as_bytes())
+            .remove(key)
             .context("Failed to delete encounter record")?;
+        self.encounter_summaries
+            .remove(key)
+            .context("Failed to delete encounter summary")?;
+        self.invalidate_job_stats();
+
+        if let Some(date_id) = date_id {
+            self.prune_date_summary(&date_id, key)
+                .context("Failed to update date summary")?;
+        }
+        Ok(())
+    }
+
+    /// Wipes every stored encounter, its summary and the date index; the schema version in
+    /// `meta` stays. With `keep_pinned`, pinned encounters (and their dates) survive.
+    /// Returns how many records were removed.
+    pub fn clear_all(&self, keep_pinned: bool) -> Result<usize> {
+        if keep_pinned {
+            let mut doomed = Vec::new();
+            for entry in self.encounter_summaries.iter() {
+                let (key, _) = entry.context("Failed to iterate encounter summaries")?;
+                if !self.is_pinned(&key)? {
+                    doomed.push(key);
+                }
+            }
+            for key in &doomed {
+                self.delete_encounter(key)?;
+            }
+            return Ok(doomed.len());
+        }
+
+        let _guard = self.lock_writes();
+        let removed = self.encounters.len();
+        self.encounters
+            .clear()
+            .context("Failed to clear encounter records")?;
+        self.encounter_summaries
+            .clear()
+            .context("Failed to clear encounter summaries")?;
+        self.date_index
+            .clear()
+            .context("Failed to clear date index")?;
+        self.invalidate_job_stats();
+        Ok(removed)
+    }
+
+    /// Deletes the encounters of every date whose latest encounter is older than `cutoff_ms`.
+    /// Pinned encounters are kept (and so is their date), and so are encounters whose summary
+    /// can't be read. Returns how many records were removed and how many unreadable date or
+    /// summary entries were skipped.
+    pub fn prune_older_than(&self, cutoff_ms: u64) -> Result<(usize, usize)> {
+        let mut stale_days = Vec::new();
+        let mut skipped = 0;
+        for entry in self.date_index.iter() {
+            let (key, bytes) = entry.context("Failed to iterate date index")?;
+            let day: DateSummaryRecord = match serde_cbor::from_slice(&bytes) {
+                Ok(day) => day,
+                Err(err) => {
+                    tracing::warn!(
+                        error = ?err,
+                        date = %String::from_utf8_lossy(&key),
+                        "skipping corrupt date summary"
+                    );
+                    skipped += 1;
+                    continue;
+                }
+            };
+            if day.last_seen_ms < cutoff_ms {
+                stale_days.push(day);
+            }
+        }
+
+        let mut pruned = 0;
+        for day in stale_days {
+            for key in &day.encounter_ids {
+                match self.is_pinned(key) {
+                    Ok(true) => continue,
+                    Ok(false) => {}
+                    Err(err) => {
+                        tracing::warn!(error = ?err, "keeping encounter with unreadable summary");
+                        skipped += 1;
+                        continue;
+                    }
+                }
+                self.delete_encounter(key)?;
+                pruned += 1;
+            }
+        }
+        Ok((pruned, skipped))
+    }
+
+    fn is_pinned(&self, key: &[u8]) -> Result<bool> {
+        let Some(bytes) = self
+            .encounter_summaries
+            .get(key)
+            .context("Failed to read encounter summary")?
+        else {
+            return Ok(false);
+        };
+        let summary: EncounterSummaryRecord = serde_cbor::from_slice(bytes.as_ref())
+            .context("Failed to deserialize encounter summary")?;
+        Ok(summary.pinned)
+    }
+
+    fn prune_date_summary(&self, date_id: &str, encounter_key: &[u8]) -> Result<()> {
+        let key = date_id.as_bytes();
+        let Some(bytes) = self
+            .date_index
+            .get(key)
+            .context("Failed to read date summary")?
+        else {
+            return Ok(());
+        };
+        let mut record: DateSummaryRecord =
+            serde_cbor::from_slice(&bytes).context("Failed to deserialize date summary")?;
+        record
+            .encounter_ids
+            .retain(|existing_key| existing_key != encounter_key);
+        if record.encounter_ids.is_empty() {
+            self.date_index
+                .remove(key)
+                .context("Failed to delete empty date summary")?;
+        } else {
+            let bytes =
+                serde_cbor::to_vec(&record).context("Failed to serialize updated date summary")?;
+            self.date_index
+                .insert(key, bytes)
+                .context("Failed to persist date summary")?;
+        }
         Ok(())
     }
 
+    fn invalidate_job_stats(&self) {
+        *self
+            .job_stats_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+
+    fn lock_writes(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     #[allow(dead_code)]
     pub fn tree(&self, name: &str) -> Result<sled::Tree> {
         self.db
@@ -328,6 +772,9 @@ impl HistoryStore {
             zone: record.encounter.zone.clone(),
             snapshots: record.snapshots,
             frames: record.frames.len() as u32,
+            pinned: false,
+            self_encdps: self_row(&record.rows, "").map(|row| row.encdps),
+            note: None,
         }
     }
 
@@ -368,12 +815,24 @@ impl HistoryStore {
         Ok(())
     }
 
-    pub fn load_dates(&self) -> Result<Vec<HistoryDay>> {
+    /// Every indexed date, newest first, plus how many malformed index entries were skipped.
+    pub fn load_dates(&self) -> Result<(Vec<HistoryDay>, usize)> {
         let mut days = Vec::new();
+        let mut skipped = 0;
         for entry in self.date_index.iter() {
             let (key_bytes, value_bytes) = entry.context("Failed to iterate history date index")?;
-            let record: DateSummaryRecord = serde_cbor::from_slice(value_bytes.as_ref())
-                .context("Failed to deserialize date summary")?;
+            let record: DateSummaryRecord = match serde_cbor::from_slice(value_bytes.as_ref()) {
+                Ok(record) => record,
+                Err(err) => {
+                    tracing::warn!(
+                        error = ?err,
+                        date = %String::from_utf8_lossy(&key_bytes),
+                        "skipping corrupt date summary"
+                    );
+                    skipped += 1;
+                    continue;
+                }
+            };
             let iso_date = String::from_utf8(key_bytes.to_vec()).unwrap_or(record.date_id.clone());
             let label = format_date_label(&iso_date, record.encounter_ids.len());
             days.push(HistoryDay {
@@ -383,41 +842,111 @@ impl HistoryStore {
                 encounters: Vec::new(),
                 encounter_ids: record.encounter_ids,
                 encounters_loaded: false,
+                aggregate: None,
             });
         }
         days.sort_by(|a, b| b.iso_date.cmp(&a.iso_date));
-        Ok(days)
+        Ok((days, skipped))
     }
 
-    pub fn load_encounter_summaries(&self, date_id: &str) -> Result<Vec<HistoryEncounterItem>> {
+    /// The encounters of `date_id`, plus how many malformed summaries were skipped. A corrupt
+    /// date entry fails the whole load, as there is no list of encounters to fall back on.
+    pub fn load_encounter_summaries(
+        &self,
+        date_id: &str,
+    ) -> Result<(Vec<HistoryEncounterItem>, usize)> {
         let key = date_id.as_bytes();
         let Some(bytes) = self
             .date_index
             .get(key)
             .context("Failed to read date summary for encounters")?
         else {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), 0));
         };
 
         let date_summary: DateSummaryRecord =
             serde_cbor::from_slice(bytes.as_ref()).context("Failed to deserialize date summary")?;
 
         let mut summaries = Vec::new();
+        let mut skipped = 0;
         for encounter_id in &date_summary.encounter_ids {
             if let Some(bytes) = self
                 .encounter_summaries
                 .get(encounter_id)
                 .context("Failed to read encounter summary")?
             {
-                let summary: EncounterSummaryRecord = serde_cbor::from_slice(bytes.as_ref())
-                    .context("Failed to deserialize encounter summary")?;
-                summaries.push(summary);
+                match serde_cbor::from_slice::<EncounterSummaryRecord>(bytes.as_ref()) {
+                    Ok(summary) => summaries.push(summary),
+                    Err(err) => {
+                        tracing::warn!(error = ?err, date = date_id, "skipping corrupt encounter summary");
+                        skipped += 1;
+                    }
+                }
             }
         }
 
-        summaries.sort_by(|a, b| b.last_seen_ms.cmp(&a.last_seen_ms));
+        // Pinned encounters first, newest first within each group.
+        summaries.sort_by_key(|summary| {
+            (
+                std::cmp::Reverse(summary.pinned),
+                std::cmp::Reverse(summary.last_seen_ms),
+            )
+        });
+
+        Ok((build_history_items_from_summaries(summaries), skipped))
+    }
+
+    /// The newest `limit` encounters across every date, newest first. Summary keys embed
+    /// `last_seen_ms` big-endian, so the summaries tree is read backwards.
+    pub fn load_recent(&self, limit: usize) -> Result<(Vec<HistoryEncounterItem>, usize)> {
+        let mut summaries = Vec::new();
+        let mut skipped = 0;
+        for entry in self.encounter_summaries.iter().rev() {
+            if summaries.len() >= limit {
+                break;
+            }
+            let (_, bytes) = entry.context("Failed to iterate encounter summaries")?;
+            match serde_cbor::from_slice::<EncounterSummaryRecord>(bytes.as_ref()) {
+                Ok(summary) => summaries.push(summary),
+                Err(err) => {
+                    tracing::warn!(error = ?err, "skipping corrupt encounter summary");
+                    skipped += 1;
+                }
+            }
+        }
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.last_seen_ms));
+        let items = summaries.into_iter().map(plain_history_item).collect();
+        Ok((items, skipped))
+    }
 
-        Ok(build_history_items_from_summaries(summaries))
+    /// Encounters across every date whose note matches `query` (see `note_matches`), newest
+    /// first, plus how many corrupt summaries were skipped.
+    pub fn search_notes(&self, query: &str) -> Result<(Vec<HistoryEncounterItem>, usize)> {
+        let mut matches = Vec::new();
+        let mut skipped = 0;
+        for entry in self.encounter_summaries.iter() {
+            let (_, bytes) = entry.context("Failed to iterate encounter summaries")?;
+            match serde_cbor::from_slice::<EncounterSummaryRecord>(bytes.as_ref()) {
+                Ok(summary) => {
+                    if summary
+                        .note
+                        .as_deref()
+                        .is_some_and(|note| note_matches(note, query))
+                    {
+                        matches.push(summary);
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(error = ?err, "skipping corrupt encounter summary");
+                    skipped += 1;
+                }
+            }
+        }
+        matches.sort_by_key(|summary| std::cmp::Reverse(summary.last_seen_ms));
+        Ok((
+            matches.into_iter().map(plain_history_item).collect(),
+            skipped,
+        ))
     }
 
     pub fn load_encounter_record(&self, key: &[u8]) -> Result<EncounterRecord> {
@@ -431,6 +960,175 @@ impl HistoryStore {
         serde_cbor::from_slice(bytes.as_ref()).context("Failed to deserialize encounter record")
     }
 
+    /// Distinct zones across all stored encounters, most visited first, and how many corrupt
+    /// summaries were skipped.
+    pub fn load_zones(&self) -> Result<(Vec<ZoneSummary>, usize)> {
+        let mut zones: HashMap<String, ZoneSummary> = HashMap::new();
+        let mut skipped = 0;
+        for entry in self.encounter_summaries.iter() {
+            let (_, bytes) = entry.context("Failed to iterate encounter summaries")?;
+            let summary: EncounterSummaryRecord = match serde_cbor::from_slice(bytes.as_ref()) {
+                Ok(summary) => summary,
+                Err(err) => {
+                    tracing::warn!(error = ?err, "skipping corrupt encounter summary");
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let zone = zones
+                .entry(summary.zone.clone())
+                .or_insert_with(|| ZoneSummary {
+                    zone: summary.zone.clone(),
+                    ..ZoneSummary::default()
+                });
+            zone.encounter_count += 1;
+            if !zone.date_ids.contains(&summary.date_id) {
+                zone.date_ids.push(summary.date_id);
+            }
+        }
+        let mut zones: Vec<ZoneSummary> = zones.into_values().collect();
+        zones.sort_by(|a, b| {
+            b.encounter_count
+                .cmp(&a.encounter_count)
+                .then_with(|| a.zone.cmp(&b.zone))
+        });
+        Ok((zones, skipped))
+    }
+
+    /// Per-job encounter count, average and best ENCDPS of the local player (the "YOU" row, or
+    /// the one named `character_name`) over the whole database, most played job first. This
+    /// reads every record, so the result is cached until the next append or delete.
+    pub fn job_stats(&self, character_name: &str) -> Result<(Vec<JobStat>, usize)> {
+        let character_name = character_name.trim();
+        if let Some((name, stats, skipped)) = self
+            .job_stats_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+        {
+            if name == character_name {
+                return Ok((stats.clone(), *skipped));
+            }
+        }
+
+        let mut totals: HashMap<String, (JobStat, f64)> = HashMap::new();
+        let mut skipped = 0;
+        for entry in self.encounters.iter() {
+            let (_, bytes) = entry.context("Failed to iterate encounter records")?;
+            let record: EncounterRecord = match serde_cbor::from_slice(bytes.as_ref()) {
+                Ok(record) => record,
+                Err(err) => {
+                    tracing::warn!(error = ?err, "skipping corrupt encounter record");
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let Some(row) = self_row(&record.rows, character_name) else {
+                continue;
+            };
+            if row.job.is_empty() {
+                continue;
+            }
+            let (stat, sum) = totals.entry(row.job.to_uppercase()).or_insert_with(|| {
+                (
+                    JobStat {
+                        job: row.job.to_uppercase(),
+                        ..JobStat::default()
+                    },
+                    0.0,
+                )
+            });
+            stat.encounters += 1;
+            stat.best_encdps = stat.best_encdps.max(row.encdps);
+            *sum += row.encdps;
+        }
+        let mut stats: Vec<JobStat> = totals
+            .into_values()
+            .map(|(mut stat, sum)| {
+                stat.average_encdps = sum / f64::from(stat.encounters);
+                stat
+            })
+            .collect();
+        stats.sort_by(|a, b| {
+            b.encounters
+                .cmp(&a.encounters)
+                .then_with(|| a.job.cmp(&b.job))
+        });
+
+        *self
+            .job_stats_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            Some((character_name.to_string(), stats.clone(), skipped));
+        Ok((stats, skipped))
+    }
+
+    /// Loads every record of `date_id` and folds them into a [`DayAggregate`]. Combat time uses
+    /// ACT's duration and falls back to wall-clock time when the timer is missing.
+    pub fn aggregate_day(&self, date_id: &str) -> Result<DayAggregate> {
+        let mut aggregate = DayAggregate::default();
+        let mut players: HashMap<String, PlayerAppearance> = HashMap::new();
+        for item in self.load_encounter_summaries(date_id)?.0 {
+            let record = self.load_encounter_record(&item.key)?;
+            aggregate.encounter_count += 1;
+            aggregate.total_combat_secs += parse_duration_secs(&record.encounter.duration)
+                .unwrap_or_else(|| record.wall_duration_secs());
+            let encdps = to_f64_any(&record.encounter.encdps);
+            if encdps > aggregate.top_encdps {
+                aggregate.top_encdps = encdps;
+                aggregate.top_encdps_title = item.display_title.clone();
+            }
+            for row in &record.rows {
+                let entry = players
+                    .entry(row.name.clone())
+                    .or_insert_with(|| PlayerAppearance {
+                        name: row.name.clone(),
+                        job: row.job.clone(),
+                        appearances: 0,
+                    });
+                entry.appearances += 1;
+            }
+        }
+        let mut players: Vec<PlayerAppearance> = players.into_values().collect();
+        players.sort_by(|a, b| {
+            b.appearances
+                .cmp(&a.appearances)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        aggregate.players = players;
+        Ok(aggregate)
+    }
+
+    /// Writes the full stored record as pretty-printed JSON; `include_frames: false` drops the
+    /// per-update frames, which make up most of the file for long pulls.
+    pub fn export_record_json(&self, key: &[u8], path: &Path, include_frames: bool) -> Result<()> {
+        let mut record = self.load_encounter_record(key)?;
+        if !include_frames {
+            record.frames.clear();
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Unable to create exports directory {}", parent.display())
+            })?;
+        }
+        let file = fs::File::create(path)
+            .with_context(|| format!("Failed to create JSON export at {}", path.display()))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &record)
+            .with_context(|| format!("Failed to write JSON export to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Most recently stored encounter, if any; keys sort by timestamp so this is the last entry.
+    pub fn load_latest_encounter(&self) -> Result<Option<EncounterRecord>> {
+        let Some(entry) = self.encounters.iter().next_back() else {
+            return Ok(None);
+        };
+        let (_, bytes) = entry.context("Failed to read latest encounter record")?;
+        let record = serde_cbor::from_slice(bytes.as_ref())
+            .context("Failed to deserialize encounter record")?;
+        Ok(Some(record))
+    }
+
     fn init_schema(&self) -> Result<()> {
         match self
             .meta
@@ -501,6 +1199,64 @@ impl RecorderHandle {
         let _ = self.inner.tx.send(RecorderMessage::Flush);
     }
 
+    /// Saves the encounter in progress now and ignores further updates to it, for when ACT
+    /// keeps reporting a finished fight as active. Recording resumes at the next rollover.
+    pub fn end_encounter(&self) {
+        let _ = self.inner.tx.send(RecorderMessage::EndEncounter);
+    }
+
+    /// Looks for an encounter left unsaved by a crashed session. One older than an hour is
+    /// stored as finished right away; a more recent one is held until [`Self::resolve_recovery`]
+    /// and its title returned so the user can be asked.
+    pub async fn recover_checkpoint(&self) -> Option<String> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.inner.tx.send(RecorderMessage::Recover(tx));
+        rx.await.ok().flatten()
+    }
+
+    /// Resumes the held encounter, or stores it as finished when `resume` is false (or when a
+    /// new pull has started meanwhile).
+    pub fn resolve_recovery(&self, resume: bool) {
+        let _ = self.inner.tx.send(RecorderMessage::ResolveRecovery(resume));
+    }
+
+    /// Forwards an encounter edge seen in the log so rollover doesn't rely on numbers alone.
+    pub fn mark_boundary(&self, boundary: EncounterBoundary) {
+        let _ = self
+            .inner
+            .tx
+            .send(RecorderMessage::Boundary(boundary, now_ms()));
+    }
+
+    pub fn set_filter(&self, filter: RecordingFilter) {
+        let _ = self.inner.tx.send(RecorderMessage::SetFilter(filter));
+    }
+
+    /// Rows named `name` are stored as the local player, so summaries and job stats find them
+    /// when ACT reports the player by name.
+    pub fn set_character_name(&self, name: &str) {
+        let _ = self
+            .inner
+            .tx
+            .send(RecorderMessage::SetCharacterName(name.to_string()));
+    }
+
+    /// Caps the frames kept per encounter; 0 keeps every frame.
+    pub fn set_max_frames(&self, max_frames: usize) {
+        let _ = self
+            .inner
+            .tx
+            .send(RecorderMessage::SetMaxFrames(max_frames));
+    }
+
+    /// Whether new encounters keep the raw payload of every frame (off by default).
+    pub fn set_store_raw_frames(&self, store_raw: bool) {
+        let _ = self
+            .inner
+            .tx
+            .send(RecorderMessage::SetStoreRawFrames(store_raw));
+    }
+
     pub async fn shutdown(&self) {
         let _ = self.inner.tx.send(RecorderMessage::Shutdown);
         if let Some(rx) = self.take_shutdown_receiver().await {
@@ -522,9 +1278,33 @@ impl Clone for RecorderHandle {
     }
 }
 
+/// Thresholds below which a finished encounter is discarded instead of stored; 0 disables each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordingFilter {
+    pub min_duration_secs: u64,
+    pub min_damage: u64,
+}
+
+impl RecordingFilter {
+    fn allows(&self, record: &EncounterRecord) -> bool {
+        let duration = parse_duration_secs(&record.encounter.duration)
+            .unwrap_or_else(|| record.wall_duration_secs());
+        let damage = to_f64_any(&record.encounter.damage);
+        duration >= self.min_duration_secs && damage >= self.min_damage as f64
+    }
+}
+
 enum RecorderMessage {
     Snapshot(Box<EncounterSnapshot>),
+    SetFilter(RecordingFilter),
+    SetCharacterName(String),
+    SetMaxFrames(usize),
+    SetStoreRawFrames(bool),
     Flush,
+    EndEncounter,
+    Boundary(EncounterBoundary, u64),
+    Recover(oneshot::Sender<Option<String>>),
+    ResolveRecovery(bool),
     Shutdown,
 }
 
@@ -536,7 +1316,23 @@ pub fn spawn_recorder(store: Arc<HistoryStore>) -> RecorderHandle {
         loop {
             match rx.recv().await {
                 Some(RecorderMessage::Snapshot(snapshot)) => worker.on_snapshot(*snapshot).await,
+                Some(RecorderMessage::SetFilter(filter)) => worker.filter = filter,
+                Some(RecorderMessage::SetCharacterName(name)) => worker.character_name = name,
+                Some(RecorderMessage::SetMaxFrames(max)) => worker.frame_policy.max_frames = max,
+                Some(RecorderMessage::SetStoreRawFrames(store_raw)) => {
+                    worker.frame_policy.store_raw = store_raw
+                }
                 Some(RecorderMessage::Flush) => worker.on_flush().await,
+                Some(RecorderMessage::EndEncounter) => worker.on_end_encounter().await,
+                Some(RecorderMessage::Boundary(boundary, at_ms)) => {
+                    worker.on_boundary(boundary, at_ms).await
+                }
+                Some(RecorderMessage::Recover(reply)) => {
+                    let _ = reply.send(worker.recover_checkpoint().await);
+                }
+                Some(RecorderMessage::ResolveRecovery(resume)) => {
+                    worker.resolve_recovery(resume).await
+                }
                 Some(RecorderMessage::Shutdown) => {
                     worker.on_flush().await;
                     break;
@@ -560,6 +1356,19 @@ pub fn spawn_recorder(store: Arc<HistoryStore>) -> RecorderHandle {
 struct RecorderWorker {
     store: Arc<HistoryStore>,
     current: Option<ActiveEncounter>,
+    /// Latest summary of a manually ended encounter; its updates are skipped until it rolls over.
+    ended: Option<EncounterSummary>,
+    filter: RecordingFilter,
+    /// See [`RecorderHandle::set_character_name`].
+    character_name: String,
+    frame_policy: FramePolicy,
+    /// When `current` was last checkpointed; `None` until the first checkpoint.
+    last_checkpoint: Option<std::time::Instant>,
+    /// `current` came from a checkpoint and has not seen a live update yet.
+    recovered: bool,
+    /// Checkpoint from a crashed session awaiting the resume prompt. It keeps the checkpoint
+    /// slot until then, so the encounter in progress is not checkpointed meanwhile.
+    pending_recovery: Option<EncounterRecord>,
 }
 
 impl RecorderWorker {
@@ -567,29 +1376,158 @@ impl RecorderWorker {
         Self {
             store,
             current: None,
+            ended: None,
+            filter: RecordingFilter::default(),
+            character_name: String::new(),
+            frame_policy: FramePolicy::default(),
+            last_checkpoint: None,
+            recovered: false,
+            pending_recovery: None,
         }
     }
 
-    async fn on_snapshot(&mut self, snapshot: EncounterSnapshot) {
-        if self.current.is_none() {
-            if !snapshot.encounter.is_active {
-                return;
+    /// Loads an encounter checkpointed by a session that ended without flushing (a crash or
+    /// kill). A recent one is held for [`Self::resolve_recovery`] and its title returned;
+    /// an older one is stored as finished.
+    async fn recover_checkpoint(&mut self) -> Option<String> {
+        let store = Arc::clone(&self.store);
+        let record = match task::spawn_blocking(move || store.load_checkpoint()).await {
+            Ok(Ok(record)) => record?,
+            Ok(Err(err)) => {
+                tracing::warn!(error = ?err, "failed to load encounter checkpoint");
+                return None;
             }
-            if !snapshot_has_activity(&snapshot) {
-                return;
+            Err(err) => {
+                tracing::warn!(error = ?err, "checkpoint load task failed");
+                return None;
             }
+        };
+        tracing::info!(
+            title = %record.encounter.title,
+            "found an unsaved encounter from the last session"
+        );
+        if now_ms().saturating_sub(record.last_seen_ms) > CHECKPOINT_MAX_AGE_MS {
+            self.store_recovered(record).await;
+            return None;
         }
+        let title = record.encounter.title.clone();
+        self.pending_recovery = Some(record);
+        Some(title)
+    }
 
-        if let Some(active) = self.current.as_ref() {
-            if should_rollover(active, &snapshot) {
-                self.flush_active().await;
-            }
+    /// Answers the resume prompt. A resumed encounter continues if ACT is still reporting the
+    /// same fight, and is otherwise stored as finished at the next rollover, flush or
+    /// shutdown. It can't be resumed once a new pull is being recorded.
+    async fn resolve_recovery(&mut self, resume: bool) {
+        let Some(record) = self.pending_recovery.take() else {
+            return;
+        };
+        if resume && self.current.is_none() {
+            self.current = Some(ActiveEncounter::from_record(record, self.frame_policy));
+            self.recovered = true;
+        } else {
+            self.store_recovered(record).await;
+        }
+        self.last_checkpoint = None;
+    }
+
+    /// Stores a recovered checkpoint as a finished encounter and drops the checkpoint.
+    async fn store_recovered(&mut self, record: EncounterRecord) {
+        let store = Arc::clone(&self.store);
+        let keep = (record.saw_active || !record.rows.is_empty()) && self.filter.allows(&record);
+        let result = task::spawn_blocking(move || -> Result<()> {
+            if keep {
+                store.append(&record)?;
+            }
+            store.clear_checkpoint()
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => tracing::warn!(error = ?err, "failed to store recovered encounter"),
+            Err(err) => tracing::warn!(error = ?err, "recovered encounter task failed"),
+        }
+    }
+
+    /// Writes the header and the frames added since the last checkpoint, at most every
+    /// [`CHECKPOINT_INTERVAL`].
+    async fn checkpoint_if_due(&mut self) {
+        if self.pending_recovery.is_some() {
+            return;
+        }
+        let due = self
+            .last_checkpoint
+            .map(|at| at.elapsed() >= CHECKPOINT_INTERVAL)
+            .unwrap_or(true);
+        let Some(active) = self.current.as_mut().filter(|_| due) else {
+            return;
+        };
+        self.last_checkpoint = Some(std::time::Instant::now());
+        let store = Arc::clone(&self.store);
+        let first_frame = active.checkpointed_frames;
+        let header = active.checkpoint_header();
+        let frames = active.frames[first_frame..].to_vec();
+        active.checkpointed_frames = active.frames.len();
+        let result =
+            task::spawn_blocking(move || store.save_checkpoint(&header, &frames, first_frame))
+                .await;
+        let saved = match result {
+            Ok(Ok(())) => true,
+            Ok(Err(err)) => {
+                tracing::warn!(error = ?err, "failed to checkpoint encounter");
+                false
+            }
+            Err(err) => {
+                tracing::warn!(error = ?err, "checkpoint task failed");
+                false
+            }
+        };
+        if !saved {
+            // Unknown what made it to disk; the next checkpoint rewrites every frame.
+            if let Some(active) = self.current.as_mut() {
+                active.checkpointed_frames = 0;
+            }
+        }
+    }
+
+    async fn on_snapshot(&mut self, mut snapshot: EncounterSnapshot) {
+        mark_self(&mut snapshot.rows, &self.character_name);
+        if std::mem::take(&mut self.recovered) {
+            let stale = self.current.as_ref().is_some_and(|active| {
+                snapshot.received_ms.saturating_sub(active.last_seen_ms)
+                    > CHECKPOINT_RESUME_WINDOW_MS
+            });
+            if stale {
+                self.flush_active().await;
+            }
+        }
+
+        if let Some(ended) = self.ended.take() {
+            if continues_ended(&ended, &snapshot.encounter) {
+                self.ended = Some(snapshot.encounter);
+                return;
+            }
+        }
+
+        if self.current.is_none() {
+            if !snapshot.encounter.is_active {
+                return;
+            }
+            if !snapshot_has_activity(&snapshot) {
+                return;
+            }
+        }
+
+        if let Some(active) = self.current.as_ref() {
+            if should_rollover(active, &snapshot) {
+                self.flush_active().await;
+            }
         }
 
         if let Some(active) = self.current.as_mut() {
             active.update(snapshot);
         } else {
-            self.current = Some(ActiveEncounter::from_snapshot(snapshot));
+            self.current = Some(ActiveEncounter::from_snapshot(snapshot, self.frame_policy));
         }
 
         if let Some(active) = self.current.as_ref() {
@@ -597,21 +1535,67 @@ impl RecorderWorker {
                 self.flush_active().await;
             }
         }
+        self.checkpoint_if_due().await;
     }
 
     async fn on_flush(&mut self) {
         self.flush_active().await;
     }
 
+    async fn on_end_encounter(&mut self) {
+        if let Some(active) = self.current.as_ref() {
+            self.ended = Some(active.latest_summary.clone());
+        }
+        self.flush_active().await;
+    }
+
+    /// A logged start closes whatever is in progress so the next update opens a fresh
+    /// encounter, even when ACT's numbers keep counting up. A logged end or zone change saves
+    /// the encounter like `end_encounter`, skipping ACT's trailing updates for it.
+    async fn on_boundary(&mut self, boundary: EncounterBoundary, at_ms: u64) {
+        match boundary {
+            EncounterBoundary::CombatStart => {
+                self.ended = None;
+                if self
+                    .current
+                    .as_ref()
+                    .is_some_and(|active| !started_near(active, at_ms))
+                {
+                    self.flush_active().await;
+                }
+            }
+            EncounterBoundary::CombatEnd | EncounterBoundary::ZoneChange => {
+                if self.current.is_some() {
+                    self.on_end_encounter().await;
+                }
+            }
+        }
+    }
+
     async fn flush_active(&mut self) {
         if let Some(active) = self.current.take() {
+            self.recovered = false;
             let store = Arc::clone(&self.store);
             let record = EncounterRecord::new(active);
-            if !record.saw_active && record.rows.is_empty() {
-                return;
-            }
-            match task::spawn_blocking(move || store.append(&record)).await {
-                Ok(Ok(_)) => {}
+            let keep =
+                (record.saw_active || !record.rows.is_empty()) && self.filter.allows(&record);
+            // A checkpoint still awaiting the resume prompt is not this encounter's to clear.
+            let clear = self.pending_recovery.is_none();
+            // The checkpoint goes in the same blocking task, after the append, so a crash in
+            // between leaves the checkpoint (a possible duplicate) rather than losing the pull.
+            let result = task::spawn_blocking(move || -> Result<()> {
+                if keep {
+                    store.append(&record)?;
+                }
+                if clear {
+                    store.clear_checkpoint()?;
+                }
+                Ok(())
+            })
+            .await;
+            self.last_checkpoint = None;
+            match result {
+                Ok(Ok(())) => {}
                 Ok(Err(err)) => {
                     eprintln!("Failed to persist encounter history: {err:#}");
                 }
@@ -632,10 +1616,37 @@ struct ActiveEncounter {
     last_raw: Value,
     saw_active: bool,
     frames: Vec<EncounterFrame>,
+    /// Every snapshot received, including the ones not kept in `frames`.
+    snapshots: u32,
+    /// Only every `frame_stride`-th snapshot is kept once the frame cap has been hit.
+    frame_stride: u32,
+    /// The newest frame is always stored; this marks it as off-stride so the next one replaces it.
+    tail_off_stride: bool,
+    /// Leading frames already in the checkpoint and unchanged since; thinning resets it.
+    checkpointed_frames: usize,
+    policy: FramePolicy,
+}
+
+/// How the recorder keeps per-update frames for the encounter in progress.
+#[derive(Debug, Clone, Copy)]
+struct FramePolicy {
+    /// 0 keeps every frame; otherwise frames are thinned whenever the cap is exceeded.
+    max_frames: usize,
+    /// Keep each frame's raw CombatData payload, not just the parsed summary and rows.
+    store_raw: bool,
+}
+
+impl Default for FramePolicy {
+    fn default() -> Self {
+        Self {
+            max_frames: DEFAULT_MAX_FRAMES,
+            store_raw: false,
+        }
+    }
 }
 
 impl ActiveEncounter {
-    fn from_snapshot(snapshot: EncounterSnapshot) -> Self {
+    fn from_snapshot(snapshot: EncounterSnapshot, policy: FramePolicy) -> Self {
         let EncounterSnapshot {
             encounter,
             rows,
@@ -643,7 +1654,8 @@ impl ActiveEncounter {
             received_ms,
         } = snapshot;
         let is_active = encounter.is_active;
-        let frame = EncounterFrame::new(received_ms, encounter.clone(), rows.clone(), raw.clone());
+        let frame_raw = policy.store_raw.then(|| raw.clone());
+        let frame = EncounterFrame::new(received_ms, encounter.clone(), rows.clone(), frame_raw);
         Self {
             first_seen_ms: received_ms,
             last_seen_ms: received_ms,
@@ -652,9 +1664,52 @@ impl ActiveEncounter {
             last_raw: raw,
             saw_active: is_active,
             frames: vec![frame],
+            snapshots: 1,
+            frame_stride: 1,
+            tail_off_stride: false,
+            checkpointed_frames: 0,
+            policy,
         }
     }
 
+    /// Rebuilds an encounter in progress from its checkpoint. The frame stride is not stored,
+    /// so thinning restarts from every frame.
+    fn from_record(record: EncounterRecord, policy: FramePolicy) -> Self {
+        let checkpointed_frames = record.frames.len();
+        Self {
+            first_seen_ms: record.first_seen_ms,
+            last_seen_ms: record.last_seen_ms,
+            latest_summary: record.encounter,
+            latest_rows: record.rows,
+            last_raw: record.raw_last.unwrap_or(Value::Null),
+            saw_active: record.saw_active,
+            frames: record.frames,
+            snapshots: record.snapshots,
+            frame_stride: 1,
+            tail_off_stride: false,
+            checkpointed_frames,
+            policy,
+        }
+    }
+
+    /// The encounter so far as a record without frames, for [`HistoryStore::save_checkpoint`].
+    fn checkpoint_header(&self) -> EncounterRecord {
+        EncounterRecord {
+            version: SCHEMA_VERSION,
+            stored_ms: now_ms(),
+            first_seen_ms: self.first_seen_ms,
+            last_seen_ms: self.last_seen_ms,
+            encounter: self.latest_summary.clone(),
+            rows: self.latest_rows.clone(),
+            raw_last: Some(self.last_raw.clone()),
+            snapshots: self.snapshots,
+            saw_active: self.saw_active,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Past `policy.max_frames`, the stored frames are thinned to every other one and the
+    /// stride for later frames doubles.
     fn update(&mut self, snapshot: EncounterSnapshot) {
         self.last_seen_ms = snapshot.received_ms;
         let EncounterSnapshot {
@@ -663,24 +1718,65 @@ impl ActiveEncounter {
             raw,
             received_ms,
         } = snapshot;
-        let frame = EncounterFrame::new(received_ms, encounter.clone(), rows.clone(), raw.clone());
+        let frame_raw = self.policy.store_raw.then(|| raw.clone());
+        let frame = EncounterFrame::new(received_ms, encounter.clone(), rows.clone(), frame_raw);
         self.latest_summary = encounter;
         self.latest_rows = rows;
         self.last_raw = raw;
+        if self.tail_off_stride {
+            self.frames.pop();
+            self.checkpointed_frames = self.checkpointed_frames.min(self.frames.len());
+        }
         self.frames.push(frame);
+        self.snapshots += 1;
+        let max_frames = self.policy.max_frames;
+        if max_frames > 0 && self.frames.len() > max_frames.max(2) {
+            thin_frames(&mut self.frames);
+            self.frame_stride = self.frame_stride.saturating_mul(2);
+            self.checkpointed_frames = 0;
+        }
+        self.tail_off_stride = !(self.snapshots - 1).is_multiple_of(self.frame_stride);
         self.saw_active |= self.latest_summary.is_active;
     }
 }
 
+/// Drops every other frame between the first and the last.
+fn thin_frames(frames: &mut Vec<EncounterFrame>) {
+    let last = frames.len().saturating_sub(1);
+    let mut idx = 0;
+    frames.retain(|_| {
+        let keep = idx == 0 || idx == last || idx % 2 == 0;
+        idx += 1;
+        keep
+    });
+}
+
+/// How close to a logged combat start an encounter may begin and still count as that pull;
+/// CombatData for a new pull can arrive just before the log line announcing it.
+const BOUNDARY_GRACE_MS: u64 = 3_000;
+
+/// Whether `active` itself began around `at_ms`, so a start logged then is its own start.
+fn started_near(active: &ActiveEncounter, at_ms: u64) -> bool {
+    active.first_seen_ms + BOUNDARY_GRACE_MS >= at_ms
+}
+
 fn should_rollover(active: &ActiveEncounter, incoming: &EncounterSnapshot) -> bool {
-    let previous = &active.latest_summary;
     let next = &incoming.encounter;
+    if next.is_active && !active.saw_active {
+        return true;
+    }
+    summary_restarted(&active.latest_summary, next)
+}
 
-    if next.is_active {
-        if !active.saw_active {
-            return true;
-        }
+/// Whether an update still belongs to a manually ended encounter: ACT still reports it active
+/// and its duration and damage kept counting up instead of resetting.
+fn continues_ended(ended: &EncounterSummary, next: &EncounterSummary) -> bool {
+    next.is_active && !summary_restarted(ended, next)
+}
 
+/// An active update whose duration or damage went backwards belongs to a new encounter.
+fn summary_restarted(previous: &EncounterSummary, next: &EncounterSummary) -> bool {
+    if next.is_active {
         if let (Some(prev_secs), Some(next_secs)) = (
             parse_duration_secs(&previous.duration),
             parse_duration_secs(&next.duration),
@@ -693,8 +1789,8 @@ fn should_rollover(active: &ActiveEncounter, incoming: &EncounterSnapshot) -> bo
             }
         }
 
-        let prev_damage = parse_number(&previous.damage);
-        let next_damage = parse_number(&next.damage);
+        let prev_damage = to_f64_any(&previous.damage);
+        let next_damage = to_f64_any(&next.damage);
         if next_damage + 1.0 < prev_damage {
             return true;
         }
@@ -707,10 +1803,10 @@ fn snapshot_has_activity(snapshot: &EncounterSnapshot) -> bool {
     if snapshot.encounter.is_active {
         return true;
     }
-    if parse_number(&snapshot.encounter.damage) > 0.0
-        || parse_number(&snapshot.encounter.healed) > 0.0
-        || parse_number(&snapshot.encounter.encdps) > 0.0
-        || parse_number(&snapshot.encounter.enchps) > 0.0
+    if to_f64_any(&snapshot.encounter.damage) > 0.0
+        || to_f64_any(&snapshot.encounter.healed) > 0.0
+        || to_f64_any(&snapshot.encounter.encdps) > 0.0
+        || to_f64_any(&snapshot.encounter.enchps) > 0.0
     {
         return true;
     }
@@ -753,7 +1849,7 @@ fn decode_key(bytes: &[u8]) -> Option<HistoryKey> {
     })
 }
 
-fn parse_duration_secs(s: &str) -> Option<u64> {
+pub fn parse_duration_secs(s: &str) -> Option<u64> {
     if s.trim().is_empty() {
         return None;
     }
@@ -778,19 +1874,6 @@ fn parse_duration_secs(s: &str) -> Option<u64> {
     Some(value)
 }
 
-fn parse_number(s: &str) -> f64 {
-    let mut buf = String::with_capacity(s.len());
-    for ch in s.chars() {
-        if ch.is_ascii_digit() || matches!(ch, '.' | '+' | '-') {
-            buf.push(ch);
-        }
-    }
-    if buf.is_empty() {
-        return 0.0;
-    }
-    buf.parse::<f64>().unwrap_or(0.0)
-}
-
 fn now_ms() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -810,11 +1893,23 @@ fn resolve_title(record: &EncounterRecord) -> String {
     "Unknown Encounter".to_string()
 }
 
-fn millis_to_local(ms: u64) -> Option<DateTime<Local>> {
+/// sled reports a held file lock as a generic I/O error, so match on its message.
+fn is_lock_error(err: &sled::Error) -> bool {
+    matches!(err, sled::Error::Io(io) if io.to_string().contains("could not acquire lock"))
+}
+
+/// Convert epoch milliseconds into local time, if the value is representable.
+pub fn millis_to_local(ms: u64) -> Option<DateTime<Local>> {
     let millis = i64::try_from(ms).ok()?;
     Local.timestamp_millis_opt(millis).single()
 }
 
+fn checkpoint_frame_key(idx: usize) -> Vec<u8> {
+    let mut key = CHECKPOINT_FRAME_PREFIX.to_vec();
+    key.extend_from_slice(&(idx as u32).to_be_bytes());
+    key
+}
+
 fn format_date_label(iso_date: &str, encounter_count: usize) -> String {
     match NaiveDate::parse_from_str(iso_date, "%Y-%m-%d") {
         Ok(date) => {
@@ -828,6 +1923,214 @@ fn format_date_label(iso_date: &str, encounter_count: usize) -> String {
     }
 }
 
+const CSV_HEADER: [&str; 11] = [
+    "name", "job", "encdps", "damage", "share", "enchps", "healed", "overheal", "crit", "dh",
+    "deaths",
+];
+
+/// Writes the record's combatant table to a CSV file under the exports directory.
+pub fn export_csv(record: &EncounterRecord) -> Result<PathBuf> {
+    let dir = config::exports_dir();
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Unable to create exports directory {}", dir.display()))?;
+    let path = dir.join(export_file_name(record, "csv"));
+    fs::write(&path, encounter_csv(record))
+        .with_context(|| format!("Failed to write CSV export to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Frame count past which a frames CSV export asks for confirmation first; each frame
+/// becomes one row per combatant.
+pub const FRAMES_CSV_WARN_FRAMES: usize = 2_000;
+
+/// Writes every stored frame of the record as long-format CSV (one row per frame and
+/// combatant) under the exports directory, for time-series analysis.
+pub fn export_frames_csv(record: &EncounterRecord) -> Result<PathBuf> {
+    let dir = config::exports_dir();
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Unable to create exports directory {}", dir.display()))?;
+    let path = dir.join(export_file_name(record, "frames.csv"));
+    fs::write(&path, frames_csv(record))
+        .with_context(|| format!("Failed to write frames CSV export to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Rows included in the clipboard summary of an encounter.
+const SUMMARY_TOP_ROWS: usize = 8;
+
+/// Plain-text summary of an encounter for pasting into chat: a title line, the encounter
+/// totals, and the top combatants by ENCDPS.
+pub fn encounter_summary_text(record: &EncounterRecord) -> String {
+    let mut out = resolve_title(record);
+    let zone = record.encounter.zone.trim();
+    if !zone.is_empty() && zone != out {
+        out.push_str(&format!(" — {zone}"));
+    }
+    out.push('\n');
+    out.push_str(&format!(
+        "Duration {} · ENCDPS {} · Damage {}\n",
+        record.encounter.duration, record.encounter.encdps, record.encounter.damage
+    ));
+    let mut rows: Vec<&CombatantRow> = record.rows.iter().collect();
+    rows.sort_by(|a, b| b.encdps.total_cmp(&a.encdps));
+    for (idx, row) in rows.iter().take(SUMMARY_TOP_ROWS).enumerate() {
+        out.push_str(&format!(
+            "{}. {} ({}) {} DPS · {}\n",
+            idx + 1,
+            row.name,
+            row.job,
+            row.encdps_str,
+            row.share_str
+        ));
+    }
+    out
+}
+
+/// Best-effort link to look an encounter up on the web, built from the user's
+/// `search_url_template`. `{title}`, `{zone}`, `{date}` (local `YYYY-MM-DD`) and `{start}`
+/// (Unix seconds) are replaced with URL-encoded values; there's no report ID to link to directly.
+pub fn encounter_search_url(template: &str, record: &EncounterRecord) -> String {
+    let date = millis_to_local(record.first_seen_ms)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    template
+        .replace("{title}", &encode_url_component(&resolve_title(record)))
+        .replace(
+            "{zone}",
+            &encode_url_component(record.encounter.zone.trim()),
+        )
+        .replace("{date}", &encode_url_component(&date))
+        .replace("{start}", &(record.first_seen_ms / 1000).to_string())
+}
+
+/// `#word` tags in an encounter note, lowercased and without the `#`, first use first:
+/// `"Clean #Clear, healer died P2 #prog"` gives `["clear", "prog"]`.
+pub fn note_tags(note: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for word in note.split_whitespace() {
+        let Some(tag) = word.strip_prefix('#') else {
+            continue;
+        };
+        let tag: String = tag
+            .chars()
+            .take_while(|ch| ch.is_alphanumeric() || matches!(ch, '-' | '_'))
+            .flat_map(char::to_lowercase)
+            .collect();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Whether `note` matches every whitespace-separated term of `query`, ignoring case: `#tag`
+/// terms must name one of the note's tags, other terms just appear somewhere in the note.
+/// A blank query matches nothing.
+pub fn note_matches(note: &str, query: &str) -> bool {
+    let note_lower = note.to_lowercase();
+    let tags = note_tags(note);
+    let mut terms = query.split_whitespace().peekable();
+    terms.peek().is_some()
+        && terms.all(|term| match term.strip_prefix('#') {
+            Some(tag) if !tag.is_empty() => tags.contains(&tag.to_lowercase()),
+            _ => note_lower.contains(&term.to_lowercase()),
+        })
+}
+
+/// Brings an imported record up to `SCHEMA_VERSION`. Older versions only lack fields that
+/// deserialize with defaults, so upgrading just restamps the version.
+fn upgrade_record(mut record: EncounterRecord) -> Result<EncounterRecord> {
+    match record.version {
+        0 => anyhow::bail!("Encounter JSON has no schema version; is it an iinact-tui export?"),
+        v if v > SCHEMA_VERSION => anyhow::bail!(
+            "Encounter JSON uses schema version {v}, newer than the supported version {SCHEMA_VERSION}"
+        ),
+        _ => {
+            record.version = SCHEMA_VERSION;
+            Ok(record)
+        }
+    }
+}
+
+/// Destination for a JSON export of `record`, named like the CSV exports.
+pub fn json_export_path(record: &EncounterRecord) -> PathBuf {
+    config::exports_dir().join(export_file_name(record, "json"))
+}
+
+fn encounter_csv(record: &EncounterRecord) -> String {
+    let mut out = CSV_HEADER.join(",");
+    out.push('\n');
+    for row in &record.rows {
+        let fields = [
+            row.name.as_str(),
+            row.job.as_str(),
+            row.encdps_str.as_str(),
+            row.damage_str.as_str(),
+            row.share_str.as_str(),
+            row.enchps_str.as_str(),
+            row.healed_str.as_str(),
+            row.overheal_pct.as_str(),
+            row.crit.as_str(),
+            row.dh.as_str(),
+            row.deaths.as_str(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_escape(field)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+const FRAMES_CSV_HEADER: [&str; 6] = ["frame", "elapsed_ms", "name", "job", "encdps", "damage"];
+
+fn frames_csv(record: &EncounterRecord) -> String {
+    let mut out = FRAMES_CSV_HEADER.join(",");
+    out.push('\n');
+    for (idx, frame) in record.frames.iter().enumerate() {
+        let elapsed_ms = frame.received_ms.saturating_sub(record.first_seen_ms);
+        for row in &frame.rows {
+            out.push_str(&format!(
+                "{idx},{elapsed_ms},{},{},{:.2},{:.0}\n",
+                csv_escape(&row.name),
+                csv_escape(&row.job),
+                row.encdps,
+                row.damage
+            ));
+        }
+    }
+    out
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn export_file_name(record: &EncounterRecord, extension: &str) -> String {
+    let stamp = millis_to_local(record.last_seen_ms)
+        .map(|dt| dt.format("%Y%m%d-%H%M%S").to_string())
+        .unwrap_or_else(|| record.last_seen_ms.to_string());
+    let slug: String = resolve_title(record)
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("{stamp}-{slug}.{extension}")
+}
+
 fn build_history_items_from_summaries(
     summaries: Vec<EncounterSummaryRecord>,
 ) -> Vec<HistoryEncounterItem> {
@@ -846,13 +2149,13 @@ fn build_history_items_from_summaries(
 
     let mut occurrence_by_key: HashMap<Vec<u8>, u32> = HashMap::new();
     for entries in chronological.values_mut() {
-        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.sort_by_key(|entry| entry.0);
         for (idx, (_, key)) in entries.iter().enumerate() {
             occurrence_by_key.insert(key.clone(), (idx + 1) as u32);
         }
     }
 
-    summaries
+    let mut items: Vec<HistoryEncounterItem> = summaries
         .into_iter()
         .map(|summary| {
             let total = totals.get(&summary.base_title).copied().unwrap_or(1);
@@ -863,17 +2166,70 @@ fn build_history_items_from_summaries(
                 summary.base_title.clone()
             };
             HistoryEncounterItem {
-                key: summary.key,
                 display_title,
-                base_title: summary.base_title,
                 occurrence,
-                time_label: summary.time_label,
-                last_seen_ms: summary.last_seen_ms,
-                timestamp_label: summary.timestamp_label,
-                record: None,
+                ..plain_history_item(summary)
             }
         })
-        .collect()
+        .collect();
+    mark_personal_bests(&mut items);
+    items
+}
+
+/// A list entry for the summary on its own, for lists spanning several dates where the
+/// per-day pull numbers and personal bests don't apply.
+fn plain_history_item(summary: EncounterSummaryRecord) -> HistoryEncounterItem {
+    HistoryEncounterItem {
+        key: summary.key,
+        display_title: summary.base_title.clone(),
+        base_title: summary.base_title,
+        occurrence: 1,
+        time_label: summary.time_label,
+        last_seen_ms: summary.last_seen_ms,
+        timestamp_label: summary.timestamp_label,
+        pinned: summary.pinned,
+        note: summary.note,
+        zone: summary.zone,
+        self_encdps: summary.self_encdps,
+        personal_best: false,
+        record: None,
+    }
+}
+
+/// The local player's row: ACT's "YOU" alias, or the one named like `character_name`.
+fn self_row<'a>(rows: &'a [CombatantRow], character_name: &str) -> Option<&'a CombatantRow> {
+    rows.iter().find(|row| {
+        row.is_self
+            || row.name == SELF_ALIAS
+            || (!character_name.is_empty() && row.name.eq_ignore_ascii_case(character_name))
+    })
+}
+
+/// Tags the pull with the highest self ENCDPS of every `base_title` seen at least twice.
+/// Titles with a single pull get no badge; there is nothing to compare it with.
+pub fn mark_personal_bests(items: &mut [HistoryEncounterItem]) {
+    let mut best: HashMap<&str, (usize, f64, u32)> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        let Some(encdps) = item.self_encdps else {
+            continue;
+        };
+        let entry = best
+            .entry(item.base_title.as_str())
+            .or_insert((idx, encdps, 0));
+        entry.2 += 1;
+        if encdps > entry.1 {
+            entry.0 = idx;
+            entry.1 = encdps;
+        }
+    }
+    let winners: Vec<usize> = best
+        .into_values()
+        .filter(|(_, _, pulls)| *pulls > 1)
+        .map(|(idx, _, _)| idx)
+        .collect();
+    for (idx, item) in items.iter_mut().enumerate() {
+        item.personal_best = winners.contains(&idx);
+    }
 }
 
 #[cfg(test)]
@@ -890,6 +2246,7 @@ mod tests {
             damage: damage.into(),
             enchps: "0".into(),
             healed: "0".into(),
+            damage_taken: "0".into(),
             is_active: active,
         };
         let row = CombatantRow {
@@ -908,13 +2265,359 @@ mod tests {
             heal_share: 0.0,
             heal_share_str: "0%".into(),
             overheal_pct: "0".into(),
+            overheal: 0.0,
+            overheal_str: "0".into(),
+            damage_taken: 0.0,
+            damage_taken_str: "0".into(),
             crit: "0".into(),
             dh: "0".into(),
+            cdh: "0".into(),
+            duration: String::new(),
             deaths: "0".into(),
+            max_hit_str: "Raiton-1,000".into(),
+            max_hit: 1000.0,
+            trend: None,
+            recently_joined: false,
+            rolling_dps: None,
+            is_self: false,
+            in_party: None,
         };
         EncounterSnapshot::new(encounter, vec![row], json!({ "type": "CombatData" }))
     }
 
+    fn temp_store() -> HistoryStore {
+        HistoryStore::open_in_memory().expect("init history store")
+    }
+
+    fn record_at(last_seen_ms: u64) -> EncounterRecord {
+        let mut record = EncounterRecord::new(ActiveEncounter::from_snapshot(
+            build_snapshot(false, "00:30", "1000"),
+            FramePolicy::default(),
+        ));
+        record.last_seen_ms = last_seen_ms;
+        record
+    }
+
+    #[test]
+    fn delete_encounter_prunes_summary_and_date_index() {
+        let store = temp_store();
+        let first = store.append(&record_at(1_700_000_000_000)).unwrap();
+        let second = store.append(&record_at(1_700_000_060_000)).unwrap();
+        let date_id = store.load_dates().unwrap().0[0].iso_date.clone();
+
+        store.delete_encounter(&first.as_bytes()).unwrap();
+        assert!(store.load_encounter_record(&first.as_bytes()).is_err());
+        let days = store.load_dates().unwrap().0;
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].encounter_ids, vec![second.as_bytes()]);
+        assert_eq!(store.load_encounter_summaries(&date_id).unwrap().0.len(), 1);
+
+        store.delete_encounter(&second.as_bytes()).unwrap();
+        assert!(store.load_dates().unwrap().0.is_empty());
+        assert!(store
+            .load_encounter_summaries(&date_id)
+            .unwrap()
+            .0
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn recent_checkpoint_waits_for_the_prompt_and_resumes() {
+        let store = Arc::new(temp_store());
+        assert!(store.load_checkpoint().unwrap().is_none());
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        worker
+            .on_snapshot(build_snapshot(true, "00:10", "1000"))
+            .await;
+        worker.last_checkpoint = None;
+        worker
+            .on_snapshot(build_snapshot(true, "00:20", "2000"))
+            .await;
+        // The second checkpoint only added the new frame to the first one's.
+        let saved = store.load_checkpoint().unwrap().expect("checkpoint");
+        assert_eq!(saved.frames.len(), 2);
+        assert_eq!(saved.encounter.damage, "2000");
+
+        // A crash: the next session finds the checkpoint and holds it for the prompt.
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        assert_eq!(
+            worker.recover_checkpoint().await.as_deref(),
+            Some("Test Encounter")
+        );
+        assert!(worker.current.is_none());
+        worker.resolve_recovery(true).await;
+        assert_eq!(worker.current.as_ref().map(|a| a.frames.len()), Some(2));
+        worker.on_flush().await;
+
+        assert!(store.load_checkpoint().unwrap().is_none());
+        let latest = store.load_latest_encounter().unwrap().expect("stored");
+        assert_eq!(latest.first_seen_ms, saved.first_seen_ms);
+        assert_eq!(latest.frames.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rows_named_like_the_character_are_recorded_as_self() {
+        let store = Arc::new(temp_store());
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        worker.character_name = "alice".into();
+        worker
+            .on_snapshot(build_snapshot(true, "00:30", "1000"))
+            .await;
+        worker.on_flush().await;
+
+        let (recent, _) = store.load_recent(1).unwrap();
+        assert_eq!(recent[0].self_encdps, Some(1000.0));
+    }
+
+    #[tokio::test]
+    async fn declined_or_old_checkpoints_are_stored_as_finished() {
+        let store = Arc::new(temp_store());
+        let mut record = record_at(now_ms());
+        record.frames.clear();
+        let frames = EncounterRecord::new(ActiveEncounter::from_snapshot(
+            build_snapshot(true, "00:30", "1000"),
+            FramePolicy::default(),
+        ))
+        .frames;
+        store.save_checkpoint(&record, &frames, 0).unwrap();
+
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        assert!(worker.recover_checkpoint().await.is_some());
+        // A new pull while the prompt is open leaves the old checkpoint alone.
+        worker
+            .on_snapshot(build_snapshot(true, "00:05", "500"))
+            .await;
+        assert_eq!(
+            store.load_checkpoint().unwrap().map(|r| r.last_seen_ms),
+            Some(record.last_seen_ms)
+        );
+        worker.resolve_recovery(false).await;
+        assert!(worker.current.is_some());
+        assert_eq!(store.load_recent(10).unwrap().0.len(), 1);
+
+        let old = record_at(1_700_000_000_000);
+        store.save_checkpoint(&old, &[], 0).unwrap();
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        assert!(worker.recover_checkpoint().await.is_none());
+        assert!(store.load_checkpoint().unwrap().is_none());
+        assert_eq!(store.load_recent(10).unwrap().0.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn logged_combat_start_splits_encounters_numbers_would_merge() {
+        let store = Arc::new(temp_store());
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        let first = build_snapshot(true, "01:00", "5000");
+        let start_ms = first.received_ms;
+        worker.on_snapshot(first).await;
+
+        // A start logged right as the pull begins belongs to it and doesn't split anything.
+        worker
+            .on_boundary(EncounterBoundary::CombatStart, start_ms + 1_000)
+            .await;
+        assert!(worker.current.is_some());
+        assert_eq!(store.encounters.len(), 0);
+
+        worker
+            .on_boundary(EncounterBoundary::CombatStart, start_ms + 60_000)
+            .await;
+        assert!(worker.current.is_none());
+        assert_eq!(store.encounters.len(), 1);
+
+        // ACT kept counting up, which the heuristics alone would treat as the same pull.
+        worker
+            .on_snapshot(build_snapshot(true, "01:05", "6000"))
+            .await;
+        assert!(worker.current.is_some());
+        assert_eq!(store.encounters.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn logged_combat_end_saves_and_skips_trailing_updates() {
+        let store = Arc::new(temp_store());
+        let mut worker = RecorderWorker::new(Arc::clone(&store));
+        worker
+            .on_snapshot(build_snapshot(true, "02:00", "9000"))
+            .await;
+        worker
+            .on_boundary(EncounterBoundary::CombatEnd, now_ms())
+            .await;
+        assert_eq!(store.encounters.len(), 1);
+
+        worker
+            .on_snapshot(build_snapshot(true, "02:03", "9100"))
+            .await;
+        assert!(worker.current.is_none());
+
+        worker
+            .on_boundary(EncounterBoundary::CombatStart, now_ms())
+            .await;
+        worker
+            .on_snapshot(build_snapshot(true, "02:10", "9500"))
+            .await;
+        assert!(worker.current.is_some());
+
+        worker
+            .on_boundary(EncounterBoundary::ZoneChange, now_ms())
+            .await;
+        assert!(worker.current.is_none());
+        assert_eq!(store.encounters.len(), 2);
+    }
+
+    #[test]
+    fn load_recent_spans_dates_newest_first() {
+        let store = temp_store();
+        store.append(&record_at(1_700_000_000_000)).unwrap();
+        let middle = store.append(&record_at(1_700_000_060_000)).unwrap();
+        let newest = store.append(&record_at(1_700_500_000_000)).unwrap();
+        assert_eq!(store.load_dates().unwrap().0.len(), 2);
+
+        let (recent, skipped) = store.load_recent(2).unwrap();
+        assert_eq!(skipped, 0);
+        let keys: Vec<Vec<u8>> = recent.into_iter().map(|item| item.key).collect();
+        assert_eq!(keys, vec![newest.as_bytes(), middle.as_bytes()]);
+        assert_eq!(store.load_recent(20).unwrap().0.len(), 3);
+    }
+
+    #[test]
+    fn clear_all_wipes_history_but_can_keep_pinned() {
+        let store = temp_store();
+        let pinned = store.append(&record_at(1_700_000_000_000)).unwrap();
+        store.append(&record_at(1_700_000_060_000)).unwrap();
+        store.append(&record_at(1_700_500_000_000)).unwrap();
+        store.set_pinned(&pinned.as_bytes(), true).unwrap();
+
+        assert_eq!(store.clear_all(true).unwrap(), 2);
+        let days = store.load_dates().unwrap().0;
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].encounter_ids, vec![pinned.as_bytes()]);
+
+        assert_eq!(store.clear_all(false).unwrap(), 1);
+        assert!(store.load_dates().unwrap().0.is_empty());
+        assert!(store.load_latest_encounter().unwrap().is_none());
+        assert!(!store.meta.is_empty());
+    }
+
+    #[test]
+    fn corrupt_index_entries_are_skipped_not_fatal() {
+        let store = temp_store();
+        let first = store.append(&record_at(1_700_000_000_000)).unwrap();
+        let second = store.append(&record_at(1_700_000_060_000)).unwrap();
+        store.append(&record_at(1_700_500_000_000)).unwrap();
+        let (days, skipped) = store.load_dates().unwrap();
+        assert_eq!((days.len(), skipped), (2, 0));
+        let (newest, oldest) = (days[0].iso_date.clone(), days[1].iso_date.clone());
+
+        let truncate = |tree: &sled::Tree, key: &[u8]| {
+            let bytes = tree.get(key).unwrap().expect("entry present");
+            tree.insert(key, &bytes[..bytes.len() / 2]).unwrap();
+        };
+        truncate(&store.date_index, newest.as_bytes());
+        truncate(&store.encounter_summaries, &first.as_bytes());
+
+        let (days, skipped) = store.load_dates().unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].iso_date, oldest);
+        let (items, skipped) = store.load_encounter_summaries(&oldest).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].key, second.as_bytes());
+
+        let (zones, skipped) = store.load_zones().unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(zones[0].encounter_count, 2);
+
+        // The oldest day holds the unreadable summary; pruning keeps that encounter and
+        // still removes the other one.
+        let (pruned, skipped) = store.prune_older_than(1_700_400_000_000).unwrap();
+        assert_eq!((pruned, skipped), (1, 2));
+        assert!(store.load_encounter_record(&first.as_bytes()).is_ok());
+        assert!(store.load_encounter_record(&second.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn job_stats_follow_the_local_player_and_refresh_after_appends() {
+        let store = temp_store();
+        store.append(&record_at(1_700_000_000_000)).unwrap();
+        assert!(store.job_stats("").unwrap().0.is_empty());
+        let (stats, skipped) = store.job_stats("alice").unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].encounters, 1);
+
+        let mut record = record_at(1_700_000_060_000);
+        record.rows[0].encdps = 3000.0;
+        store.append(&record).unwrap();
+        let (stats, _) = store.job_stats("Alice").unwrap();
+        assert_eq!(
+            stats,
+            vec![JobStat {
+                job: "NIN".into(),
+                encounters: 2,
+                average_encdps: 2000.0,
+                best_encdps: 3000.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn load_latest_encounter_returns_newest_record() {
+        let store = temp_store();
+        assert!(store.load_latest_encounter().unwrap().is_none());
+        store.append(&record_at(1_700_000_060_000)).unwrap();
+        store.append(&record_at(1_700_000_000_000)).unwrap();
+        let latest = store
+            .load_latest_encounter()
+            .unwrap()
+            .expect("latest record");
+        assert_eq!(latest.last_seen_ms, 1_700_000_060_000);
+    }
+
+    #[test]
+    fn aggregate_day_totals_encounters_and_players() {
+        let store = temp_store();
+        store.append(&record_at(1_700_000_000_000)).unwrap();
+        let mut stronger = record_at(1_700_000_060_000);
+        stronger.encounter.encdps = "2,500".into();
+        stronger.encounter.duration = "01:15".into();
+        store.append(&stronger).unwrap();
+        let date_id = store.load_dates().unwrap().0[0].iso_date.clone();
+
+        let aggregate = store.aggregate_day(&date_id).unwrap();
+        assert_eq!(aggregate.encounter_count, 2);
+        assert_eq!(aggregate.total_combat_secs, 105);
+        assert_eq!(aggregate.top_encdps, 2500.0);
+        assert_eq!(aggregate.players.len(), 1);
+        assert_eq!(aggregate.players[0].name, "Alice");
+        assert_eq!(aggregate.players[0].appearances, 2);
+
+        let empty = store.aggregate_day("1999-01-01").unwrap();
+        assert_eq!(empty.encounter_count, 0);
+    }
+
+    #[test]
+    fn load_zones_counts_encounters_and_dates() {
+        let store = temp_store();
+        store.append(&record_at(1_700_000_000_000)).unwrap();
+        store.append(&record_at(1_700_000_060_000)).unwrap();
+        let mut elsewhere = record_at(1_700_200_000_000);
+        elsewhere.encounter.zone = "Other Zone".into();
+        store.append(&elsewhere).unwrap();
+        let mut later = record_at(1_700_400_000_000);
+        later.encounter.zone = "Test Zone".into();
+        store.append(&later).unwrap();
+
+        let (zones, skipped) = store.load_zones().unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].zone, "Test Zone");
+        assert_eq!(zones[0].encounter_count, 3);
+        assert_eq!(zones[0].date_ids.len(), 2);
+        assert_eq!(zones[1].zone, "Other Zone");
+        assert_eq!(zones[1].encounter_count, 1);
+    }
+
     #[test]
     fn key_roundtrip() {
         let key = HistoryKey::new("enc", 12345, 42);
@@ -934,30 +2637,55 @@ mod tests {
 
     #[test]
     fn rollover_detects_duration_reset() {
-        let active = ActiveEncounter::from_snapshot(build_snapshot(true, "01:20", "5000"));
+        let active = ActiveEncounter::from_snapshot(
+            build_snapshot(true, "01:20", "5000"),
+            FramePolicy::default(),
+        );
         let incoming = build_snapshot(true, "00:05", "100");
         assert!(should_rollover(&active, &incoming));
     }
 
     #[test]
     fn rollover_ignores_inactive_duration_reset() {
-        let active = ActiveEncounter::from_snapshot(build_snapshot(true, "01:20", "5000"));
+        let active = ActiveEncounter::from_snapshot(
+            build_snapshot(true, "01:20", "5000"),
+            FramePolicy::default(),
+        );
         let incoming = build_snapshot(false, "00:00", "5000");
         assert!(!should_rollover(&active, &incoming));
     }
 
     #[test]
     fn rollover_ignores_title_change_mid_fight() {
-        let active = ActiveEncounter::from_snapshot(build_snapshot(true, "01:20", "5000"));
+        let active = ActiveEncounter::from_snapshot(
+            build_snapshot(true, "01:20", "5000"),
+            FramePolicy::default(),
+        );
         let mut incoming = build_snapshot(true, "01:21", "5200");
         incoming.encounter.title = "Renamed Encounter".into();
         incoming.encounter.zone = "Updated Zone".into();
         assert!(!should_rollover(&active, &incoming));
     }
 
+    #[test]
+    fn ended_encounter_skips_updates_until_it_restarts() {
+        let ended = build_snapshot(true, "05:00", "90000").encounter;
+        let later = build_snapshot(true, "05:30", "91000").encounter;
+        assert!(continues_ended(&ended, &later));
+
+        let restarted = build_snapshot(true, "00:03", "1200").encounter;
+        assert!(!continues_ended(&later, &restarted));
+
+        let finished = build_snapshot(false, "05:31", "91000").encounter;
+        assert!(!continues_ended(&later, &finished));
+    }
+
     #[test]
     fn encounter_record_preserves_all_frames() {
-        let mut active = ActiveEncounter::from_snapshot(build_snapshot(true, "00:01", "100"));
+        let mut active = ActiveEncounter::from_snapshot(
+            build_snapshot(true, "00:01", "100"),
+            FramePolicy::default(),
+        );
         active.update(build_snapshot(true, "00:02", "200"));
         active.update(build_snapshot(false, "00:02", "200"));
         let record = EncounterRecord::new(active);
@@ -967,6 +2695,387 @@ mod tests {
         assert!(!record.frames.last().unwrap().encounter.is_active);
     }
 
+    #[test]
+    fn wall_duration_uses_first_and_last_seen() {
+        let mut record = record_at(1_700_000_095_900);
+        record.first_seen_ms = 1_700_000_000_000;
+        assert_eq!(record.wall_duration_secs(), 95);
+        record.first_seen_ms = record.last_seen_ms + 1;
+        assert_eq!(record.wall_duration_secs(), 0);
+    }
+
+    #[test]
+    fn dps_timeline_buckets_frames_per_second() {
+        let mut first = build_snapshot(true, "00:01", "100");
+        first.received_ms = 10_000;
+        first.encounter.encdps = "100".into();
+        let mut active = ActiveEncounter::from_snapshot(first, FramePolicy::default());
+        for (received_ms, encdps) in [(10_400, "150"), (11_100, "1,200"), (13_000, "900")] {
+            let mut snapshot = build_snapshot(true, "00:02", "200");
+            snapshot.received_ms = received_ms;
+            snapshot.encounter.encdps = encdps.into();
+            active.update(snapshot);
+        }
+        let record = EncounterRecord::new(active);
+        assert_eq!(
+            record.dps_timeline(),
+            vec![(0, 150.0), (1, 1200.0), (3, 900.0)]
+        );
+    }
+
+    #[test]
+    fn frame_active_secs_spans_first_to_last_contribution() {
+        let mut first = build_snapshot(true, "00:01", "100");
+        first.received_ms = 10_000;
+        first.rows[0].damage = 100.0;
+        let mut active = ActiveEncounter::from_snapshot(first, FramePolicy::default());
+        for (received_ms, damage) in [(20_000, 500.0), (45_000, 900.0), (60_000, 900.0)] {
+            let mut snapshot = build_snapshot(true, "00:02", "200");
+            snapshot.received_ms = received_ms;
+            snapshot.rows[0].damage = damage;
+            active.update(snapshot);
+        }
+        let record = EncounterRecord::new(active);
+        let name = record.rows[0].name.clone();
+        assert_eq!(record.frame_active_secs().get(&name), Some(&35));
+    }
+
+    #[test]
+    fn encounter_summary_text_lists_top_rows() {
+        let record = record_at(1_700_000_000_000);
+        let text = encounter_summary_text(&record);
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("Test Encounter — Test Zone"));
+        assert_eq!(
+            lines.next(),
+            Some("Duration 00:30 · ENCDPS 1000 · Damage 1000")
+        );
+        assert_eq!(lines.next(), Some("1. Alice (NIN) 1000 DPS · 100%"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn encounter_search_url_fills_encoded_placeholders() {
+        let record = record_at(1_700_000_000_000);
+        let date = millis_to_local(record.first_seen_ms)
+            .unwrap()
+            .format("%Y-%m-%d")
+            .to_string();
+        assert_eq!(
+            encounter_search_url(
+                "https://example.com/?q={title}&z={zone}&d={date}&t={start}",
+                &record
+            ),
+            format!(
+                "https://example.com/?q=Test%20Encounter&z=Test%20Zone&d={date}&t={}",
+                record.first_seen_ms / 1000
+            )
+        );
+    }
+
+    #[test]
+    fn encounter_csv_escapes_fields() {
+        let mut snapshot = build_snapshot(false, "00:30", "1,000");
+        snapshot.rows[0].name = "Alice \"Ace\"".into();
+        let record = EncounterRecord::new(ActiveEncounter::from_snapshot(
+            snapshot,
+            FramePolicy::default(),
+        ));
+        let csv = encounter_csv(&record);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("name,job,encdps,damage,share,enchps,healed,overheal,crit,dh,deaths")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("\"Alice \"\"Ace\"\"\",NIN,1000,\"1,000\",100%,0,0,0,0,0,0")
+        );
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn frames_csv_emits_one_row_per_frame_and_combatant() {
+        let mut active = ActiveEncounter::from_snapshot(
+            build_snapshot(true, "00:01", "100"),
+            FramePolicy::default(),
+        );
+        let mut second = build_snapshot(true, "00:02", "300");
+        second.received_ms = active.first_seen_ms + 1_250;
+        let mut bob = second.rows[0].clone();
+        bob.name = "Bob, Jr.".into();
+        bob.job = "WHM".into();
+        bob.encdps = 12.345;
+        bob.damage = 50.0;
+        second.rows.push(bob);
+        active.update(second);
+        let record = EncounterRecord::new(active);
+
+        let csv = frames_csv(&record);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "frame,elapsed_ms,name,job,encdps,damage");
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("0,0,Alice,NIN,1000.00,"));
+        assert!(lines[2].starts_with("1,1250,Alice,NIN,"));
+        assert_eq!(lines[3], "1,1250,\"Bob, Jr.\",WHM,12.35,50");
+    }
+
+    #[test]
+    fn export_record_json_optionally_drops_frames() {
+        let store = temp_store();
+        let key = store
+            .append(&record_at(1_700_000_000_000))
+            .unwrap()
+            .as_bytes();
+        let path =
+            std::env::temp_dir().join(format!("iinact-tui-export-{}.json", std::process::id()));
+
+        store.export_record_json(&key, &path, true).unwrap();
+        let full: EncounterRecord = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(full.frames.len(), 1);
+        assert_eq!(full.last_seen_ms, 1_700_000_000_000);
+
+        store.export_record_json(&key, &path, false).unwrap();
+        let slim: EncounterRecord = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert!(slim.frames.is_empty());
+        assert_eq!(slim.rows.len(), full.rows.len());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn import_record_json_round_trips_and_rejects_newer_schema() {
+        let source = temp_store();
+        let key = source
+            .append(&record_at(1_700_000_000_000))
+            .unwrap()
+            .as_bytes();
+        let path =
+            std::env::temp_dir().join(format!("iinact-tui-import-{}.json", std::process::id()));
+        source.export_record_json(&key, &path, true).unwrap();
+
+        let target = temp_store();
+        let imported = target.import_record_json(&path).unwrap();
+        let record = target.load_encounter_record(&imported.as_bytes()).unwrap();
+        assert_eq!(record.last_seen_ms, 1_700_000_000_000);
+        assert_eq!(target.load_dates().unwrap().0.len(), 1);
+
+        let mut newer = record;
+        newer.version = SCHEMA_VERSION + 1;
+        fs::write(&path, serde_json::to_vec(&newer).unwrap()).unwrap();
+        assert!(target.import_record_json(&path).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn open_reports_database_held_by_another_instance() {
+        let path =
+            std::env::temp_dir().join(format!("iinact-tui-lock-{}.sled", std::process::id()));
+        let first = HistoryStore::open(&path).unwrap();
+        let err = HistoryStore::open(&path)
+            .err()
+            .expect("second open must fail");
+        assert!(format!("{err:#}").contains("in use by another iinact-tui instance"));
+        drop(first);
+        let _ = fs::remove_dir_all(&path);
+    }
+
+    #[test]
+    fn dropping_raw_frames_shrinks_records() {
+        let record_with = |store_raw: bool| {
+            let policy = FramePolicy {
+                store_raw,
+                ..FramePolicy::default()
+            };
+            let mut first = build_snapshot(true, "00:00", "0");
+            first.raw = json!({ "type": "CombatData", "padding": "x".repeat(2_000) });
+            let mut active = ActiveEncounter::from_snapshot(first.clone(), policy);
+            for _ in 0..50 {
+                active.update(first.clone());
+            }
+            EncounterRecord::new(active)
+        };
+        let full = record_with(true);
+        let slim = record_with(false);
+        assert!(full.frames.iter().all(|frame| frame.raw.is_some()));
+        assert!(slim.frames.iter().all(|frame| frame.raw.is_none()));
+        assert!(slim.raw_last.is_some());
+
+        let full_size = serde_cbor::to_vec(&full).unwrap().len();
+        let slim_size = serde_cbor::to_vec(&slim).unwrap().len();
+        assert!(
+            slim_size * 4 < full_size,
+            "raw-less record is {slim_size} bytes vs {full_size} with raw frames"
+        );
+
+        // Records written before raw became optional still load.
+        let mut legacy = serde_json::to_value(&slim).unwrap();
+        legacy["frames"][0]["raw"] = json!({ "type": "CombatData" });
+        let restored: EncounterRecord = serde_json::from_value(legacy).unwrap();
+        assert!(restored.frames[0].raw.is_some());
+    }
+
+    #[test]
+    fn frame_cap_thins_frames_but_counts_every_snapshot() {
+        let mut first = build_snapshot(true, "00:00", "0");
+        first.received_ms = 0;
+        let capped = FramePolicy {
+            max_frames: 100,
+            ..FramePolicy::default()
+        };
+        let mut active = ActiveEncounter::from_snapshot(first, capped);
+        for i in 1..1000u64 {
+            let mut snapshot = build_snapshot(true, "00:01", "100");
+            snapshot.received_ms = i;
+            active.update(snapshot);
+        }
+        let record = EncounterRecord::new(active);
+        assert_eq!(record.snapshots, 1000);
+        assert!(record.frames.len() <= 100);
+        assert!(record.frames.len() >= 50);
+        assert_eq!(record.frames.last().unwrap().received_ms, 999);
+        assert!(record
+            .frames
+            .windows(2)
+            .all(|pair| pair[0].received_ms < pair[1].received_ms));
+
+        let unlimited = FramePolicy {
+            max_frames: 0,
+            ..FramePolicy::default()
+        };
+        let mut uncapped =
+            ActiveEncounter::from_snapshot(build_snapshot(true, "00:00", "0"), unlimited);
+        for _ in 1..1000 {
+            uncapped.update(build_snapshot(true, "00:01", "100"));
+        }
+        assert_eq!(EncounterRecord::new(uncapped).frames.len(), 1000);
+    }
+
+    #[test]
+    fn recording_filter_skips_short_or_light_encounters() {
+        let mut record = record_at(1_700_000_000_000);
+        record.encounter.duration = "00:08".into();
+        record.encounter.damage = "40,000".into();
+        assert!(RecordingFilter::default().allows(&record));
+
+        let by_duration = RecordingFilter {
+            min_duration_secs: 10,
+            min_damage: 0,
+        };
+        assert!(!by_duration.allows(&record));
+
+        let by_damage = RecordingFilter {
+            min_duration_secs: 0,
+            min_damage: 50_000,
+        };
+        assert!(!by_damage.allows(&record));
+
+        record.encounter.duration = "01:05".into();
+        record.encounter.damage = "1,250,000".into();
+        assert!(by_duration.allows(&record));
+        assert!(by_damage.allows(&record));
+    }
+
+    #[test]
+    fn notes_are_stored_on_the_summary_and_can_be_removed() {
+        let store = temp_store();
+        let key = store.append(&record_at(1_700_000_000_000)).unwrap();
+        let date_id = store.load_dates().unwrap().0[0].iso_date.clone();
+
+        store
+            .set_note(&key.as_bytes(), Some("clean kill #clear".into()))
+            .unwrap();
+        store.set_pinned(&key.as_bytes(), true).unwrap();
+        let items = store.load_encounter_summaries(&date_id).unwrap().0;
+        assert_eq!(items[0].note.as_deref(), Some("clean kill #clear"));
+        assert!(items[0].pinned);
+
+        store.set_note(&key.as_bytes(), None).unwrap();
+        let items = store.load_encounter_summaries(&date_id).unwrap().0;
+        assert_eq!(items[0].note, None);
+        assert!(store.set_note(b"missing", None).is_err());
+    }
+
+    #[test]
+    fn note_search_matches_tags_and_words_newest_first() {
+        let store = temp_store();
+        let older = store.append(&record_at(1_700_000_000_000)).unwrap();
+        let newer = store.append(&record_at(1_700_100_000_000)).unwrap();
+        let prog = store.append(&record_at(1_700_000_060_000)).unwrap();
+        store.append(&record_at(1_700_000_120_000)).unwrap();
+        store
+            .set_note(&older.as_bytes(), Some("clean kill #clear".into()))
+            .unwrap();
+        store
+            .set_note(&newer.as_bytes(), Some("#Clear with 2 deaths".into()))
+            .unwrap();
+        store
+            .set_note(
+                &prog.as_bytes(),
+                Some("healer died P2 #prog #clearance".into()),
+            )
+            .unwrap();
+
+        let (found, skipped) = store.search_notes("#clear").unwrap();
+        assert_eq!(skipped, 0);
+        assert!(found
+            .iter()
+            .all(|item| item.display_title == item.base_title));
+        let keys: Vec<Vec<u8>> = found.into_iter().map(|item| item.key).collect();
+        assert_eq!(keys, vec![newer.as_bytes(), older.as_bytes()]);
+
+        let (found, _) = store.search_notes("HEALER p2").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].key, prog.as_bytes());
+        assert!(store.search_notes("  ").unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn note_tags_are_lowercased_and_deduplicated() {
+        assert_eq!(
+            note_tags("Clean #Clear, healer died P2 #prog #clear #"),
+            vec!["clear".to_string(), "prog".to_string()]
+        );
+        assert!(note_tags("no tags here").is_empty());
+    }
+
+    #[test]
+    fn pinned_encounters_sort_first_within_their_date() {
+        let store = temp_store();
+        let older = store.append(&record_at(1_700_000_000_000)).unwrap();
+        let newer = store.append(&record_at(1_700_000_060_000)).unwrap();
+        let date_id = store.load_dates().unwrap().0[0].iso_date.clone();
+
+        store.set_pinned(&older.as_bytes(), true).unwrap();
+        let items = store.load_encounter_summaries(&date_id).unwrap().0;
+        assert_eq!(items[0].key, older.as_bytes());
+        assert!(items[0].pinned);
+        assert_eq!(items[1].key, newer.as_bytes());
+        assert!(!items[1].pinned);
+    }
+
+    #[test]
+    fn prune_older_than_keeps_recent_and_pinned_encounters() {
+        let store = temp_store();
+        const DAY_MS: u64 = 86_400_000;
+        let old = store.append(&record_at(1_700_000_000_000)).unwrap();
+        let old_pinned = store.append(&record_at(1_700_000_060_000)).unwrap();
+        let recent = store
+            .append(&record_at(1_700_000_000_000 + 10 * DAY_MS))
+            .unwrap();
+        store.set_pinned(&old_pinned.as_bytes(), true).unwrap();
+
+        let pruned = store
+            .prune_older_than(1_700_000_000_000 + 5 * DAY_MS)
+            .unwrap();
+        assert_eq!(pruned, (1, 0));
+        assert!(store.load_encounter_record(&old.as_bytes()).is_err());
+        assert!(store.load_encounter_record(&old_pinned.as_bytes()).is_ok());
+        assert!(store.load_encounter_record(&recent.as_bytes()).is_ok());
+        assert_eq!(store.load_dates().unwrap().0.len(), 2);
+    }
+
     #[test]
     fn snapshot_activity_detects_idle_state() {
         let idle = EncounterSnapshot::new(
@@ -978,6 +3087,7 @@ mod tests {
                 damage: "0".into(),
                 enchps: "0".into(),
                 healed: "0".into(),
+                damage_taken: "0".into(),
                 is_active: false,
             },
             Vec::new(),
@@ -991,9 +3101,42 @@ mod tests {
     }
 
     #[test]
-    fn parse_number_handles_commas_and_percent() {
-        assert_eq!(parse_number("12,345.6"), 12345.6);
-        assert_eq!(parse_number("98%"), 98.0);
+    fn history_numbers_handle_grouping_and_percent() {
+        assert_eq!(to_f64_any("12,345.6"), 12345.6);
+        assert_eq!(to_f64_any("98%"), 98.0);
+    }
+
+    #[test]
+    fn comma_decimal_frames_keep_their_value() {
+        // ACT on a comma-decimal system: "1.000,5" is a thousand, not one, so climbing past
+        // it from "999,5" is the same fight.
+        let active = ActiveEncounter::from_snapshot(
+            build_snapshot(true, "00:40", "999,5"),
+            FramePolicy::default(),
+        );
+        let incoming = build_snapshot(true, "00:41", "1.000,5");
+        assert!(!should_rollover(&active, &incoming));
+        let incoming = build_snapshot(true, "00:41", "99,5");
+        assert!(should_rollover(&active, &incoming));
+
+        let mut first = build_snapshot(true, "00:01", "100");
+        first.received_ms = 10_000;
+        first.encounter.encdps = "1.234,5".into();
+        let record = EncounterRecord::new(ActiveEncounter::from_snapshot(
+            first,
+            FramePolicy::default(),
+        ));
+        assert_eq!(record.dps_timeline(), vec![(0, 1234.5)]);
+
+        let mut record = record_at(1_700_000_000_000);
+        record.encounter.damage = "45.000,5".into();
+        let filter = RecordingFilter {
+            min_duration_secs: 0,
+            min_damage: 50_000,
+        };
+        assert!(!filter.allows(&record));
+        record.encounter.damage = "1.250.000,5".into();
+        assert!(filter.allows(&record));
     }
 
     fn make_summary(key: &[u8], base_title: &str, last_seen: u64) -> EncounterSummaryRecord {
@@ -1011,6 +3154,9 @@ mod tests {
             zone: "Zone".into(),
             snapshots: 3,
             frames: 3,
+            pinned: false,
+            self_encdps: None,
+            note: None,
         }
     }
 
@@ -1036,7 +3182,7 @@ mod tests {
             make_summary(&[2], "Rubicante", 3_000),
             make_summary(&[3], "Rubicante", 2_000),
         ];
-        summaries.sort_by(|a, b| b.last_seen_ms.cmp(&a.last_seen_ms));
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.last_seen_ms));
 
         let items = build_history_items_from_summaries(summaries);
         assert_eq!(items.len(), 3);
@@ -1044,4 +3190,24 @@ mod tests {
         assert_eq!(items[1].display_title, "Rubicante (2)");
         assert_eq!(items[2].display_title, "Rubicante (1)");
     }
+
+    #[test]
+    fn personal_best_marks_top_self_encdps_per_title() {
+        let summary = |key: u8, title: &str, self_encdps: Option<f64>| EncounterSummaryRecord {
+            self_encdps,
+            ..make_summary(&[key], title, u64::from(key) * 1_000)
+        };
+        let items = build_history_items_from_summaries(vec![
+            summary(1, "Rubicante", Some(9_000.0)),
+            summary(2, "Rubicante", Some(12_000.0)),
+            summary(3, "Rubicante", None),
+            summary(4, "Striking Dummy", Some(20_000.0)),
+        ]);
+        let marked: Vec<&[u8]> = items
+            .iter()
+            .filter(|item| item.personal_best)
+            .map(|item| item.key.as_slice())
+            .collect();
+        assert_eq!(marked, vec![&[2u8][..]]);
+    }
 }
@@ -0,0 +1,266 @@
+use crossterm::event::KeyCode;
+
+use crate::config::KeybindingsConfig;
+
+/// Global actions whose keys can be remapped through the `keybindings` config section.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ToggleHistory,
+    ToggleIdle,
+    CycleDecoration,
+    CycleMode,
+    Settings,
+    ToggleCompact,
+    ToggleAnonymize,
+    ToggleHeader,
+    ToggleFooter,
+    Help,
+    About,
+    Logs,
+}
+
+impl Action {
+    const ALL: [Action; 13] = [
+        Action::Quit,
+        Action::ToggleHistory,
+        Action::ToggleIdle,
+        Action::CycleDecoration,
+        Action::CycleMode,
+        Action::Settings,
+        Action::ToggleCompact,
+        Action::ToggleAnonymize,
+        Action::ToggleHeader,
+        Action::ToggleFooter,
+        Action::Help,
+        Action::About,
+        Action::Logs,
+    ];
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ToggleHistory => "toggle_history",
+            Action::ToggleIdle => "toggle_idle",
+            Action::CycleDecoration => "cycle_decoration",
+            Action::CycleMode => "cycle_mode",
+            Action::Settings => "settings",
+            Action::ToggleCompact => "toggle_compact",
+            Action::ToggleAnonymize => "toggle_anonymize",
+            Action::ToggleHeader => "toggle_header",
+            Action::ToggleFooter => "toggle_footer",
+            Action::Help => "help",
+            Action::About => "about",
+            Action::Logs => "logs",
+        }
+    }
+
+    /// Short description for the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "quit / close the open panel",
+            Action::ToggleHistory => "encounter history",
+            Action::ToggleIdle => "idle overlay on/off",
+            Action::CycleDecoration => "cycle decorations",
+            Action::CycleMode => "cycle DPS/HEAL/TANK",
+            Action::Settings => "settings pane",
+            Action::ToggleCompact => "compact mode",
+            Action::ToggleAnonymize => "anonymize names",
+            Action::ToggleHeader => "show/hide the header",
+            Action::ToggleFooter => "show/hide the footer",
+            Action::Help => "this help",
+            Action::About => "version and paths",
+            Action::Logs => "recent log lines",
+        }
+    }
+
+    fn key_in(self, cfg: &KeybindingsConfig) -> char {
+        match self {
+            Action::Quit => cfg.quit,
+            Action::ToggleHistory => cfg.toggle_history,
+            Action::ToggleIdle => cfg.toggle_idle,
+            Action::CycleDecoration => cfg.cycle_decoration,
+            Action::CycleMode => cfg.cycle_mode,
+            Action::Settings => cfg.settings,
+            Action::ToggleCompact => cfg.toggle_compact,
+            Action::ToggleAnonymize => cfg.toggle_anonymize,
+            Action::ToggleHeader => cfg.toggle_header,
+            Action::ToggleFooter => cfg.toggle_footer,
+            Action::Help => cfg.help,
+            Action::About => cfg.about,
+            Action::Logs => cfg.logs,
+        }
+    }
+}
+
+/// Keys with fixed meanings on the main screen; binding an action to one shadows it.
+const RESERVED_KEYS: [char; 7] = ['o', 'f', ' ', '[', ']', '+', '-'];
+
+/// Keys the history panel handles itself; while it is open they never trigger a bound action.
+const HISTORY_KEYS: [char; 17] = [
+    'e', 'E', 'j', 'y', 'w', 'd', 'X', 'P', 'p', 'a', 't', 'L', 'o', 'z', '/', 'n', 'r',
+];
+
+/// Resolved key → action table consulted by the event loop. `Esc` always quits.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(char, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&KeybindingsConfig::default()).0
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap, returning a warning for every conflict. When two actions share a key,
+    /// the later one falls back to its default key, or stays unbound if that is taken too.
+    pub fn from_config(cfg: &KeybindingsConfig) -> (Self, Vec<String>) {
+        let defaults = KeybindingsConfig::default();
+        let mut bindings: Vec<(char, Action)> = Vec::with_capacity(Action::ALL.len());
+        let mut warnings = Vec::new();
+        for action in Action::ALL {
+            let key = action.key_in(cfg);
+            let Some(other) = bound_action(&bindings, key) else {
+                if RESERVED_KEYS.contains(&key) {
+                    warnings.push(format!(
+                        "Key {key:?} for {} shadows a built-in key",
+                        action.config_key()
+                    ));
+                }
+                bindings.push((key, action));
+                continue;
+            };
+            let fallback = action.key_in(&defaults);
+            if bound_action(&bindings, fallback).is_none() {
+                warnings.push(format!(
+                    "Key {key:?} for {} is already bound to {}; using {fallback:?}",
+                    action.config_key(),
+                    other.config_key()
+                ));
+                bindings.push((fallback, action));
+            } else {
+                warnings.push(format!(
+                    "Key {key:?} for {} is already bound to {}; leaving it unbound",
+                    action.config_key(),
+                    other.config_key()
+                ));
+            }
+        }
+        (Self { bindings }, warnings)
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        match code {
+            KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Char(ch) => bound_action(&self.bindings, ch),
+            _ => None,
+        }
+    }
+
+    /// Like [`Keymap::action`], but while the history panel is open its own keys win over any
+    /// action remapped onto them.
+    pub fn action_for(&self, code: KeyCode, history_open: bool) -> Option<Action> {
+        match code {
+            KeyCode::Char(ch) if history_open && HISTORY_KEYS.contains(&ch) => None,
+            _ => self.action(code),
+        }
+    }
+
+    /// Bound keys with their descriptions, in action order, for the help overlay.
+    pub fn help_entries(&self) -> Vec<(char, String)> {
+        Action::ALL
+            .into_iter()
+            .filter_map(|action| {
+                let (key, _) = self.bindings.iter().find(|(_, bound)| *bound == action)?;
+                Some((*key, action.description().to_string()))
+            })
+            .collect()
+    }
+}
+
+fn bound_action(bindings: &[(char, Action)], key: char) -> Option<Action> {
+    bindings
+        .iter()
+        .find(|(bound, _)| *bound == key)
+        .map(|(_, action)| *action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_builtin_keys() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(keymap.action(KeyCode::Esc), Some(Action::Quit));
+        assert_eq!(
+            keymap.action(KeyCode::Char('h')),
+            Some(Action::ToggleHistory)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('s')), Some(Action::Settings));
+        assert_eq!(keymap.action(KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn duplicate_keys_warn_and_fall_back() {
+        let cfg = KeybindingsConfig {
+            toggle_history: 'x',
+            cycle_mode: 'x',
+            ..KeybindingsConfig::default()
+        };
+        let (keymap, warnings) = Keymap::from_config(&cfg);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            keymap.action(KeyCode::Char('x')),
+            Some(Action::ToggleHistory)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('m')), Some(Action::CycleMode));
+        assert_eq!(keymap.action(KeyCode::Char('h')), None);
+    }
+
+    #[test]
+    fn history_keys_win_over_remapped_actions_while_history_is_open() {
+        let cfg = KeybindingsConfig {
+            quit: 'e',
+            toggle_history: 'z',
+            ..KeybindingsConfig::default()
+        };
+        let (keymap, _) = Keymap::from_config(&cfg);
+        assert_eq!(
+            keymap.action_for(KeyCode::Char('e'), false),
+            Some(Action::Quit)
+        );
+        assert_eq!(keymap.action_for(KeyCode::Char('e'), true), None);
+        assert_eq!(keymap.action_for(KeyCode::Char('z'), true), None);
+        assert_eq!(keymap.action_for(KeyCode::Esc, true), Some(Action::Quit));
+        assert_eq!(
+            keymap.action_for(KeyCode::Char('i'), true),
+            Some(Action::ToggleIdle)
+        );
+    }
+
+    #[test]
+    fn help_entries_follow_remapped_keys_and_skip_unbound_actions() {
+        let cfg = KeybindingsConfig {
+            toggle_header: 'x',
+            toggle_footer: '?',
+            help: 'n',
+            ..KeybindingsConfig::default()
+        };
+        let (keymap, _) = Keymap::from_config(&cfg);
+        let entries = keymap.help_entries();
+        let key_of = |description: &str| {
+            entries
+                .iter()
+                .find(|(_, text)| text == description)
+                .map(|(key, _)| *key)
+        };
+        assert_eq!(key_of(Action::ToggleHeader.description()), Some('x'));
+        assert_eq!(key_of(Action::ToggleFooter.description()), Some('?'));
+        assert_eq!(key_of(Action::Help.description()), None);
+        assert_eq!(entries.len(), 12);
+    }
+}
@@ -0,0 +1,112 @@
+//! Bounded in-memory copy of recent log events, shown in the in-app log pane.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Lines kept before the oldest are dropped.
+pub const LOG_BUFFER_LINES: usize = 200;
+
+/// Shared ring of formatted log lines, oldest first.
+pub type LogLines = Arc<Mutex<VecDeque<String>>>;
+
+/// Appends `line`, dropping the oldest line once the buffer is full.
+pub fn push_line(lines: &LogLines, line: String) {
+    let Ok(mut lines) = lines.lock() else {
+        return;
+    };
+    if lines.len() == LOG_BUFFER_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+/// Copies the buffered lines out so the lock isn't held while drawing.
+pub fn recent_lines(lines: &LogLines) -> Vec<String> {
+    lines
+        .lock()
+        .map(|lines| lines.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Tracing layer that formats each event into one line and pushes it into the buffer.
+/// Install it behind a level filter: it runs on the emitting thread.
+pub struct BufferLayer {
+    lines: LogLines,
+}
+
+impl BufferLayer {
+    pub fn new(lines: LogLines) -> Self {
+        Self { lines }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for BufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let line = format!(
+            "{} {:>5} {}{}",
+            chrono::Local::now().format("%H:%M:%S"),
+            event.metadata().level(),
+            visitor.message,
+            visitor.fields
+        );
+        push_line(&self.lines, line);
+    }
+}
+
+/// Collects the `message` field plus ` key=value` pairs for the rest.
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn layer_keeps_only_the_newest_lines() {
+        let lines = LogLines::default();
+        let subscriber = tracing_subscriber::registry().with(BufferLayer::new(lines.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            for idx in 0..LOG_BUFFER_LINES + 5 {
+                tracing::warn!(attempt = idx, "reconnecting");
+            }
+        });
+
+        let recent = recent_lines(&lines);
+        assert_eq!(recent.len(), LOG_BUFFER_LINES);
+        assert!(recent[0].ends_with(" WARN reconnecting attempt=5"));
+        assert!(recent
+            .last()
+            .unwrap()
+            .ends_with(&format!("reconnecting attempt={}", LOG_BUFFER_LINES + 4)));
+    }
+}
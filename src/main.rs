@@ -1,80 +1,238 @@
 use std::env;
 use std::fs::{create_dir_all, OpenOptions};
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{io, sync::Arc};
 
 use anyhow::{bail, Context, Result};
 use crossterm::event::{
-    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent,
-    MouseEventKind,
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    MouseButton, MouseEvent, MouseEventKind,
 };
-use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
+use crossterm::{cursor, execute};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, oneshot, watch, RwLock};
 use tokio::task;
 
+mod clipboard;
 mod config;
 mod history;
+mod keymap;
+mod log_buffer;
 mod model;
 mod parse;
+mod session;
 mod theme;
 mod ui;
+mod ui_confirm;
 mod ui_history;
 mod ui_idle;
+mod ui_logs;
+mod util;
 mod ws_client;
 
-use history::HistoryStore;
-use model::{AppEvent, AppSettings, AppState, HistoryPanelLevel, SettingsField, WS_URL_DEFAULT};
+use history::{EncounterFrame, EncounterRecord, HistoryStore};
+use keymap::{Action, Keymap};
+use log_buffer::{BufferLayer, LogLines};
+use model::{
+    AppEvent, AppSettings, AppState, HistoryPanelLevel, PendingAction, SettingsField,
+    RECENT_ENCOUNTERS_LIMIT,
+};
 use tracing::level_filters::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
 const HISTORY_LIST_OFFSET: u16 = 4;
+/// Clean frames are still redrawn this often so clocks, countdowns and fades keep moving.
+const FORCED_REDRAW: Duration = Duration::from_secs(1);
+/// Longer gaps between recorded frames (e.g. a paused ACT) are shortened to this when replaying.
+const REPLAY_MAX_GAP: Duration = Duration::from_secs(5);
 
 enum HistoryTask {
-    LoadEncounters { date_id: String },
-    LoadEncounterDetail { key: Vec<u8> },
+    LoadEncounters {
+        date_id: String,
+    },
+    LoadEncounterDetail {
+        key: Vec<u8>,
+    },
+    AggregateDay {
+        date_id: String,
+    },
+    LoadZones {
+        zone: String,
+    },
+    LoadJobStats {
+        character_name: String,
+    },
+    ExportCsv {
+        record: Box<EncounterRecord>,
+    },
+    ExportFramesCsv {
+        record: Box<EncounterRecord>,
+    },
+    CopyText {
+        text: String,
+        /// What was copied, for the status message.
+        what: &'static str,
+    },
+    ExportJson {
+        key: Vec<u8>,
+        path: PathBuf,
+        include_frames: bool,
+    },
+    DeleteEncounter {
+        date_id: String,
+        key: Vec<u8>,
+    },
+    SetPinned {
+        key: Vec<u8>,
+        pinned: bool,
+    },
+    SetNote {
+        key: Vec<u8>,
+        note: Option<String>,
+    },
+    ClearAll {
+        keep_pinned: bool,
+    },
+    Prune {
+        retention_days: u64,
+    },
+    /// The recent list, plus the date list when the panel was opened straight onto it.
+    LoadRecent {
+        include_dates: bool,
+    },
+    SearchNotes {
+        query: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = parse_cli()?;
-    init_tracing(&cli)?;
+    if let Some(name) = &cli.profile {
+        config::set_profile(name)?;
+    }
+    let log_lines = init_tracing(&cli)?;
+
+    if let Some(path) = &cli.import {
+        return import_encounter(path);
+    }
 
     // Shared app state
     let state = Arc::new(RwLock::new(AppState::default()));
 
     // History persistence (sled-backed)
-    let history_store = Arc::new(history::HistoryStore::open_default()?);
+    // A missing or locked database only costs persistence; the live meter keeps running.
+    let (history_store, history_notice) = match HistoryStore::open_default() {
+        Ok(store) => (store, None),
+        Err(err) => {
+            tracing::warn!(error = ?err, "history unavailable; using in-memory store");
+            let notice = format!("{err:#}. This session's encounters won't be saved.");
+            (HistoryStore::open_in_memory()?, Some(notice))
+        }
+    };
+    let history_store = Arc::new(history_store);
     let history_recorder = history::spawn_recorder(history_store.clone());
 
     // Load persisted configuration into state
-    let cfg = match config::load() {
-        Ok(c) => c,
+    // Stderr is hidden once the TUI starts, so load failures are also reported in the footer.
+    let (cfg, config_error) = match config::load() {
+        Ok(c) => (c, None),
         Err(err) => {
+            tracing::warn!(error = ?err, "failed to load config; using defaults");
             eprintln!("Failed to load config: {err:?}. Using defaults.");
-            config::AppConfig::default()
+            (config::AppConfig::default(), Some(format!("{err:#}")))
         }
     };
+    let (keymap, keymap_warnings) = Keymap::from_config(&cfg.keybindings);
+    for warning in keymap_warnings {
+        tracing::warn!(%warning, "keybinding conflict");
+        eprintln!("Keybinding conflict: {warning}");
+    }
+    for (mode, names) in [
+        ("dps", &cfg.columns.dps),
+        ("heal", &cfg.columns.heal),
+        ("tank", &cfg.columns.tank),
+    ] {
+        for warning in model::parse_table_columns(names).1 {
+            tracing::warn!(%warning, mode, "ignoring configured column");
+            eprintln!("Columns ({mode}): {warning}");
+        }
+    }
+    let color_depth = cli.color.unwrap_or_else(theme::ColorDepth::detect);
+    theme::set_color_depth(color_depth);
+    theme::set_unicode_glyphs(theme::detect_unicode());
     {
         let mut s = state.write().await;
         s.apply_settings(AppSettings::from(cfg.clone()));
+        s.compact |= cli.compact;
+        s.color_depth = color_depth;
+        s.key_help = keymap.help_entries();
+        s.log_lines = log_lines;
+        s.history_notice = history_notice;
+        history_recorder.set_filter(s.settings.recording_filter());
+        history_recorder.set_character_name(&s.settings.character_name);
+        history_recorder.set_max_frames(s.settings.max_frames as usize);
+        history_recorder.set_store_raw_frames(s.settings.store_raw_frames);
+        spawn_history_prune(history_store.clone(), s.settings.retention_days);
+        match session::load() {
+            Ok(session) => s.restore_session(session),
+            Err(err) => eprintln!("Failed to load session: {err:?}. Using defaults."),
+        }
+    }
+    if let Some(title) = history_recorder.recover_checkpoint().await {
+        state.write().await.request_resume(&title);
     }
 
     // WS event channel
     let (tx, mut rx) = mpsc::unbounded_channel::<AppEvent>();
     let event_tx = tx.clone();
+    if let Some(message) = config_error {
+        let _ = tx.send(AppEvent::ConfigError { message });
+    }
 
-    // Spawn WS client task (auto-connect and subscribe)
-    let ws_url = WS_URL_DEFAULT.to_string();
     let history_tx = history_recorder.clone();
     let ws_tx = tx.clone();
-    tokio::spawn(async move { ws_client::run(ws_url, ws_tx, history_tx).await });
+    // What the about panel reports as the data source; CLI > config file > default for the URL
+    let data_source = match &cli.replay_file {
+        Some(path) => format!("{} (replay file)", path.display()),
+        None => cli.url.clone().unwrap_or_else(|| cfg.ws_url.clone()),
+    };
+    if let Some(path) = &cli.replay_file {
+        // Capture playback replaces the WebSocket connection entirely
+        let lines = ws_client::read_capture(path)?;
+        tokio::spawn(async move { ws_client::run_capture(lines, ws_tx, history_tx).await });
+    } else {
+        // Spawn WS client task (auto-connect and subscribe)
+        let ws_url = data_source.clone();
+        ws_client::validate_url(&ws_url)?;
+        let ws_auth = ws_client::WsAuth::from_config(&cfg);
+        ws_client::handshake_request(&ws_url, &ws_auth)?;
+        let capture = cli
+            .capture
+            .as_deref()
+            .map(|path| ws_client::CaptureWriter::open(path)?.spawn())
+            .transpose()?;
+        let events = cfg.subscribe_events.clone();
+        if !events.is_empty() && !events.iter().any(|event| event == "CombatData") {
+            tracing::warn!(?events, "subscribe_events lacks CombatData");
+            eprintln!(
+                "subscribe_events does not include CombatData; the live table will stay empty"
+            );
+        }
+        tokio::spawn(async move {
+            ws_client::run(ws_url, ws_auth, events, capture, ws_tx, history_tx).await
+        });
+    }
 
     // TUI init
+    install_panic_hook();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -82,38 +240,137 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // App loop
-    let tick = Duration::from_millis(100);
+    let tick = state.read().await.settings.redraw_interval();
+    let mut last_tick = Instant::now();
     let mut last_draw = Instant::now();
     let mut running = true;
+    let mut exit_code = None;
+    let mut shutdown_rx = spawn_signal_listener();
+    // Dropping the sender stops the running replay driver
+    let mut replay_speed: Option<watch::Sender<u32>> = None;
 
     while running {
+        // SIGINT/SIGTERM take the same teardown path as `q`
+        if let Ok(code) = shutdown_rx.try_recv() {
+            exit_code = Some(code);
+            break;
+        }
+
         // Drain any incoming WS events into state
         while let Ok(evt) = rx.try_recv() {
             let mut s = state.write().await;
+            // A new zone filter drops the loaded lists, so reload the open day right away
+            let reload = matches!(evt, AppEvent::HistoryZonesLoaded { .. });
             s.apply(evt);
+            if reload {
+                if let Some(task) = determine_history_task(&mut s) {
+                    spawn_history_task(task, history_store.clone(), event_tx.clone());
+                }
+            }
         }
 
-        // Draw at most every tick interval or immediately on first loop
-        if last_draw.elapsed() >= tick {
-            let s = state.read().await.clone_snapshot();
-            terminal.draw(|f| ui::draw(f, &s))?;
-            last_draw = Instant::now();
+        // Refresh the idle highlights from history whenever idle mode begins
+        let entered_idle = state.write().await.observe_idle(Instant::now());
+        if entered_idle {
+            spawn_recent_encounter_load(history_store.clone(), event_tx.clone());
+        }
+        {
+            let now = Instant::now();
+            let mut s = state.write().await;
+            if s.idle_rotation_due(now) {
+                let next = ui_idle::rotate_scene(s.idle_scene, true);
+                s.set_idle_scene(next, now);
+            }
         }
 
-        // Non-blocking input with small timeout so we keep redrawing
-        if event::poll(Duration::from_millis(10))? {
-            match event::read()? {
-                Event::Key(key) => match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
+        // Once per tick, draw if anything changed (or the forced redraw is due)
+        if last_tick.elapsed() >= tick {
+            last_tick = Instant::now();
+            let snapshot = {
+                let mut s = state.write().await;
+                if s.take_dirty() || s.timer_running() || last_draw.elapsed() >= FORCED_REDRAW {
+                    Some(s.clone_snapshot())
+                } else {
+                    None
+                }
+            };
+            if let Some(s) = snapshot {
+                let mut page = None;
+                terminal.draw(|f| page = ui::draw(f, &s))?;
+                last_draw = Instant::now();
+                if let Some(rows) = page {
+                    state.write().await.set_table_page(rows);
+                }
+            }
+        }
+
+        // Wait for input until the next tick is due; keys still wake the loop immediately
+        if event::poll(tick.saturating_sub(last_tick.elapsed()))? {
+            let input = event::read()?;
+            // Any key, click or resize may change what is on screen
+            let history_open = {
+                let mut s = state.write().await;
+                s.dirty = true;
+                // Mouse motion alone doesn't count as use for `input_delays_idle`
+                if matches!(
+                    input,
+                    Event::Key(_)
+                        | Event::Mouse(MouseEvent {
+                            kind: MouseEventKind::Down(_)
+                                | MouseEventKind::ScrollUp
+                                | MouseEventKind::ScrollDown,
+                            ..
+                        })
+                ) {
+                    s.note_input(Instant::now());
+                }
+                s.history.visible
+            };
+            match input {
+                // Raw mode turns Ctrl-C into a key press instead of SIGINT
+                Event::Key(key)
+                    if key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    exit_code = Some(EXIT_SIGINT);
+                    running = false;
+                }
+                Event::Key(_) if state.write().await.dismiss_help() => {}
+                Event::Key(key) if handle_about_key(key.code, &keymap, &state).await => {}
+                Event::Key(key) if handle_logs_key(key.code, &keymap, &state).await => {}
+                Event::Key(key)
+                    if handle_settings_input_key(key.code, &state, &history_recorder).await => {}
+                Event::Key(key)
+                    if handle_confirm_key(
+                        key.code,
+                        &state,
+                        &history_store,
+                        &history_recorder,
+                        &event_tx,
+                    )
+                    .await => {}
+                Event::Key(key) if handle_history_filter_key(key.code, &state).await => {}
+                Event::Key(key)
+                    if handle_history_note_key(key, &state, &history_store, &event_tx).await => {}
+                Event::Key(key)
+                    if handle_note_search_key(key.code, &state, &history_store, &event_tx)
+                        .await => {}
+                Event::Key(key) => match keymap.action_for(key.code, history_open) {
+                    Some(Action::Quit) => {
                         let mut s = state.write().await;
                         if s.history.visible {
                             s.history.visible = false;
                             s.history.reset();
+                        } else if s.stop_replay() {
+                            replay_speed = None;
+                            s.apply(AppEvent::StatusMessage {
+                                message: "Replay stopped".to_string(),
+                            });
                         } else {
                             running = false;
                         }
                     }
-                    KeyCode::Char('h') => {
+                    Some(Action::ToggleHistory) => {
                         let should_load = {
                             let mut s = state.write().await;
                             if s.toggle_history() {
@@ -128,8 +385,9 @@ async fn main() -> Result<()> {
                             let tx = event_tx.clone();
                             tokio::spawn(async move {
                                 match task::spawn_blocking(move || store.load_dates()).await {
-                                    Ok(Ok(days)) => {
-                                        let _ = tx.send(AppEvent::HistoryDatesLoaded { days });
+                                    Ok(Ok((days, skipped))) => {
+                                        let _ =
+                                            tx.send(AppEvent::HistoryDatesLoaded { days, skipped });
                                     }
                                     Ok(Err(err)) => {
                                         let _ = tx.send(AppEvent::HistoryError {
@@ -145,7 +403,7 @@ async fn main() -> Result<()> {
                             });
                         }
                     }
-                    KeyCode::Char('i') => {
+                    Some(Action::ToggleIdle) => {
                         let mut s = state.write().await;
                         if !s.history.visible {
                             let now = Instant::now();
@@ -154,21 +412,67 @@ async fn main() -> Result<()> {
                             }
                         }
                     }
-                    _ => {
+                    action => {
                         let mut pending_task = None;
+                        let mut replay_frames = None;
                         let history_active = {
                             let mut s = state.write().await;
                             if s.history.visible {
+                                let mut action_task = None;
                                 match key.code {
                                     KeyCode::Up => s.history_move_selection(-1),
                                     KeyCode::Down => s.history_move_selection(1),
                                     KeyCode::PageUp => s.history_move_selection(-5),
                                     KeyCode::PageDown => s.history_move_selection(5),
+                                    KeyCode::Home => s.history_move_to_edge(false),
+                                    KeyCode::End => s.history_move_to_edge(true),
                                     KeyCode::Left | KeyCode::Backspace => s.history_back(),
                                     KeyCode::Right | KeyCode::Enter => s.history_enter(),
+                                    KeyCode::Char('e') => action_task = determine_export_task(&s),
+                                    KeyCode::Char('E') => {
+                                        action_task =
+                                            s.history_request_frames_export().map(|record| {
+                                                HistoryTask::ExportFramesCsv {
+                                                    record: Box::new(record),
+                                                }
+                                            })
+                                    }
+                                    KeyCode::Char('j') => {
+                                        action_task = determine_json_export_task(&s)
+                                    }
+                                    KeyCode::Char('y') => action_task = determine_copy_task(&s),
+                                    KeyCode::Char('w') => {
+                                        action_task = determine_search_link_task(&mut s)
+                                    }
+                                    KeyCode::Char('d') => s.history_request_delete(),
+                                    KeyCode::Char('X') => s.history_request_clear_all(),
+                                    KeyCode::Char('P') => s.history_request_prune(),
+                                    KeyCode::Char('p') => {
+                                        action_task = s.history_toggle_pin().map(|(key, pinned)| {
+                                            HistoryTask::SetPinned { key, pinned }
+                                        })
+                                    }
+                                    KeyCode::Char('a') => s.history_open_day_summary(),
+                                    KeyCode::Char('t') => s.history_open_job_stats(),
+                                    KeyCode::Char('L') => s.history_open_recent(),
+                                    KeyCode::Char('o') => s.history_cycle_job_stats_sort(),
+                                    KeyCode::Char('z') => {
+                                        action_task = s
+                                            .history_toggle_zone_filter()
+                                            .map(|zone| HistoryTask::LoadZones { zone })
+                                    }
+                                    KeyCode::Char('/')
+                                        if s.history.level == HistoryPanelLevel::Dates =>
+                                    {
+                                        s.history_open_filter()
+                                    }
+                                    KeyCode::Char('/') => s.history_open_note_search(),
+                                    KeyCode::Char('n') => s.history_begin_note(),
+                                    KeyCode::Char('r') => replay_frames = s.history_start_replay(),
                                     _ => {}
                                 }
-                                pending_task = determine_history_task(&mut s);
+                                pending_task =
+                                    action_task.or_else(|| determine_history_task(&mut s));
                                 true
                             } else {
                                 false
@@ -179,39 +483,133 @@ async fn main() -> Result<()> {
                             spawn_history_task(task, history_store.clone(), event_tx.clone());
                         }
 
+                        if let Some(frames) = replay_frames {
+                            replay_speed = Some(spawn_replay(frames, event_tx.clone()));
+                        }
+
                         if history_active {
                             continue;
                         }
 
-                        match key.code {
-                            KeyCode::Char('d') => {
+                        match (action, key.code) {
+                            (Some(Action::CycleDecoration), _) => {
                                 let mut s = state.write().await;
                                 s.decoration = s.decoration.next();
                             }
-                            KeyCode::Char('m') => {
+                            (Some(Action::CycleMode), _) => {
                                 let mut s = state.write().await;
                                 s.mode = s.mode.next();
                             }
-                            KeyCode::Char('s') => {
+                            (_, KeyCode::Char('o')) => {
+                                let mut s = state.write().await;
+                                s.sort_key = s.sort_key.next();
+                            }
+                            (_, KeyCode::Char('[') | KeyCode::Char(']') | KeyCode::Tab) => {
+                                let forward = key.code != KeyCode::Char('[');
+                                let mut s = state.write().await;
+                                if s.show_idle_overlay && s.is_idle_at(Instant::now()) {
+                                    let next = ui_idle::rotate_scene(s.idle_scene, forward);
+                                    s.set_idle_scene(next, Instant::now());
+                                }
+                            }
+                            (_, KeyCode::Char('L'))
+                                if state.write().await.open_recent_history() =>
+                            {
+                                spawn_history_task(
+                                    HistoryTask::LoadRecent {
+                                        include_dates: true,
+                                    },
+                                    history_store.clone(),
+                                    event_tx.clone(),
+                                );
+                            }
+                            (_, KeyCode::Char('f')) => {
+                                history_recorder.end_encounter();
+                                let mut s = state.write().await;
+                                s.apply(AppEvent::StatusMessage {
+                                    message: "Ended the current encounter; saving it to history"
+                                        .to_string(),
+                                });
+                            }
+                            (_, KeyCode::Char('+') | KeyCode::Char('-')) => {
+                                let faster = key.code == KeyCode::Char('+');
+                                let speed = state.write().await.change_replay_speed(faster);
+                                if let (Some(speed), Some(sender)) = (speed, &replay_speed) {
+                                    let _ = sender.send(speed);
+                                }
+                            }
+                            (_, KeyCode::Char(' ')) => {
+                                let mut s = state.write().await;
+                                s.toggle_pause();
+                            }
+                            (Some(Action::ToggleAnonymize), _) => {
+                                let settings = {
+                                    let mut s = state.write().await;
+                                    s.toggle_anonymize();
+                                    s.settings.clone()
+                                };
+                                persist_settings(settings, &history_recorder);
+                            }
+                            (Some(Action::ToggleCompact), _) => {
+                                let settings = {
+                                    let mut s = state.write().await;
+                                    s.toggle_compact();
+                                    s.settings.clone()
+                                };
+                                persist_settings(settings, &history_recorder);
+                            }
+                            (Some(Action::ToggleHeader), _) => {
+                                let settings = {
+                                    let mut s = state.write().await;
+                                    s.toggle_header();
+                                    s.settings.clone()
+                                };
+                                persist_settings(settings, &history_recorder);
+                            }
+                            (Some(Action::ToggleFooter), _) => {
+                                let settings = {
+                                    let mut s = state.write().await;
+                                    s.toggle_footer();
+                                    s.settings.clone()
+                                };
+                                persist_settings(settings, &history_recorder);
+                            }
+                            (Some(Action::Help), _) => {
+                                state.write().await.show_help = true;
+                            }
+                            (Some(Action::Logs), _) => state.write().await.toggle_logs(),
+                            (Some(Action::About), _) => {
+                                state.write().await.open_about(&data_source);
+                                let store = history_store.clone();
+                                let tx = event_tx.clone();
+                                tokio::spawn(async move {
+                                    if let Ok(count) =
+                                        task::spawn_blocking(move || store.encounter_count()).await
+                                    {
+                                        let _ = tx.send(AppEvent::HistoryEncounterCount { count });
+                                    }
+                                });
+                            }
+                            (Some(Action::Settings), _) => {
                                 let mut s = state.write().await;
                                 s.show_settings = !s.show_settings;
                                 if s.show_settings {
                                     s.settings_cursor = SettingsField::default();
                                 }
                             }
-                            KeyCode::Up => {
+                            (_, KeyCode::Up) => {
                                 let mut s = state.write().await;
                                 if s.show_settings {
                                     s.prev_setting();
                                 }
                             }
-                            KeyCode::Down => {
+                            (_, KeyCode::Down) => {
                                 let mut s = state.write().await;
                                 if s.show_settings {
                                     s.next_setting();
                                 }
                             }
-                            KeyCode::Left | KeyCode::Right => {
+                            (_, KeyCode::Left | KeyCode::Right) => {
                                 let forward = matches!(key.code, KeyCode::Right);
                                 let updated = {
                                     let mut s = state.write().await;
@@ -222,18 +620,40 @@ async fn main() -> Result<()> {
                                     }
                                 };
                                 if let Some(settings) = updated {
-                                    let cfg: config::AppConfig = settings.into();
-                                    if let Err(err) = config::save(&cfg) {
-                                        eprintln!("Failed to save config: {err:?}");
+                                    persist_settings(settings, &history_recorder);
+                                }
+                            }
+                            (_, KeyCode::PageUp | KeyCode::PageDown) => {
+                                let forward = matches!(key.code, KeyCode::PageUp);
+                                let updated = {
+                                    let mut s = state.write().await;
+                                    if s.show_settings {
+                                        s.adjust_selected_setting_page(forward)
+                                            .then(|| s.settings.clone())
+                                    } else {
+                                        if s.table_in_front(Instant::now()) {
+                                            s.scroll_table(if forward { -5 } else { 5 });
+                                        }
+                                        None
                                     }
+                                };
+                                if let Some(settings) = updated {
+                                    persist_settings(settings, &history_recorder);
                                 }
                             }
+                            (_, KeyCode::Enter) => {
+                                state.write().await.settings_begin_input();
+                            }
                             _ => {}
                         }
                     }
                 },
                 Event::Mouse(mouse) => {
+                    if let Some(settings) = handle_settings_mouse(mouse, &state).await {
+                        persist_settings(settings, &history_recorder);
+                    }
                     handle_history_mouse(mouse, &state).await;
+                    handle_table_mouse(mouse, &state).await;
                     let mut s = state.write().await;
                     if s.history.visible {
                         if let Some(task) = determine_history_task(&mut s) {
@@ -246,21 +666,91 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Restore terminal
+    restore_terminal()?;
+    let session = state.read().await.session_state();
+    if let Err(err) = session::save(&session) {
+        eprintln!("Failed to save session: {err:?}");
+    }
+    history_recorder.shutdown().await;
+    if let Some(code) = exit_code {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Conventional 128 + signal number exit codes.
+const EXIT_SIGINT: i32 = 130;
+#[cfg(unix)]
+const EXIT_SIGTERM: i32 = 143;
+
+/// Resolves with the exit code once SIGINT (or SIGTERM on Unix) arrives.
+fn spawn_signal_listener() -> oneshot::Receiver<i32> {
+    let (tx, rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let code = wait_for_signal().await;
+        tracing::info!(code, "shutdown signal received");
+        let _ = tx.send(code);
+    });
+    rx
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> i32 {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut sigterm = match signal(SignalKind::terminate()) {
+        Ok(sigterm) => sigterm,
+        Err(err) => {
+            tracing::warn!(error = ?err, "failed to listen for SIGTERM");
+            let _ = tokio::signal::ctrl_c().await;
+            return EXIT_SIGINT;
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => EXIT_SIGINT,
+        _ = sigterm.recv() => EXIT_SIGTERM,
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> i32 {
+    let _ = tokio::signal::ctrl_c().await;
+    EXIT_SIGINT
+}
+
+/// Undoes the TUI setup: raw mode, alternate screen, mouse capture and the hidden cursor.
+fn restore_terminal() -> io::Result<()> {
     disable_raw_mode()?;
     execute!(
-        terminal.backend_mut(),
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    history_recorder.shutdown().await;
-    Ok(())
+        DisableMouseCapture,
+        cursor::Show
+    )
+}
+
+/// Restores the terminal before the default hook prints, so a panic in draw code leaves a
+/// usable shell and a readable message. Only the main thread runs the UI loop; a panic in a
+/// spawned task is caught by its runtime and the UI keeps drawing, so the terminal stays as is.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().name() == Some("main") {
+            let _ = restore_terminal();
+        }
+        default_hook(info);
+    }));
 }
 
 #[derive(Debug, Default)]
 struct CliArgs {
     debug: Option<DebugTarget>,
+    url: Option<String>,
+    color: Option<theme::ColorDepth>,
+    import: Option<PathBuf>,
+    replay_file: Option<PathBuf>,
+    capture: Option<PathBuf>,
+    compact: bool,
+    profile: Option<String>,
 }
 
 #[derive(Debug)]
@@ -272,6 +762,13 @@ enum DebugTarget {
 fn parse_cli() -> Result<CliArgs> {
     let mut args = env::args().skip(1).peekable();
     let mut debug = None;
+    let mut url = None;
+    let mut color = None;
+    let mut import = None;
+    let mut replay_file = None;
+    let mut capture = None;
+    let mut compact = false;
+    let mut profile = None;
 
     while let Some(arg) = args.next() {
         if arg == "--debug" {
@@ -298,57 +795,414 @@ fn parse_cli() -> Result<CliArgs> {
             } else {
                 debug = Some(DebugTarget::Path(PathBuf::from(rest)));
             }
+        } else if arg == "--url" {
+            if url.is_some() {
+                bail!("`--url` specified more than once");
+            }
+            match args.next() {
+                Some(value) if !value.starts_with('-') => url = Some(value),
+                _ => bail!("`--url` requires a value, e.g. `--url ws://127.0.0.1:10501/ws`"),
+            }
+        } else if let Some(rest) = arg.strip_prefix("--url=") {
+            if url.is_some() {
+                bail!("`--url` specified more than once");
+            }
+            if rest.is_empty() {
+                bail!("`--url` requires a value, e.g. `--url=ws://127.0.0.1:10501/ws`");
+            }
+            url = Some(rest.to_string());
+        } else if arg == "--color" || arg.starts_with("--color=") {
+            if color.is_some() {
+                bail!("`--color` specified more than once");
+            }
+            let value = match arg.strip_prefix("--color=") {
+                Some(rest) => Some(rest.to_string()),
+                None => args.next(),
+            };
+            color = match value.as_deref().and_then(theme::ColorDepth::from_cli_value) {
+                Some(depth) => Some(depth),
+                None => bail!("`--color` expects one of `truecolor`, `256`, `16`"),
+            };
+        } else if arg == "--import" || arg.starts_with("--import=") {
+            if import.is_some() {
+                bail!("`--import` specified more than once");
+            }
+            let value = match arg.strip_prefix("--import=") {
+                Some(rest) => Some(rest.to_string()),
+                None => args.next(),
+            };
+            match value {
+                Some(path) if !path.is_empty() => import = Some(PathBuf::from(path)),
+                _ => bail!("`--import` requires a path to an exported encounter JSON file"),
+            }
+        } else if arg == "--replay-file" || arg.starts_with("--replay-file=") {
+            if replay_file.is_some() {
+                bail!("`--replay-file` specified more than once");
+            }
+            let value = match arg.strip_prefix("--replay-file=") {
+                Some(rest) => Some(rest.to_string()),
+                None => args.next(),
+            };
+            match value {
+                Some(path) if !path.is_empty() => replay_file = Some(PathBuf::from(path)),
+                _ => bail!("`--replay-file` requires a path to a newline-delimited JSON capture"),
+            }
+        } else if arg == "--capture" || arg.starts_with("--capture=") {
+            if capture.is_some() {
+                bail!("`--capture` specified more than once");
+            }
+            let value = match arg.strip_prefix("--capture=") {
+                Some(rest) => Some(rest.to_string()),
+                None => args.next(),
+            };
+            match value {
+                Some(path) if !path.is_empty() => capture = Some(PathBuf::from(path)),
+                _ => bail!("`--capture` requires a path to write received frames to"),
+            }
+        } else if arg == "--compact" {
+            compact = true;
+        } else if arg == "--profile" || arg.starts_with("--profile=") {
+            if profile.is_some() {
+                bail!("`--profile` specified more than once");
+            }
+            let value = match arg.strip_prefix("--profile=") {
+                Some(rest) => Some(rest.to_string()),
+                None => args.next(),
+            };
+            match value {
+                Some(name) if !name.is_empty() && !name.starts_with('-') => profile = Some(name),
+                _ => bail!("`--profile` requires a name, e.g. `--profile alt`"),
+            }
         } else {
             bail!("unknown argument: {arg}");
         }
     }
 
-    Ok(CliArgs { debug })
+    if url.is_some() && replay_file.is_some() {
+        bail!("`--url` and `--replay-file` cannot be used together");
+    }
+    if capture.is_some() && replay_file.is_some() {
+        bail!("`--capture` and `--replay-file` cannot be used together");
+    }
+
+    Ok(CliArgs {
+        debug,
+        url,
+        color,
+        import,
+        replay_file,
+        capture,
+        compact,
+        profile,
+    })
+}
+
+/// `--import <path>`: stores an exported encounter in history and exits without opening the TUI.
+fn import_encounter(path: &Path) -> Result<()> {
+    let store = HistoryStore::open_default()?;
+    store.import_record_json(path)?;
+    store.flush()?;
+    println!("Imported {} into encounter history", path.display());
+    Ok(())
 }
 
-fn init_tracing(cli: &CliArgs) -> Result<()> {
-    if let Some(target) = &cli.debug {
-        let log_path = match target {
-            DebugTarget::Default => config::config_dir().join("debug.log"),
-            DebugTarget::Path(path) => path.clone(),
-        };
+/// Always feeds info and above into the in-app log pane; `--debug` adds a log file.
+fn init_tracing(cli: &CliArgs) -> Result<LogLines> {
+    let lines = LogLines::default();
+    let buffer = BufferLayer::new(lines.clone()).with_filter(LevelFilter::INFO);
 
-        if let Some(parent) = log_path.parent() {
-            if !parent.as_os_str().is_empty() {
-                create_dir_all(parent).with_context(|| {
-                    format!("failed to create log directory {}", parent.display())
-                })?;
+    let log_path = cli.debug.as_ref().map(|target| match target {
+        DebugTarget::Default => config::profile_dir().join("debug.log"),
+        DebugTarget::Path(path) => path.clone(),
+    });
+    let file_layer = match &log_path {
+        Some(log_path) => {
+            if let Some(parent) = log_path.parent() {
+                if !parent.as_os_str().is_empty() {
+                    create_dir_all(parent).with_context(|| {
+                        format!("failed to create log directory {}", parent.display())
+                    })?;
+                }
             }
-        }
 
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .write(true)
-            .open(&log_path)
-            .with_context(|| format!("failed to open log file {}", log_path.display()))?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(log_path)
+                .with_context(|| format!("failed to open log file {}", log_path.display()))?;
 
-        let subscriber = tracing_subscriber::fmt()
-            .with_writer(move || file.try_clone().expect("failed to clone log file handle"))
-            .with_ansi(false)
-            .with_target(false)
-            .with_max_level(LevelFilter::DEBUG);
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(move || file.try_clone().expect("failed to clone log file handle"))
+                    .with_ansi(false)
+                    .with_target(false)
+                    .with_filter(LevelFilter::DEBUG),
+            )
+        }
+        None => None,
+    };
 
-        subscriber.try_init().map_err(|err| {
-            anyhow::anyhow!(
+    tracing_subscriber::registry()
+        .with(buffer)
+        .with(file_layer)
+        .try_init()
+        .map_err(|err| match &log_path {
+            Some(path) => anyhow::anyhow!(
                 "failed to initialize logging to {}: {}",
-                log_path.display(),
+                path.display(),
                 err
-            )
+            ),
+            None => anyhow::anyhow!("failed to initialize logging: {err}"),
         })?;
+
+    Ok(lines)
+}
+
+/// Applies the recording filter and writes the settings to the config file.
+fn persist_settings(settings: AppSettings, history_recorder: &history::RecorderHandle) {
+    history_recorder.set_filter(settings.recording_filter());
+    history_recorder.set_character_name(&settings.character_name);
+    history_recorder.set_max_frames(settings.max_frames as usize);
+    history_recorder.set_store_raw_frames(settings.store_raw_frames);
+    let cfg: config::AppConfig = settings.into();
+    if let Err(err) = config::save(&cfg) {
+        eprintln!("Failed to save config: {err:?}");
     }
+}
 
-    Ok(())
+/// Clicking a settings row selects it; the wheel over the modal adjusts the selected value.
+/// Returns the new settings when a value changed and needs saving.
+async fn handle_settings_mouse(
+    mouse: MouseEvent,
+    state: &Arc<RwLock<AppState>>,
+) -> Option<AppSettings> {
+    let mut s = state.write().await;
+    if !s.show_settings || s.history.visible {
+        return None;
+    }
+    let (cols, rows) = crossterm::terminal::size().ok()?;
+    let screen = ratatui::layout::Rect::new(0, 0, cols, rows);
+    let area = ui::settings_area(screen);
+    let over_modal = mouse.column >= area.x
+        && mouse.column < area.right()
+        && mouse.row >= area.y
+        && mouse.row < area.bottom();
+    if !over_modal {
+        return None;
+    }
+    let forward = match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(field) = ui::settings_field_at(screen, mouse.row, s.settings_cursor) {
+                s.settings_cursor = field;
+            }
+            return None;
+        }
+        MouseEventKind::ScrollUp => true,
+        MouseEventKind::ScrollDown => false,
+        _ => return None,
+    };
+    if s.adjust_selected_setting(forward) {
+        Some(s.settings.clone())
+    } else {
+        None
+    }
+}
+
+/// Routes keys into the idle timeout entry while it is open; Enter commits and saves.
+async fn handle_settings_input_key(
+    code: KeyCode,
+    state: &Arc<RwLock<AppState>>,
+    history_recorder: &history::RecorderHandle,
+) -> bool {
+    let mut s = state.write().await;
+    if !s.settings_input_editing() {
+        return false;
+    }
+    match code {
+        KeyCode::Char(ch) => s.settings_input_push(ch),
+        KeyCode::Backspace => s.settings_input_pop(),
+        KeyCode::Esc => s.settings_cancel_input(),
+        KeyCode::Enter if s.settings_commit_input() => {
+            persist_settings(s.settings.clone(), history_recorder);
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Keeps keys inside the about panel while it is open; Esc or its own key closes it.
+async fn handle_about_key(code: KeyCode, keymap: &Keymap, state: &Arc<RwLock<AppState>>) -> bool {
+    let mut s = state.write().await;
+    if s.about.is_none() {
+        return false;
+    }
+    if code == KeyCode::Esc || keymap.action(code) == Some(Action::About) {
+        s.about = None;
+    }
+    true
+}
+
+/// Scrolls the log pane while it is open; Esc or its own key closes it.
+async fn handle_logs_key(code: KeyCode, keymap: &Keymap, state: &Arc<RwLock<AppState>>) -> bool {
+    let mut s = state.write().await;
+    if !s.show_logs {
+        return false;
+    }
+    match code {
+        KeyCode::Up => s.scroll_logs(1),
+        KeyCode::Down => s.scroll_logs(-1),
+        KeyCode::PageUp => s.scroll_logs(10),
+        KeyCode::PageDown => s.scroll_logs(-10),
+        KeyCode::Home => s.scroll_logs(isize::MAX),
+        KeyCode::End => s.log_scroll = 0,
+        KeyCode::Esc => s.toggle_logs(),
+        _ if keymap.action(code) == Some(Action::Logs) => s.toggle_logs(),
+        _ => {}
+    }
+    true
+}
+
+/// Answers the confirmation modal while it is open: `y`/Enter runs the pending action, `n`/Esc
+/// drops it, and other keys are swallowed so nothing happens behind the modal. A modal that
+/// asks for a typed word takes characters as text instead, and Tab toggles keeping pinned
+/// encounters when wiping history.
+async fn handle_confirm_key(
+    code: KeyCode,
+    state: &Arc<RwLock<AppState>>,
+    store: &Arc<HistoryStore>,
+    history_recorder: &history::RecorderHandle,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> bool {
+    let mut s = state.write().await;
+    if !s.confirm_open() {
+        return false;
+    }
+    let typing = s.confirm_typing();
+    match code {
+        KeyCode::Char(ch) if typing => s.confirm_push(ch),
+        KeyCode::Backspace if typing => s.confirm_pop(),
+        KeyCode::Tab => s.confirm_toggle_keep_pinned(),
+        KeyCode::Char('y' | 'Y') | KeyCode::Enter => {
+            let task = match s.confirm_accept() {
+                Some(PendingAction::ResumeEncounter) => {
+                    history_recorder.resolve_recovery(true);
+                    return true;
+                }
+                Some(PendingAction::DeleteEncounter { date_id, key }) => {
+                    HistoryTask::DeleteEncounter { date_id, key }
+                }
+                Some(PendingAction::PruneHistory { retention_days }) => {
+                    HistoryTask::Prune { retention_days }
+                }
+                Some(PendingAction::ClearAll { keep_pinned }) => {
+                    HistoryTask::ClearAll { keep_pinned }
+                }
+                None => return true,
+            };
+            spawn_history_task(task, store.clone(), tx.clone());
+        }
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+            if let Some(PendingAction::ResumeEncounter) = s.confirm_cancel() {
+                history_recorder.resolve_recovery(false);
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Routes typing into the history date filter; returns true when the key was consumed.
+async fn handle_history_filter_key(code: KeyCode, state: &Arc<RwLock<AppState>>) -> bool {
+    let mut s = state.write().await;
+    if !s.history_filter_editing() {
+        return false;
+    }
+    match code {
+        KeyCode::Char(ch) => s.history_filter_push(ch),
+        KeyCode::Backspace => s.history_filter_pop(),
+        KeyCode::Esc => s.history_clear_filter(),
+        KeyCode::Tab => s.history_filter_to_note_search(),
+        _ => return false,
+    }
+    true
+}
+
+/// Line editing for the encounter note: Enter saves, Esc cancels, Ctrl-U clears the line and
+/// Ctrl-W drops the last word. Returns true when the key was consumed.
+async fn handle_history_note_key(
+    key: KeyEvent,
+    state: &Arc<RwLock<AppState>>,
+    store: &Arc<HistoryStore>,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> bool {
+    let mut s = state.write().await;
+    if !s.history_note_editing() {
+        return false;
+    }
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('u') if ctrl => s.history_note_clear(),
+        KeyCode::Char('w') if ctrl => s.history_note_delete_word(),
+        KeyCode::Char(ch) => s.history_note_push(ch),
+        KeyCode::Backspace => s.history_note_pop(),
+        KeyCode::Esc => s.history_cancel_note(),
+        KeyCode::Enter => {
+            if let Some((key, note)) = s.history_commit_note() {
+                spawn_history_task(
+                    HistoryTask::SetNote { key, note },
+                    store.clone(),
+                    tx.clone(),
+                );
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Routes typing into the note search query; Enter runs the search in a blocking task.
+async fn handle_note_search_key(
+    code: KeyCode,
+    state: &Arc<RwLock<AppState>>,
+    store: &Arc<HistoryStore>,
+    tx: &mpsc::UnboundedSender<AppEvent>,
+) -> bool {
+    let mut s = state.write().await;
+    if !s.history_note_search_editing() {
+        return false;
+    }
+    match code {
+        KeyCode::Char(ch) => s.history_note_search_push(ch),
+        KeyCode::Backspace => s.history_note_search_pop(),
+        KeyCode::Esc => s.history_cancel_note_search(),
+        KeyCode::Enter => {
+            s.history_submit_note_search();
+            if let Some(task) = determine_history_task(&mut s) {
+                spawn_history_task(task, store.clone(), tx.clone());
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+/// The wheel scrolls the live table while nothing is open over it.
+async fn handle_table_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>) {
+    let mut s = state.write().await;
+    if !s.table_in_front(Instant::now()) {
+        return;
+    }
+    match mouse.kind {
+        MouseEventKind::ScrollDown => s.scroll_table(1),
+        MouseEventKind::ScrollUp => s.scroll_table(-1),
+        _ => {}
+    }
 }
 
 async fn handle_history_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>) {
     let mut s = state.write().await;
-    if !s.history.visible || s.history.loading {
+    // The note editor owns the panel until it is saved or cancelled
+    if !s.history.visible || s.history.loading || s.history_note_editing() {
         return;
     }
 
@@ -359,9 +1213,9 @@ async fn handle_history_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>)
             let index = mouse.row.saturating_sub(HISTORY_LIST_OFFSET) as usize;
             match s.history.level {
                 HistoryPanelLevel::Dates => {
-                    if !s.history.days.is_empty() {
-                        let max_index = s.history.days.len().saturating_sub(1);
-                        s.history.selected_day = index.min(max_index);
+                    let visible = s.history.visible_day_indices();
+                    if let Some(&last) = visible.last() {
+                        s.history.selected_day = visible.get(index).copied().unwrap_or(last);
                     }
                     s.history_enter();
                 }
@@ -374,7 +1228,28 @@ async fn handle_history_mouse(mouse: MouseEvent, state: &Arc<RwLock<AppState>>)
                         }
                     }
                 }
-                HistoryPanelLevel::EncounterDetail => {}
+                HistoryPanelLevel::Recent => {
+                    let len = s.history.recent.as_ref().map_or(0, Vec::len);
+                    if len > 0 {
+                        s.history.selected_recent = index.min(len - 1);
+                        s.history_enter();
+                    }
+                }
+                HistoryPanelLevel::NoteSearch => {
+                    let len = s
+                        .history
+                        .note_search
+                        .as_ref()
+                        .and_then(|search| search.results.as_ref())
+                        .map_or(0, Vec::len);
+                    if let Some(search) = s.history.note_search.as_mut().filter(|_| len > 0) {
+                        search.selected = index.min(len - 1);
+                        s.history_enter();
+                    }
+                }
+                HistoryPanelLevel::EncounterDetail
+                | HistoryPanelLevel::DaySummary
+                | HistoryPanelLevel::JobStats => {}
             }
         }
         _ => {}
@@ -388,18 +1263,7 @@ fn determine_history_task(state: &mut AppState) -> Option<HistoryTask> {
 
     match state.history.level {
         HistoryPanelLevel::Encounters => {
-            let need_load = state
-                .history
-                .current_day()
-                .filter(|day| !day.encounters_loaded)
-                .and_then(|day| {
-                    if day.encounter_ids.is_empty() {
-                        None
-                    } else {
-                        Some(day.iso_date.clone())
-                    }
-                });
-            if let Some(date_id) = need_load {
+            if let Some(date_id) = state.history.pending_encounter_load() {
                 state.history_set_loading();
                 return Some(HistoryTask::LoadEncounters { date_id });
             }
@@ -415,12 +1279,193 @@ fn determine_history_task(state: &mut AppState) -> Option<HistoryTask> {
                 return Some(HistoryTask::LoadEncounterDetail { key });
             }
         }
+        HistoryPanelLevel::DaySummary => {
+            let need_load = state
+                .history
+                .current_day()
+                .filter(|day| day.aggregate.is_none())
+                .map(|day| day.iso_date.clone());
+            if let Some(date_id) = need_load {
+                state.history_set_loading();
+                return Some(HistoryTask::AggregateDay { date_id });
+            }
+        }
+        HistoryPanelLevel::JobStats => {
+            if state.history.job_stats.is_none() {
+                state.history_set_loading();
+                return Some(HistoryTask::LoadJobStats {
+                    character_name: state.settings.character_name.clone(),
+                });
+            }
+        }
+        HistoryPanelLevel::Recent => {
+            if state.history.recent.is_none() {
+                state.history_set_loading();
+                return Some(HistoryTask::LoadRecent {
+                    include_dates: false,
+                });
+            }
+        }
+        HistoryPanelLevel::NoteSearch => {
+            let query = state
+                .history
+                .note_search
+                .as_ref()
+                .filter(|search| !search.editing && search.results.is_none())
+                .map(|search| search.query.trim().to_string())
+                .filter(|query| !query.is_empty());
+            if let Some(query) = query {
+                state.history_set_loading();
+                return Some(HistoryTask::SearchNotes { query });
+            }
+        }
         HistoryPanelLevel::Dates => {}
     }
 
     None
 }
 
+fn spawn_recent_encounter_load(store: Arc<HistoryStore>, tx: mpsc::UnboundedSender<AppEvent>) {
+    tokio::spawn(async move {
+        match task::spawn_blocking(move || store.load_latest_encounter()).await {
+            Ok(Ok(record)) => {
+                let _ = tx.send(AppEvent::RecentEncounterLoaded { record });
+            }
+            Ok(Err(err)) => {
+                tracing::warn!(error = ?err, "failed to load recent encounter for idle view");
+            }
+            Err(err) => {
+                tracing::warn!(error = ?err, "recent encounter load task failed");
+            }
+        }
+    });
+}
+
+/// Plays recorded frames back as `ReplayFrame` events, spaced like the original
+/// `received_ms` deltas divided by the current speed. Dropping the returned sender stops it.
+fn spawn_replay(
+    frames: Vec<EncounterFrame>,
+    tx: mpsc::UnboundedSender<AppEvent>,
+) -> watch::Sender<u32> {
+    let (speed_tx, mut speed_rx) = watch::channel(1u32);
+    tokio::spawn(async move {
+        let mut previous_ms = None;
+        for frame in frames {
+            if let Some(previous_ms) = previous_ms {
+                let gap = Duration::from_millis(frame.received_ms.saturating_sub(previous_ms))
+                    .min(REPLAY_MAX_GAP);
+                let speed = (*speed_rx.borrow_and_update()).max(1);
+                let sleep = tokio::time::sleep(gap / speed);
+                tokio::pin!(sleep);
+                loop {
+                    tokio::select! {
+                        _ = &mut sleep => break,
+                        changed = speed_rx.changed() => {
+                            if changed.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+            previous_ms = Some(frame.received_ms);
+            let event = AppEvent::ReplayFrame {
+                encounter: frame.encounter,
+                rows: frame.rows,
+            };
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+        // A stopped replay has already restored the live table
+        if speed_rx.has_changed().is_ok() {
+            let _ = tx.send(AppEvent::ReplayFinished);
+        }
+    });
+    speed_tx
+}
+
+/// Drops history past the retention window once per launch; 0 days keeps everything.
+fn spawn_history_prune(store: Arc<HistoryStore>, retention_days: u64) {
+    if retention_days == 0 {
+        return;
+    }
+    let cutoff_ms = retention_cutoff_ms(retention_days);
+    tokio::spawn(async move {
+        match task::spawn_blocking(move || store.prune_older_than(cutoff_ms)).await {
+            Ok(Ok((pruned, skipped))) => {
+                tracing::info!(
+                    pruned,
+                    skipped,
+                    retention_days,
+                    "pruned old encounter history"
+                );
+            }
+            Ok(Err(err)) => tracing::warn!(error = ?err, "failed to prune encounter history"),
+            Err(err) => tracing::warn!(error = ?err, "history prune task failed"),
+        }
+    });
+}
+
+/// Start of the retention window: encounters last seen before it are pruned.
+fn retention_cutoff_ms(retention_days: u64) -> u64 {
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    now_ms.saturating_sub(retention_days.saturating_mul(86_400_000))
+}
+
+fn determine_export_task(state: &AppState) -> Option<HistoryTask> {
+    if state.history.loading || state.history.level != HistoryPanelLevel::EncounterDetail {
+        return None;
+    }
+    let record = state.history.current_encounter()?.record.clone()?;
+    Some(HistoryTask::ExportCsv {
+        record: Box::new(record),
+    })
+}
+
+fn determine_copy_task(state: &AppState) -> Option<HistoryTask> {
+    if state.history.loading || state.history.level != HistoryPanelLevel::EncounterDetail {
+        return None;
+    }
+    let record = state.history.current_encounter()?.record.as_ref()?;
+    Some(HistoryTask::CopyText {
+        text: history::encounter_summary_text(record),
+        what: "encounter summary",
+    })
+}
+
+fn determine_search_link_task(state: &mut AppState) -> Option<HistoryTask> {
+    if state.history.loading || state.history.level != HistoryPanelLevel::EncounterDetail {
+        return None;
+    }
+    let template = state.settings.search_url_template.trim().to_string();
+    if template.is_empty() {
+        state.history.error = Some("Set search_url_template in the config file first".to_string());
+        return None;
+    }
+    let record = state.history.current_encounter()?.record.as_ref()?;
+    Some(HistoryTask::CopyText {
+        text: history::encounter_search_url(&template, record),
+        what: "encounter search link",
+    })
+}
+
+fn determine_json_export_task(state: &AppState) -> Option<HistoryTask> {
+    if state.history.loading || state.history.level != HistoryPanelLevel::EncounterDetail {
+        return None;
+    }
+    let item = state.history.current_encounter()?;
+    let record = item.record.as_ref()?;
+    Some(HistoryTask::ExportJson {
+        key: item.key.clone(),
+        path: history::json_export_path(record),
+        include_frames: state.settings.json_export_frames,
+    })
+}
+
 fn spawn_history_task(
     task: HistoryTask,
     store: Arc<HistoryStore>,
@@ -437,10 +1482,11 @@ fn spawn_history_task(
                 })
                 .await;
                 match result {
-                    Ok(Ok(encounters)) => {
+                    Ok(Ok((encounters, skipped))) => {
                         let _ = tx_enc.send(AppEvent::HistoryEncountersLoaded {
                             date_id,
                             encounters,
+                            skipped,
                         });
                     }
                     Ok(Err(err)) => {
@@ -481,5 +1527,362 @@ fn spawn_history_task(
                 }
             });
         }
+        HistoryTask::AggregateDay { date_id } => {
+            let tx_aggregate = tx.clone();
+            let store_clone = store.clone();
+            tokio::spawn(async move {
+                let date_for_block = date_id.clone();
+                let result =
+                    task::spawn_blocking(move || store_clone.aggregate_day(&date_for_block)).await;
+                match result {
+                    Ok(Ok(aggregate)) => {
+                        let _ = tx_aggregate
+                            .send(AppEvent::HistoryDayAggregated { date_id, aggregate });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx_aggregate.send(AppEvent::HistoryError {
+                            message: err.to_string(),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx_aggregate.send(AppEvent::HistoryError {
+                            message: format!("History load failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::LoadZones { zone } => {
+            tokio::spawn(async move {
+                match task::spawn_blocking(move || store.load_zones()).await {
+                    Ok(Ok((zones, skipped))) => {
+                        let _ = tx.send(AppEvent::HistoryZonesLoaded {
+                            zone,
+                            zones,
+                            skipped,
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: err.to_string(),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("History load failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::SearchNotes { query } => {
+            tokio::spawn(async move {
+                let search = query.clone();
+                let result = task::spawn_blocking(move || store.search_notes(&search)).await;
+                match result {
+                    Ok(Ok((encounters, skipped))) => {
+                        let _ = tx.send(AppEvent::HistoryNotesSearched {
+                            query,
+                            encounters,
+                            skipped,
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: err.to_string(),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("Note search failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::LoadRecent { include_dates } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || -> anyhow::Result<_> {
+                    let dates = if include_dates {
+                        Some(store.load_dates()?)
+                    } else {
+                        None
+                    };
+                    let recent = store.load_recent(RECENT_ENCOUNTERS_LIMIT)?;
+                    Ok((dates, recent))
+                })
+                .await;
+                match result {
+                    Ok(Ok((dates, (encounters, skipped)))) => {
+                        if let Some((days, skipped)) = dates {
+                            let _ = tx.send(AppEvent::HistoryDatesLoaded { days, skipped });
+                        }
+                        let _ = tx.send(AppEvent::HistoryRecentLoaded {
+                            encounters,
+                            skipped,
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: err.to_string(),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("History load failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::LoadJobStats { character_name } => {
+            tokio::spawn(async move {
+                match task::spawn_blocking(move || store.job_stats(&character_name)).await {
+                    Ok(Ok((stats, skipped))) => {
+                        let _ = tx.send(AppEvent::HistoryJobStatsLoaded { stats, skipped });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: err.to_string(),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("History load failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::ExportCsv { record } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || history::export_csv(&record)).await;
+                match result {
+                    Ok(Ok(path)) => {
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: format!("Exported CSV to {}", path.display()),
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("CSV export failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::ExportFramesCsv { record } => {
+            tokio::spawn(async move {
+                let result =
+                    task::spawn_blocking(move || history::export_frames_csv(&record)).await;
+                match result {
+                    Ok(Ok(path)) => {
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: format!("Exported frames CSV to {}", path.display()),
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("Frames CSV export failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::CopyText { text, what } => {
+            tokio::spawn(async move {
+                match task::spawn_blocking(move || clipboard::copy_text(&text)).await {
+                    Ok(Ok(())) => {
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: format!("Copied {what} to clipboard"),
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("Clipboard copy failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::ExportJson {
+            key,
+            path,
+            include_frames,
+        } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || {
+                    store
+                        .export_record_json(&key, &path, include_frames)
+                        .map(|_| path)
+                })
+                .await;
+                match result {
+                    Ok(Ok(path)) => {
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: format!("Exported JSON to {}", path.display()),
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("JSON export failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::SetPinned { key, pinned } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || store.set_pinned(&key, pinned)).await;
+                let message = match result {
+                    Ok(Ok(())) => {
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: if pinned { "Pinned" } else { "Unpinned" }.to_string(),
+                        });
+                        return;
+                    }
+                    Ok(Err(err)) => format!("{err:#}"),
+                    Err(err) => format!("Pin update failed: {err}"),
+                };
+                let _ = tx.send(AppEvent::HistoryError { message });
+            });
+        }
+        HistoryTask::SetNote { key, note } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || {
+                    store.set_note(&key, note.clone())?;
+                    Ok::<_, anyhow::Error>((key, note))
+                })
+                .await;
+                let message = match result {
+                    Ok(Ok((key, note))) => {
+                        let _ = tx.send(AppEvent::HistoryNoteSaved { key, note });
+                        return;
+                    }
+                    Ok(Err(err)) => format!("{err:#}"),
+                    Err(err) => format!("Note update failed: {err}"),
+                };
+                let _ = tx.send(AppEvent::HistoryError { message });
+            });
+        }
+        HistoryTask::DeleteEncounter { date_id, key } => {
+            tokio::spawn(async move {
+                // Delete and reload inside one blocking task so the refreshed lists reflect the
+                // store right after the write.
+                let result = task::spawn_blocking(move || -> anyhow::Result<_> {
+                    store.delete_encounter(&key)?;
+                    let (mut days, mut skipped) = store.load_dates()?;
+                    if let Some(day) = days.iter_mut().find(|day| day.iso_date == date_id) {
+                        let (encounters, skipped_encounters) =
+                            store.load_encounter_summaries(&date_id)?;
+                        day.encounters = encounters;
+                        day.encounters_loaded = true;
+                        skipped += skipped_encounters;
+                    }
+                    Ok((days, skipped))
+                })
+                .await;
+                match result {
+                    Ok(Ok((days, skipped))) => {
+                        let _ = tx.send(AppEvent::HistoryDatesLoaded { days, skipped });
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: "Encounter deleted".to_string(),
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("History delete failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::ClearAll { keep_pinned } => {
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || -> anyhow::Result<_> {
+                    let removed = store.clear_all(keep_pinned)?;
+                    let (days, skipped) = store.load_dates()?;
+                    Ok((removed, days, skipped))
+                })
+                .await;
+                match result {
+                    Ok(Ok((removed, days, skipped))) => {
+                        let _ = tx.send(AppEvent::HistoryDatesLoaded { days, skipped });
+                        let _ = tx.send(AppEvent::StatusMessage {
+                            message: format!("Cleared {removed} encounters from history"),
+                        });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("History clear failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
+        HistoryTask::Prune { retention_days } => {
+            let cutoff_ms = retention_cutoff_ms(retention_days);
+            tokio::spawn(async move {
+                let result = task::spawn_blocking(move || -> anyhow::Result<_> {
+                    let (pruned, unreadable) = store.prune_older_than(cutoff_ms)?;
+                    let (days, skipped) = store.load_dates()?;
+                    Ok((pruned, unreadable, days, skipped))
+                })
+                .await;
+                match result {
+                    Ok(Ok((pruned, unreadable, days, skipped))) => {
+                        let _ = tx.send(AppEvent::HistoryDatesLoaded { days, skipped });
+                        let message = if unreadable == 0 {
+                            format!("Pruned {pruned} encounters from history")
+                        } else {
+                            format!(
+                                "Pruned {pruned} encounters from history; skipped {unreadable} unreadable entries (see log)"
+                            )
+                        };
+                        let _ = tx.send(AppEvent::StatusMessage { message });
+                    }
+                    Ok(Err(err)) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("{err:#}"),
+                        });
+                    }
+                    Err(err) => {
+                        let _ = tx.send(AppEvent::HistoryError {
+                            message: format!("History prune failed: {err}"),
+                        });
+                    }
+                }
+            });
+        }
     }
 }
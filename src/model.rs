@@ -1,12 +1,47 @@
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
-use crate::config::AppConfig;
-use crate::history::{EncounterRecord, HistoryDay, HistoryEncounterItem};
+use crate::config::{self, AppConfig, ColumnsConfig, KeybindingsConfig};
+use crate::history::{
+    parse_duration_secs, DayAggregate, EncounterFrame, EncounterRecord, HistoryDay,
+    HistoryEncounterItem, JobStat, RecordingFilter, ZoneSummary, DEFAULT_MAX_FRAMES,
+    FRAMES_CSV_WARN_FRAMES,
+};
+use crate::log_buffer::{self, LogLines};
+use crate::parse::{
+    self, mark_self, parse_percent, rebase_shares, to_f64_any, NumberLocale, SELF_ALIAS,
+};
+use crate::session::SessionState;
+use crate::theme::{self, ColorDepth, Role, Theme};
+use crate::util::group_thousands;
 
 pub const WS_URL_DEFAULT: &str = "ws://127.0.0.1:10501/ws";
+const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(4);
+const RECENT_DEATHS_LIMIT: usize = 5;
+/// How long a combatant that joins mid-encounter keeps its row highlighted.
+const NEW_ROW_HIGHLIGHT: Duration = Duration::from_secs(2);
+/// How far back the rolling DPS column looks.
+const ROLLING_DPS_WINDOW: Duration = Duration::from_secs(15);
+/// The rolling DPS stays blank until the buffered samples span at least this long.
+const ROLLING_DPS_MIN_SPAN: Duration = Duration::from_secs(1);
+const IDLE_ROTATE_STEP: i64 = 5;
+const INPUT_GRACE_STEP: i64 = 5;
+/// PageUp/PageDown step for the idle timeout.
+const IDLE_TIMEOUT_PAGE_STEP: i64 = 10;
+/// Largest idle timeout accepted from the text entry (one day).
+const MAX_IDLE_SECONDS: u64 = 86_400;
+const MIN_RECORD_DAMAGE_STEP: u64 = 10_000;
+const MAX_FRAMES_STEP: u64 = 100;
+const MAX_REPLAY_SPEED: u32 = 16;
+/// Longest encounter note accepted by the editor, in characters.
+const NOTE_MAX_CHARS: usize = 80;
+const MIN_REDRAW_MS: u64 = 16;
+/// Rows kept by the party-only filter when the payload has no party membership flags.
+const PARTY_SIZE: usize = 8;
+const MAX_REDRAW_MS: u64 = 1_000;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum HistoryPanelLevel {
@@ -14,6 +49,148 @@ pub enum HistoryPanelLevel {
     Dates,
     Encounters,
     EncounterDetail,
+    /// Party-wide totals for the selected date, opened from the date list.
+    DaySummary,
+    /// The local player's per-job stats across the whole database, opened from the date list.
+    JobStats,
+    /// The newest encounters across every date, opened with `L`.
+    Recent,
+    /// Encounters across every date whose note matches a query, opened with `/` outside the
+    /// date list.
+    NoteSearch,
+}
+
+/// Query and results of the note search view.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NoteSearch {
+    pub query: String,
+    /// The query line has focus; Enter runs the search.
+    pub editing: bool,
+    /// Matches for `query`, newest first; `None` until the search comes back.
+    pub results: Option<Vec<HistoryEncounterItem>>,
+    pub selected: usize,
+}
+
+/// Note being typed in the detail view, tied to the encounter it was opened on.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NoteDraft {
+    pub key: Vec<u8>,
+    pub text: String,
+}
+
+/// How many encounters the recent list shows.
+pub const RECENT_ENCOUNTERS_LIMIT: usize = 20;
+
+/// Column the job stats table is ordered by; cycled with `o`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum JobStatsSort {
+    #[default]
+    Encounters,
+    Average,
+    Best,
+    Job,
+}
+
+impl JobStatsSort {
+    pub fn next(self) -> Self {
+        match self {
+            JobStatsSort::Encounters => JobStatsSort::Average,
+            JobStatsSort::Average => JobStatsSort::Best,
+            JobStatsSort::Best => JobStatsSort::Job,
+            JobStatsSort::Job => JobStatsSort::Encounters,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            JobStatsSort::Encounters => "Encounters",
+            JobStatsSort::Average => "Avg ENCDPS",
+            JobStatsSort::Best => "Best ENCDPS",
+            JobStatsSort::Job => "Job",
+        }
+    }
+
+    fn sort(self, stats: &mut [JobStat]) {
+        stats.sort_by(|a, b| {
+            let primary = match self {
+                JobStatsSort::Encounters => b.encounters.cmp(&a.encounters),
+                JobStatsSort::Average => b.average_encdps.total_cmp(&a.average_encdps),
+                JobStatsSort::Best => b.best_encdps.total_cmp(&a.best_encdps),
+                JobStatsSort::Job => Ordering::Equal,
+            };
+            primary.then_with(|| a.job.cmp(&b.job))
+        });
+    }
+}
+
+/// Word that must be typed to confirm wiping the whole history.
+pub const CLEAR_HISTORY_CONFIRMATION: &str = "wipe";
+
+/// Version and environment details for the about panel, to paste into bug reports.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AboutInfo {
+    pub version: String,
+    /// The WebSocket URL in use (after `--url`), or the replay file being played.
+    pub source: String,
+    pub config_path: String,
+    pub history_path: String,
+    /// Stored encounters; `None` until the count comes back from the store.
+    pub encounters: Option<usize>,
+    /// Game language from IINACT's `getLanguage` answer; `None` until it arrives.
+    pub language: Option<String>,
+}
+
+/// An action held back until the user answers the confirmation modal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PendingAction {
+    DeleteEncounter {
+        date_id: String,
+        key: Vec<u8>,
+    },
+    /// Delete unpinned encounters older than the retention setting right away.
+    PruneHistory {
+        retention_days: u64,
+    },
+    /// Wipe every stored encounter, sparing pinned ones when `keep_pinned` is set.
+    ClearAll {
+        keep_pinned: bool,
+    },
+    /// Continue recording the encounter a crashed session left unsaved; declining stores it
+    /// as finished instead.
+    ResumeEncounter,
+}
+
+/// The open confirmation: `y`/Enter runs `action`, `n`/Esc drops it. When `typed` is `Some`,
+/// [`CLEAR_HISTORY_CONFIRMATION`] has to be typed before Enter accepts, and `y`/`n` are text.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Confirm {
+    pub message: String,
+    pub action: PendingAction,
+    pub typed: Option<String>,
+}
+
+impl Confirm {
+    fn clear_all(keep_pinned: bool, typed: String) -> Self {
+        let scope = if keep_pinned {
+            "all unpinned history"
+        } else {
+            "ALL history, pinned included"
+        };
+        Self {
+            message: format!("Wipe {scope}?"),
+            action: PendingAction::ClearAll { keep_pinned },
+            typed: Some(typed),
+        }
+    }
+
+    /// False while the confirmation word still has to be typed.
+    pub fn word_typed(&self) -> bool {
+        self.typed.as_deref().is_none_or(|typed| {
+            typed
+                .trim()
+                .eq_ignore_ascii_case(CLEAR_HISTORY_CONFIRMATION)
+        })
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -25,6 +202,29 @@ pub struct HistoryPanel {
     pub selected_day: usize,
     pub selected_encounter: usize,
     pub error: Option<String>,
+    /// Key of a large encounter whose frames CSV export was warned about; exporting it again
+    /// goes ahead.
+    pub frames_export_armed: Option<Vec<u8>>,
+    /// Inline date filter; `Some` while the filter input is open (even if empty).
+    pub filter: Option<String>,
+    /// Date to re-select the next time the date list loads (kept across close/reopen and launches).
+    pub remembered_date: Option<String>,
+    /// Narrows dates and encounter lists to one zone; set from the selected encounter with `z`.
+    pub zone_filter: Option<ZoneSummary>,
+    /// Loaded on first visit to the job stats view; dropped whenever the date list reloads.
+    pub job_stats: Option<Vec<JobStat>>,
+    pub job_stats_sort: JobStatsSort,
+    /// Loaded on first visit to the recent list; dropped when the panel closes or history
+    /// is deleted.
+    pub recent: Option<Vec<HistoryEncounterItem>>,
+    pub selected_recent: usize,
+    /// The detail view was opened from the recent list, so it shows `recent[selected_recent]`.
+    pub detail_from_recent: bool,
+    /// Note being typed in the detail view; `Some` while the editor is open.
+    pub note_draft: Option<NoteDraft>,
+    pub note_search: Option<NoteSearch>,
+    /// The detail view was opened from the note search, so it shows its selected result.
+    pub detail_from_search: bool,
 }
 
 impl Default for HistoryPanel {
@@ -37,17 +237,41 @@ impl Default for HistoryPanel {
             selected_day: 0,
             selected_encounter: 0,
             error: None,
+            frames_export_armed: None,
+            filter: None,
+            remembered_date: None,
+            zone_filter: None,
+            job_stats: None,
+            job_stats_sort: JobStatsSort::default(),
+            recent: None,
+            selected_recent: 0,
+            detail_from_recent: false,
+            note_draft: None,
+            note_search: None,
+            detail_from_search: false,
         }
     }
 }
 
 impl HistoryPanel {
     pub fn reset(&mut self) {
+        if let Some(day) = self.current_day() {
+            self.remembered_date = Some(day.iso_date.clone());
+        }
         self.loading = false;
         self.level = HistoryPanelLevel::Dates;
         self.selected_day = 0;
         self.selected_encounter = 0;
         self.error = None;
+        self.frames_export_armed = None;
+        self.filter = None;
+        self.zone_filter = None;
+        self.recent = None;
+        self.selected_recent = 0;
+        self.detail_from_recent = false;
+        self.note_draft = None;
+        self.note_search = None;
+        self.detail_from_search = false;
         for day in &mut self.days {
             day.encounters.clear();
             day.encounters_loaded = false;
@@ -58,7 +282,84 @@ impl HistoryPanel {
         self.days.get(self.selected_day)
     }
 
+    pub fn last_selected_date(&self) -> Option<String> {
+        if self.visible {
+            if let Some(day) = self.current_day() {
+                return Some(day.iso_date.clone());
+            }
+        }
+        self.remembered_date.clone()
+    }
+
+    /// Indices into `days` that match the active date and zone filters (all days when unset).
+    pub fn visible_day_indices(&self) -> Vec<usize> {
+        let query = self
+            .filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::to_lowercase);
+        self.days
+            .iter()
+            .enumerate()
+            .filter(|(_, day)| match &query {
+                Some(query) => day.label.to_lowercase().contains(query),
+                None => true,
+            })
+            .filter(|(_, day)| match &self.zone_filter {
+                Some(zone) => zone.date_ids.contains(&day.iso_date),
+                None => true,
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Drops every loaded encounter list so they are fetched again under the current zone filter.
+    fn invalidate_encounter_lists(&mut self) {
+        for day in &mut self.days {
+            day.encounters.clear();
+            day.encounters_loaded = false;
+        }
+        self.selected_encounter = 0;
+        if self.level == HistoryPanelLevel::EncounterDetail {
+            self.level = HistoryPanelLevel::Encounters;
+        }
+        self.sync_selection_with_filter();
+        if self.visible_day_indices().is_empty() {
+            self.level = HistoryPanelLevel::Dates;
+        }
+    }
+
+    /// Keeps `selected_day` pointing at a visible entry after the filter or day list changes.
+    fn sync_selection_with_filter(&mut self) {
+        let visible = self.visible_day_indices();
+        if visible.contains(&self.selected_day) {
+            return;
+        }
+        if let Some(&first) = visible.first() {
+            self.selected_day = first;
+            self.selected_encounter = 0;
+        }
+    }
+
+    /// Date whose encounter summaries the Encounters level still has to load, if any.
+    pub fn pending_encounter_load(&self) -> Option<String> {
+        if self.level != HistoryPanelLevel::Encounters {
+            return None;
+        }
+        self.current_day()
+            .filter(|day| !day.encounters_loaded && !day.encounter_ids.is_empty())
+            .map(|day| day.iso_date.clone())
+    }
+
     pub fn current_encounter(&self) -> Option<&HistoryEncounterItem> {
+        if self.detail_from_search {
+            let search = self.note_search.as_ref()?;
+            return search.results.as_ref()?.get(search.selected);
+        }
+        if self.detail_from_recent {
+            return self.recent.as_ref()?.get(self.selected_recent);
+        }
         self.current_day()
             .and_then(|day| day.encounters.get(self.selected_encounter))
     }
@@ -67,13 +368,22 @@ impl HistoryPanel {
         self.days.iter_mut().find(|day| day.iso_date == date_id)
     }
 
-    pub fn find_encounter_mut(&mut self, key: &[u8]) -> Option<&mut HistoryEncounterItem> {
-        for day in &mut self.days {
-            if let Some(item) = day.encounters.iter_mut().find(|item| item.key == key) {
-                return Some(item);
-            }
-        }
-        None
+    /// Every loaded copy of an encounter: the same one can sit in a date's list, the recent
+    /// list and the note search results at once.
+    pub fn encounters_mut<'a>(
+        &'a mut self,
+        key: &'a [u8],
+    ) -> impl Iterator<Item = &'a mut HistoryEncounterItem> + 'a {
+        let search = self
+            .note_search
+            .as_mut()
+            .and_then(|search| search.results.as_mut());
+        self.recent
+            .iter_mut()
+            .chain(search)
+            .chain(self.days.iter_mut().map(|day| &mut day.encounters))
+            .flatten()
+            .filter(move |item| item.key == key)
     }
 }
 
@@ -84,15 +394,52 @@ pub struct AppSnapshot {
     pub last_update_ms: u128,
     pub encounter: Option<EncounterSummary>,
     pub rows: Vec<CombatantRow>,
+    /// The party-total line: the encounter's own totals, or `rows` added up once some
+    /// combatants are hidden.
+    pub total_row: Option<CombatantRow>,
     pub decoration: Decoration,
     pub mode: ViewMode,
+    pub sort_key: SortKey,
     pub is_idle: bool,
     pub idle_scene: IdleScene,
     pub settings: AppSettings,
+    /// Configured live table columns, parsed from `settings.columns` in `apply_settings`.
+    #[serde(skip)]
+    pub table_columns: ConfiguredColumns,
     pub show_settings: bool,
     pub settings_cursor: SettingsField,
+    /// Digits typed for the idle timeout; `Some` while its text entry is open.
+    pub settings_input: Option<String>,
     pub history: HistoryPanel,
     pub show_idle_overlay: bool,
+    pub compact: bool,
+    pub confirm: Option<Confirm>,
+    pub about: Option<AboutInfo>,
+    pub show_logs: bool,
+    /// Recent log lines, oldest first; only filled while the log pane is open.
+    pub logs: Vec<String>,
+    pub log_scroll: usize,
+    pub table_scroll: usize,
+    pub show_help: bool,
+    /// Bound keys and what they do; only filled while the help overlay is open.
+    pub key_help: Vec<(char, String)>,
+    pub status_message: Option<String>,
+    pub paused: bool,
+    pub replay: Option<ReplayInfo>,
+    pub reconnect: Option<ReconnectStatus>,
+    /// Seconds into the active encounter by the local clock, when the live timer is on.
+    pub combat_elapsed: Option<u64>,
+    pub idle_highlights: Option<IdleHighlights>,
+    pub color_depth: ColorDepth,
+    pub recent_deaths: Vec<RecentDeath>,
+    pub history_notice: Option<String>,
+    pub config_notice: Option<String>,
+    /// Approximate CombatData frames per second, from a rolling average of arrival gaps.
+    pub update_rate: Option<f64>,
+    /// Time from the handshake to the first CombatData frame of this connection.
+    pub first_frame_ms: Option<u64>,
+    /// Seconds since the app was started.
+    pub session_uptime_secs: u64,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -119,7 +466,7 @@ impl IdleScene {
     pub fn description(self) -> &'static str {
         match self {
             IdleScene::Status => "Connection & encounter healthcheck",
-            IdleScene::TopCritChain => "Highlights the longest critical damage streak",
+            IdleScene::TopCritChain => "Top three crit% of the last encounter",
             IdleScene::AsciiArt => "Rotating ASCII art showcase",
             IdleScene::TipOfTheDay => "Rotation and encounter tips",
             IdleScene::AchievementTicker => "Recently unlocked achievements",
@@ -127,22 +474,145 @@ impl IdleScene {
     }
 }
 
+/// Weight of the newest gap in the rolling frame-interval average.
+const FEED_RATE_SMOOTHING: f64 = 0.2;
+
+/// Arrival timing of live CombatData frames for the current connection.
+#[derive(Clone, Debug, Default)]
+pub struct FeedRate {
+    connected_at: Option<Instant>,
+    last_frame: Option<Instant>,
+    first_frame_latency: Option<Duration>,
+    /// Exponential moving average of the gap between frames, in seconds.
+    interval_avg: Option<f64>,
+}
+
+impl FeedRate {
+    /// Starts timing a fresh connection established at `now`.
+    pub fn reset(&mut self, now: Option<Instant>) {
+        *self = FeedRate {
+            connected_at: now,
+            ..FeedRate::default()
+        };
+    }
+
+    /// Records a frame arriving at `now`.
+    pub fn record(&mut self, now: Instant) {
+        match self.last_frame {
+            Some(prev) => {
+                let gap = now.saturating_duration_since(prev).as_secs_f64();
+                self.interval_avg = Some(match self.interval_avg {
+                    Some(avg) => avg + FEED_RATE_SMOOTHING * (gap - avg),
+                    None => gap,
+                });
+            }
+            None => {
+                self.first_frame_latency = self
+                    .connected_at
+                    .map(|at| now.saturating_duration_since(at));
+            }
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// Frames per second implied by the average gap; `None` until two frames arrived.
+    pub fn per_second(&self) -> Option<f64> {
+        self.interval_avg
+            .filter(|avg| *avg > 0.0)
+            .map(|avg| 1.0 / avg)
+    }
+
+    pub fn first_frame_latency(&self) -> Option<Duration> {
+        self.first_frame_latency
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AppState {
     pub connected: bool,
     pub last_update: Option<Instant>,
     pub last_active: Option<Instant>,
+    /// Most recent key press or mouse event; see `note_input`.
+    pub last_input: Option<Instant>,
     pub connected_since: Option<Instant>,
+    pub feed_rate: FeedRate,
+    /// Latest reconnect attempt number and when its retry is due; cleared once connected.
+    pub reconnect: Option<(u32, Instant)>,
     pub encounter: Option<EncounterSummary>,
     pub rows: Vec<CombatantRow>,
     pub decoration: Decoration,
     pub mode: ViewMode,
+    pub sort_key: SortKey,
     pub idle_scene: IdleScene,
     pub settings: AppSettings,
+    /// Configured live table columns, parsed from `settings.columns` in `apply_settings`.
+    pub table_columns: ConfiguredColumns,
     pub show_settings: bool,
     pub settings_cursor: SettingsField,
+    /// Digits typed for the idle timeout; `Some` while its text entry is open.
+    pub settings_input: Option<String>,
     pub history: HistoryPanel,
     pub show_idle_overlay: bool,
+    /// Table-only layout; starts from `settings.compact` unless `--compact` forces it on.
+    pub compact: bool,
+    /// Open yes/no confirmation for a destructive action; see `request_confirm`.
+    pub confirm: Option<Confirm>,
+    /// Open about panel; see `open_about`.
+    pub about: Option<AboutInfo>,
+    /// Log pane over the live view; reads `log_lines` only while open.
+    pub show_logs: bool,
+    /// Lines scrolled up from the newest log line; 0 follows new lines.
+    pub log_scroll: usize,
+    /// Ring buffer fed by the tracing layer; see `log_buffer::BufferLayer`.
+    pub log_lines: LogLines,
+    /// Rows scrolled past at the top of the live table; 0 keeps the top performer in view.
+    /// Reset on rollover.
+    pub table_scroll: usize,
+    /// Table rows that fit on screen at the last draw; `None` until the table has been drawn.
+    pub table_page: Option<usize>,
+    /// Help overlay listing the keys; any key closes it.
+    pub show_help: bool,
+    /// Bound keys and what they do, from the keymap; see `keymap::Keymap::help_entries`.
+    pub key_help: Vec<(char, String)>,
+    pub status_message: Option<(String, Instant)>,
+    /// While paused the table renders `frozen_rows`; `rows` keeps tracking live data.
+    pub paused: bool,
+    pub frozen_rows: Vec<CombatantRow>,
+    /// Set while a history encounter plays back into the table; live frames are held aside.
+    pub replay: Option<ReplayInfo>,
+    /// Live encounter and rows from before the replay, kept current and restored when it ends.
+    replay_saved: Option<(Option<EncounterSummary>, Vec<CombatantRow>)>,
+    /// Latest stored encounter, fetched from history each time idle mode begins.
+    pub recent_encounter: Option<EncounterRecord>,
+    pub idle_highlights: Option<IdleHighlights>,
+    /// Detected (or `--color` forced) terminal color depth.
+    pub color_depth: ColorDepth,
+    /// Newest first, capped at `RECENT_DEATHS_LIMIT`; fed by LogLine death events.
+    pub recent_deaths: Vec<RecentDeath>,
+    /// Set when the on-disk history couldn't be opened and recording is in-memory only.
+    pub history_notice: Option<String>,
+    /// Set when the config file could not be loaded and defaults are in use.
+    pub config_notice: Option<String>,
+    /// Game language IINACT reported at connect; kept across reconnects. Language-specific
+    /// parsing (pet and Limit Break names differ per client language) can key off this.
+    pub game_language: Option<String>,
+    /// When the idle scene last changed, by hand or by rotation; drives auto-rotation.
+    idle_scene_since: Option<Instant>,
+    /// When each combatant of the current encounter first showed up; reset on rollover.
+    first_seen: HashMap<String, Instant>,
+    /// Recent (time, cumulative damage) samples per combatant for the rolling DPS column;
+    /// reset on rollover.
+    damage_history: HashMap<String, VecDeque<(Instant, f64)>>,
+    /// Start of the current encounter; rows first seen after it count as newly joined.
+    encounter_started: Option<Instant>,
+    /// When the active encounter began, backdated by ACT's duration at the time it was seen
+    /// active; cleared when it goes inactive. Drives the live combat timer.
+    active_since: Option<Instant>,
+    was_idle: bool,
+    /// When this app session began, for the footer's uptime.
+    session_started: Instant,
+    /// Something drawn changed since the last frame; see `take_dirty`.
+    pub dirty: bool,
 }
 
 impl Default for AppState {
@@ -151,23 +621,60 @@ impl Default for AppState {
             connected: false,
             last_update: None,
             last_active: None,
+            last_input: None,
             connected_since: None,
+            feed_rate: FeedRate::default(),
+            reconnect: None,
             encounter: None,
             rows: Vec::new(),
             decoration: Decoration::default(),
             mode: ViewMode::default(),
+            sort_key: SortKey::default(),
             idle_scene: IdleScene::default(),
             settings: AppSettings::default(),
+            table_columns: ConfiguredColumns::default(),
             show_settings: false,
             settings_cursor: SettingsField::default(),
+            settings_input: None,
             history: HistoryPanel::default(),
             show_idle_overlay: true,
+            compact: false,
+            confirm: None,
+            about: None,
+            show_logs: false,
+            log_scroll: 0,
+            log_lines: LogLines::default(),
+            table_scroll: 0,
+            table_page: None,
+            show_help: false,
+            key_help: Vec::new(),
+            status_message: None,
+            paused: false,
+            frozen_rows: Vec::new(),
+            replay: None,
+            replay_saved: None,
+            recent_encounter: None,
+            idle_highlights: None,
+            color_depth: ColorDepth::default(),
+            recent_deaths: Vec::new(),
+            history_notice: None,
+            config_notice: None,
+            game_language: None,
+            idle_scene_since: None,
+            first_seen: HashMap::new(),
+            damage_history: HashMap::new(),
+            encounter_started: None,
+            active_since: None,
+            was_idle: false,
+            session_started: Instant::now(),
+            dirty: true,
         }
     }
 }
 
 impl AppState {
     pub fn apply(&mut self, evt: AppEvent) {
+        self.dirty = true;
         match evt {
             AppEvent::Connected => {
                 self.connected = true;
@@ -175,35 +682,53 @@ impl AppState {
                 self.last_update = Some(now);
                 self.last_active = None;
                 self.connected_since = Some(now);
+                self.feed_rate.reset(Some(now));
+                self.reconnect = None;
+            }
+            AppEvent::Reconnecting { attempt, delay_ms } => {
+                let retry_at = Instant::now() + Duration::from_millis(delay_ms);
+                self.reconnect = Some((attempt, retry_at));
             }
             AppEvent::Disconnected => {
                 self.connected = false;
                 self.last_update = None;
                 self.last_active = None;
                 self.connected_since = None;
+                self.feed_rate.reset(None);
             }
             AppEvent::CombatData { encounter, rows } => {
-                let now = Instant::now();
-                self.encounter = Some(encounter);
-                self.rows = rows;
-                self.last_update = Some(now);
-                self.idle_scene = IdleScene::Status;
-                if self
-                    .encounter
-                    .as_ref()
-                    .map(|enc| enc.is_active)
-                    .unwrap_or(false)
-                {
-                    self.last_active = Some(now);
+                self.feed_rate.record(Instant::now());
+                if self.replay.is_some() {
+                    self.replay_saved = Some((Some(encounter), rows));
+                } else {
+                    self.apply_combat_data(encounter, rows);
+                }
+            }
+            AppEvent::ReplayFrame { encounter, rows } => {
+                if self.replay.is_some() {
+                    self.apply_combat_data(encounter, rows);
+                }
+            }
+            AppEvent::ReplayFinished => {
+                if self.stop_replay() {
+                    self.status_message = Some(("Replay finished".to_string(), Instant::now()));
                 }
             }
-            AppEvent::HistoryDatesLoaded { days } => {
+            AppEvent::HistoryDatesLoaded { days, skipped } => {
+                self.note_skipped_history_entries(skipped);
                 self.history.loading = false;
                 self.history.error = None;
                 self.history.days = days;
+                self.history.job_stats = None;
+                if let Some(date) = self.history.remembered_date.take() {
+                    if let Some(idx) = self.history.days.iter().position(|d| d.iso_date == date) {
+                        self.history.selected_day = idx;
+                    }
+                }
                 if self.history.selected_day >= self.history.days.len() {
                     self.history.selected_day = 0;
                 }
+                self.history.sync_selection_with_filter();
                 if let Some(day) = self.history.current_day() {
                     if day.encounters.is_empty() {
                         self.history.selected_encounter = 0;
@@ -222,6 +747,11 @@ impl AppState {
                 {
                     self.history.level = HistoryPanelLevel::Dates;
                 }
+                if self.history.level == HistoryPanelLevel::DaySummary
+                    && self.history.current_day().is_none()
+                {
+                    self.history.level = HistoryPanelLevel::Dates;
+                }
                 if self.history.level == HistoryPanelLevel::EncounterDetail
                     && self.history.current_encounter().is_none()
                 {
@@ -240,7 +770,9 @@ impl AppState {
             AppEvent::HistoryEncountersLoaded {
                 date_id,
                 encounters,
+                skipped,
             } => {
+                self.note_skipped_history_entries(skipped);
                 let selected_matches = self
                     .history
                     .days
@@ -248,11 +780,19 @@ impl AppState {
                     .map(|d| d.iso_date == date_id)
                     .unwrap_or(false);
 
+                let total = encounters.len();
+                let encounters: Vec<HistoryEncounterItem> = match &self.history.zone_filter {
+                    Some(filter) => encounters
+                        .into_iter()
+                        .filter(|enc| enc.zone == filter.zone)
+                        .collect(),
+                    None => encounters,
+                };
                 let new_len = encounters.len();
                 if let Some(day) = self.history.find_day_mut(&date_id) {
                     day.encounters = encounters;
                     day.encounters_loaded = true;
-                    day.encounter_count = new_len;
+                    day.encounter_count = total;
                 }
                 self.history.loading = false;
 
@@ -264,15 +804,102 @@ impl AppState {
                 }
             }
             AppEvent::HistoryEncounterLoaded { key, record } => {
-                if let Some(item) = self.history.find_encounter_mut(&key) {
-                    item.record = Some(record);
+                for item in self.history.encounters_mut(&key) {
+                    item.record = Some(record.clone());
+                }
+                self.history.loading = false;
+            }
+            AppEvent::HistoryNotesSearched {
+                query,
+                encounters,
+                skipped,
+            } => {
+                self.note_skipped_history_entries(skipped);
+                if let Some(search) = self
+                    .history
+                    .note_search
+                    .as_mut()
+                    .filter(|search| search.query.trim() == query)
+                {
+                    search.selected = search.selected.min(encounters.len().saturating_sub(1));
+                    search.results = Some(encounters);
+                }
+                self.history.loading = false;
+            }
+            AppEvent::HistoryNoteSaved { key, note } => {
+                let message = if note.is_some() {
+                    "Note saved"
+                } else {
+                    "Note removed"
+                };
+                for item in self.history.encounters_mut(&key) {
+                    item.note = note.clone();
+                }
+                self.status_message = Some((message.to_string(), Instant::now()));
+            }
+            AppEvent::HistoryDayAggregated { date_id, aggregate } => {
+                if let Some(day) = self.history.find_day_mut(&date_id) {
+                    day.aggregate = Some(aggregate);
                 }
                 self.history.loading = false;
             }
+            AppEvent::HistoryRecentLoaded {
+                encounters,
+                skipped,
+            } => {
+                self.note_skipped_history_entries(skipped);
+                self.history.selected_recent = self
+                    .history
+                    .selected_recent
+                    .min(encounters.len().saturating_sub(1));
+                self.history.recent = Some(encounters);
+                self.history.loading = false;
+            }
+            AppEvent::HistoryJobStatsLoaded { mut stats, skipped } => {
+                self.note_skipped_history_entries(skipped);
+                self.history.job_stats_sort.sort(&mut stats);
+                self.history.job_stats = Some(stats);
+                self.history.loading = false;
+            }
+            AppEvent::HistoryZonesLoaded {
+                zone,
+                zones,
+                skipped,
+            } => {
+                self.note_skipped_history_entries(skipped);
+                self.history.loading = false;
+                self.history.zone_filter = zones.into_iter().find(|z| z.zone == zone);
+                self.history.invalidate_encounter_lists();
+            }
+            AppEvent::ConfigError { message } => {
+                self.config_notice = Some(message);
+            }
+            AppEvent::HistoryEncounterCount { count } => {
+                if let Some(about) = self.about.as_mut() {
+                    about.encounters = Some(count);
+                }
+            }
+            AppEvent::GameLanguage { language } => {
+                if let Some(about) = self.about.as_mut() {
+                    about.language = Some(language.clone());
+                }
+                self.game_language = Some(language);
+            }
             AppEvent::HistoryError { message } => {
                 self.history.loading = false;
                 self.history.error = Some(message);
             }
+            AppEvent::StatusMessage { message } => {
+                self.status_message = Some((message, Instant::now()));
+            }
+            AppEvent::DeathLogged { name, ms } => {
+                self.recent_deaths.insert(0, RecentDeath { name, ms });
+                self.recent_deaths.truncate(RECENT_DEATHS_LIMIT);
+            }
+            AppEvent::RecentEncounterLoaded { record } => {
+                self.idle_highlights = record.as_ref().map(IdleHighlights::from_record);
+                self.recent_encounter = record;
+            }
         }
     }
 
@@ -282,20 +909,136 @@ impl AppState {
             .last_update
             .map(|t| now.saturating_duration_since(t).as_millis())
             .unwrap_or(0);
+        let mut rows = if self.paused {
+            self.frozen_rows.clone()
+        } else {
+            self.rows.clone()
+        };
+        // Numbered over every combatant, so hiding rows doesn't renumber the rest
+        let placeholders = self.settings.anonymize.then(|| placeholder_names(&rows));
+        let before = rows.len();
+        if self.settings.hide_pets {
+            rows.retain(|row| !is_pet_or_limit_break(&row.name, &self.settings.pet_names));
+        }
+        if self.settings.party_only {
+            keep_party(&mut rows);
+        }
+        if rows.len() != before {
+            rebase_shares(&mut rows);
+        }
+        // After the share math, so hidden rows still count towards the party total.
+        if self.settings.hide_zero_rows {
+            rows.retain(|row| {
+                self.mode.metric_value(row) > 0.0 || (self.settings.keep_self_row && row.is_self)
+            });
+        }
+        let total_row = self.encounter.as_ref().map(|encounter| {
+            if rows.len() == before {
+                encounter.total_row()
+            } else {
+                summed_total_row(&rows)
+            }
+        });
+        self.sort_key.sort_rows(self.mode, &mut rows);
+        for row in &mut rows {
+            row.recently_joined = self.recently_joined(&row.name, now);
+        }
+        let mut recent_deaths = self.recent_deaths.clone();
+        if let Some(names) = &placeholders {
+            for row in &mut rows {
+                row.name = anonymized_name(names, &row.name);
+            }
+            for death in &mut recent_deaths {
+                death.name = anonymized_name(names, &death.name);
+            }
+        }
         AppSnapshot {
             connected: self.connected,
             last_update_ms: elapsed_ms,
             encounter: self.encounter.clone(),
-            rows: self.rows.clone(),
+            rows,
+            total_row,
             decoration: self.decoration,
             mode: self.mode,
+            sort_key: self.sort_key,
             is_idle: self.is_idle_at(now),
             idle_scene: self.idle_scene,
             settings: self.settings.clone(),
+            table_columns: self.table_columns.clone(),
             show_settings: self.show_settings,
             settings_cursor: self.settings_cursor,
+            settings_input: self.settings_input.clone(),
             history: self.history.clone(),
             show_idle_overlay: self.show_idle_overlay,
+            compact: self.compact,
+            confirm: self.confirm.clone(),
+            about: self.about.clone(),
+            show_logs: self.show_logs,
+            logs: if self.show_logs {
+                log_buffer::recent_lines(&self.log_lines)
+            } else {
+                Vec::new()
+            },
+            log_scroll: self.log_scroll,
+            table_scroll: self.table_scroll,
+            show_help: self.show_help,
+            key_help: if self.show_help {
+                self.key_help.clone()
+            } else {
+                Vec::new()
+            },
+            status_message: self
+                .status_message
+                .as_ref()
+                .filter(|(_, at)| now.saturating_duration_since(*at) < STATUS_MESSAGE_TTL)
+                .map(|(message, _)| message.clone()),
+            paused: self.paused,
+            replay: self.replay.clone(),
+            combat_elapsed: self
+                .active_since
+                .filter(|_| self.settings.live_timer)
+                .map(|since| now.saturating_duration_since(since).as_secs()),
+            reconnect: self
+                .reconnect
+                .filter(|_| !self.connected)
+                .map(|(attempt, retry_at)| ReconnectStatus {
+                    attempt,
+                    retry_in_ms: retry_at.saturating_duration_since(now).as_millis() as u64,
+                }),
+            idle_highlights: self.idle_highlights.clone(),
+            color_depth: self.color_depth,
+            recent_deaths,
+            history_notice: self.history_notice.clone(),
+            config_notice: self.config_notice.clone(),
+            update_rate: self.feed_rate.per_second().filter(|_| self.connected),
+            first_frame_ms: self
+                .feed_rate
+                .first_frame_latency()
+                .filter(|_| self.connected)
+                .map(|latency| latency.as_millis() as u64),
+            session_uptime_secs: now
+                .saturating_duration_since(self.session_started)
+                .as_secs(),
+        }
+    }
+}
+
+impl AppSnapshot {
+    /// Connected and active but with no rows to show, so the table would otherwise be blank.
+    pub fn awaiting_combatants(&self) -> bool {
+        self.connected && !self.is_idle && self.replay.is_none() && self.rows.is_empty()
+    }
+
+    /// Seconds since the last update when the feed looks stalled; `None` while idle or disconnected.
+    pub fn stale_seconds(&self) -> Option<u64> {
+        if !self.connected || self.is_idle {
+            return None;
+        }
+        let threshold = self.settings.stale_duration()?;
+        if self.last_update_ms >= threshold.as_millis() {
+            Some((self.last_update_ms / 1000) as u64)
+        } else {
+            None
         }
     }
 }
@@ -316,6 +1059,14 @@ impl AppState {
         {
             return false;
         }
+        // Input only holds idle mode off; once idle, keys like `i` and `]` shouldn't end it.
+        if self.settings.input_delays_idle && !self.was_idle {
+            if let Some(input) = self.last_input {
+                if now.saturating_duration_since(input) < self.settings.input_grace() {
+                    return false;
+                }
+            }
+        }
         if let Some(active) = self.last_active {
             if now.saturating_duration_since(active) >= threshold {
                 return true;
@@ -328,89 +1079,710 @@ impl AppState {
         false
     }
 
-    pub fn apply_settings(&mut self, settings: AppSettings) {
-        self.settings = settings;
-        self.sync_current_with_defaults();
+    /// Records a key press, click or scroll for `input_delays_idle`.
+    pub fn note_input(&mut self, now: Instant) {
+        self.last_input = Some(now);
     }
 
-    pub fn adjust_idle_seconds(&mut self, delta: i64) -> bool {
-        let current = self.settings.idle_seconds;
-        let raw = current as i64 + delta;
-        let adjusted = if raw < 0 { 0 } else { raw as u64 };
-        if adjusted != current {
-            self.settings.idle_seconds = adjusted;
-            true
-        } else {
-            false
+    /// Switches the idle scene and restarts the auto-rotation countdown.
+    pub fn set_idle_scene(&mut self, scene: IdleScene, now: Instant) {
+        self.idle_scene = scene;
+        self.idle_scene_since = Some(now);
+        self.dirty = true;
+    }
+
+    /// Whether a redraw is needed, clearing the flag.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// True once the current idle scene has been shown for `idle_rotate_seconds`.
+    pub fn idle_rotation_due(&self, now: Instant) -> bool {
+        let secs = self.settings.idle_rotate_seconds;
+        if secs == 0 || !self.show_idle_overlay || self.history.visible || !self.is_idle_at(now) {
+            return false;
         }
+        self.idle_scene_since
+            .map(|since| now.saturating_duration_since(since) >= Duration::from_secs(secs))
+            .unwrap_or(false)
     }
 
-    pub fn adjust_selected_setting(&mut self, forward: bool) -> bool {
-        match self.settings_cursor {
-            SettingsField::IdleTimeout => self.adjust_idle_seconds(if forward { 1 } else { -1 }),
-            SettingsField::DefaultDecoration => {
-                let changed = self.cycle_default_decoration(forward);
-                if changed {
-                    self.sync_current_with_defaults();
-                }
-                changed
-            }
-            SettingsField::DefaultMode => {
-                let changed = self.cycle_default_mode(forward);
-                if changed {
-                    self.sync_current_with_defaults();
-                }
-                changed
-            }
+    /// Records the idle state at `now`; returns true on the transition into idle.
+    pub fn observe_idle(&mut self, now: Instant) -> bool {
+        let idle = self.is_idle_at(now);
+        let entered = idle && !self.was_idle;
+        if self.was_idle && !idle {
+            self.idle_scene = IdleScene::Status;
+        }
+        if entered {
+            self.idle_scene_since = Some(now);
+        }
+        if idle != self.was_idle {
+            self.dirty = true;
         }
+        self.was_idle = idle;
+        entered
     }
 
-    pub fn next_setting(&mut self) {
-        self.settings_cursor = self.settings_cursor.next();
+    pub fn restore_session(&mut self, session: SessionState) {
+        self.idle_scene = session.idle_scene;
+        self.show_idle_overlay = session.show_idle_overlay;
+        self.history.remembered_date = session.last_history_date;
     }
 
-    pub fn prev_setting(&mut self) {
-        self.settings_cursor = self.settings_cursor.prev();
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            idle_scene: self.idle_scene,
+            show_idle_overlay: self.show_idle_overlay,
+            last_history_date: self.history.last_selected_date(),
+        }
     }
 
-    fn cycle_default_decoration(&mut self, forward: bool) -> bool {
-        let current = self.settings.default_decoration;
-        let next = if forward {
-            current.next()
+    fn apply_combat_data(&mut self, encounter: EncounterSummary, mut rows: Vec<CombatantRow>) {
+        mark_self(&mut rows, &self.settings.character_name);
+        let now = Instant::now();
+        let restarted = self.track_first_seen(&encounter, &rows, now);
+        self.track_rolling_dps(&mut rows, restarted, now);
+        if restarted {
+            self.table_scroll = 0;
+        }
+        if !encounter.is_active {
+            self.active_since = None;
+        } else if restarted || self.active_since.is_none() {
+            let elapsed = parse_duration_secs(&encounter.duration).unwrap_or(0);
+            self.active_since = now.checked_sub(Duration::from_secs(elapsed)).or(Some(now));
+        }
+        self.encounter = Some(encounter);
+        self.rows = with_trends(&self.rows, rows);
+        self.last_update = Some(now);
+        if self
+            .encounter
+            .as_ref()
+            .map(|enc| enc.is_active)
+            .unwrap_or(false)
+        {
+            self.last_active = Some(now);
+        }
+    }
+
+    /// Starts replaying the encounter open in the history detail view, returning its frames
+    /// for the replay driver. The history panel closes so the table is visible.
+    pub fn history_start_replay(&mut self) -> Option<Vec<EncounterFrame>> {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::EncounterDetail
+        {
+            return None;
+        }
+        let record = self.history.current_encounter()?.record.as_ref()?;
+        if record.frames.is_empty() {
+            self.apply(AppEvent::StatusMessage {
+                message: "No frames were recorded for this encounter".to_string(),
+            });
+            return None;
+        }
+        let title = if record.encounter.title.is_empty() {
+            "Encounter".to_string()
         } else {
-            current.prev()
+            record.encounter.title.clone()
         };
-        if next != current {
-            self.settings.default_decoration = next;
-            true
-        } else {
-            false
-        }
+        let frames = record.frames.clone();
+        self.start_replay(title);
+        self.history.visible = false;
+        Some(frames)
     }
 
-    fn cycle_default_mode(&mut self, forward: bool) -> bool {
-        let current = self.settings.default_mode;
-        let next = if forward {
-            current.next()
+    /// Returns the open encounter for a frames CSV export. Encounters with more than
+    /// `FRAMES_CSV_WARN_FRAMES` frames only get a size warning the first time.
+    pub fn history_request_frames_export(&mut self) -> Option<EncounterRecord> {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::EncounterDetail
+        {
+            return None;
+        }
+        let item = self.history.current_encounter()?;
+        let record = item.record.as_ref()?;
+        let frames = record.frames.len();
+        let message = if frames == 0 {
+            "No frames were recorded for this encounter".to_string()
+        } else if frames > FRAMES_CSV_WARN_FRAMES
+            && self.history.frames_export_armed.as_ref() != Some(&item.key)
+        {
+            let rows: usize = record.frames.iter().map(|frame| frame.rows.len()).sum();
+            self.history.frames_export_armed = Some(item.key.clone());
+            format!("{frames} frames make a large CSV (~{rows} rows); press E again to export")
         } else {
-            current.prev()
+            let record = record.clone();
+            self.history.frames_export_armed = None;
+            return Some(record);
         };
-        if next != current {
-            self.settings.default_mode = next;
-            true
+        self.apply(AppEvent::StatusMessage { message });
+        None
+    }
+
+    /// Clears the table for a replay at 1x, setting the live data aside unless one is
+    /// already running (its frames are simply replaced).
+    fn start_replay(&mut self, title: String) {
+        if self.replay.is_none() {
+            self.replay_saved = Some((self.encounter.take(), std::mem::take(&mut self.rows)));
         } else {
-            false
+            self.encounter = None;
+            self.rows.clear();
         }
+        self.replay = Some(ReplayInfo { title, speed: 1 });
+        self.paused = false;
+        self.frozen_rows.clear();
     }
 
-    fn sync_current_with_defaults(&mut self) {
-        self.decoration = self.settings.default_decoration;
-        self.mode = self.settings.default_mode;
+    /// Ends a replay and puts the live table back; returns false when none was running.
+    pub fn stop_replay(&mut self) -> bool {
+        if self.replay.take().is_none() {
+            return false;
+        }
+        let (encounter, rows) = self.replay_saved.take().unwrap_or_default();
+        self.encounter = encounter;
+        self.rows = rows;
+        self.first_seen.clear();
+        self.damage_history.clear();
+        self.encounter_started = None;
+        self.active_since = None;
+        self.table_scroll = 0;
+        true
     }
 
-    pub fn toggle_history(&mut self) -> bool {
-        if self.history.visible {
-            self.history.visible = false;
+    /// Doubles or halves the replay speed (1x to 16x); returns the new speed while replaying.
+    pub fn change_replay_speed(&mut self, faster: bool) -> Option<u32> {
+        let replay = self.replay.as_mut()?;
+        replay.speed = if faster {
+            (replay.speed * 2).min(MAX_REPLAY_SPEED)
+        } else {
+            (replay.speed / 2).max(1)
+        };
+        Some(replay.speed)
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused {
+            self.frozen_rows = self.rows.clone();
+        } else {
+            self.frozen_rows.clear();
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: AppSettings) {
+        self.settings = settings;
+        self.table_columns = ConfiguredColumns::parse(&self.settings.columns);
+        self.compact = self.settings.compact;
+        self.sync_current_with_defaults();
+        theme::set_theme(self.settings.theme);
+        parse::set_number_locale(self.settings.number_locale);
+    }
+
+    /// Records newcomers to the current encounter, starting over when the encounter rolls over
+    /// (its duration went backwards), so a pull's opening roster is never highlighted.
+    fn track_first_seen(
+        &mut self,
+        next: &EncounterSummary,
+        rows: &[CombatantRow],
+        now: Instant,
+    ) -> bool {
+        let restarted = match &self.encounter {
+            None => true,
+            Some(current) => {
+                match (
+                    parse_duration_secs(&current.duration),
+                    parse_duration_secs(&next.duration),
+                ) {
+                    (Some(prev), Some(secs)) => secs < prev,
+                    _ => current.title != next.title,
+                }
+            }
+        };
+        if restarted {
+            self.first_seen.clear();
+            self.encounter_started = Some(now);
+        }
+        for row in rows {
+            self.first_seen.entry(row.name.clone()).or_insert(now);
+        }
+        restarted
+    }
+
+    /// Buffers each combatant's cumulative damage and sets `rolling_dps` from the damage dealt
+    /// over the last `ROLLING_DPS_WINDOW`. Buffers start over on rollover and for combatants
+    /// whose damage went backwards.
+    fn track_rolling_dps(&mut self, rows: &mut [CombatantRow], restarted: bool, now: Instant) {
+        if restarted {
+            self.damage_history.clear();
+        }
+        for row in rows.iter_mut() {
+            let samples = self.damage_history.entry(row.name.clone()).or_default();
+            if samples
+                .back()
+                .is_some_and(|&(_, damage)| row.damage < damage)
+            {
+                samples.clear();
+            }
+            samples.push_back((now, row.damage));
+            // Keep the newest sample at or before the window start as the baseline.
+            while samples.len() > 1
+                && samples
+                    .get(1)
+                    .is_some_and(|&(at, _)| now.saturating_duration_since(at) >= ROLLING_DPS_WINDOW)
+            {
+                samples.pop_front();
+            }
+            row.rolling_dps = rolling_dps(samples);
+        }
+    }
+
+    /// True while the live combat timer is counting, so every tick needs a redraw.
+    pub fn timer_running(&self) -> bool {
+        self.settings.live_timer && self.active_since.is_some()
+    }
+
+    fn recently_joined(&self, name: &str, now: Instant) -> bool {
+        match (self.first_seen.get(name), self.encounter_started) {
+            (Some(seen), Some(started)) => {
+                *seen > started && now.saturating_duration_since(*seen) < NEW_ROW_HIGHLIGHT
+            }
+            _ => false,
+        }
+    }
+
+    /// Flips the header; the choice is saved with the settings.
+    pub fn toggle_header(&mut self) {
+        self.settings.show_header = !self.settings.show_header;
+    }
+
+    /// Flips the footer; the choice is saved with the settings. The help overlay still lists
+    /// the keys while it is hidden.
+    pub fn toggle_footer(&mut self) {
+        self.settings.show_footer = !self.settings.show_footer;
+    }
+
+    /// Opens the about panel with the paths in use and `source`, the endpoint or replay file
+    /// the session actually reads; the encounter count arrives separately as
+    /// `HistoryEncounterCount`.
+    pub fn open_about(&mut self, source: &str) {
+        let mut history_path = config::history_db_path().display().to_string();
+        if self.history_notice.is_some() {
+            history_path.push_str(" (unavailable, not saving)");
+        }
+        self.about = Some(AboutInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            source: source.to_string(),
+            config_path: config::config_path().display().to_string(),
+            history_path,
+            encounters: None,
+            language: self.game_language.clone(),
+        });
+    }
+
+    /// Opens or closes the log pane, starting at the newest line.
+    pub fn toggle_logs(&mut self) {
+        self.show_logs = !self.show_logs;
+        self.log_scroll = 0;
+    }
+
+    /// Scrolls the log pane; positive `delta` moves towards older lines.
+    pub fn scroll_logs(&mut self, delta: isize) {
+        let max = log_buffer::recent_lines(&self.log_lines)
+            .len()
+            .saturating_sub(1);
+        self.log_scroll = self.log_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    /// Scrolls the live table; positive `delta` moves down towards the lower rows. Stops once
+    /// the last page is on screen.
+    pub fn scroll_table(&mut self, delta: isize) {
+        self.table_scroll = self
+            .table_scroll
+            .saturating_add_signed(delta)
+            .min(self.max_table_scroll());
+    }
+
+    /// Records how many rows the last draw fit and pulls the scroll back onto the last page.
+    pub fn set_table_page(&mut self, rows: usize) {
+        self.table_page = Some(rows);
+        self.table_scroll = self.table_scroll.min(self.max_table_scroll());
+    }
+
+    fn max_table_scroll(&self) -> usize {
+        let rows = if self.paused {
+            self.frozen_rows.len()
+        } else {
+            self.rows.len()
+        };
+        rows.saturating_sub(self.table_page.unwrap_or(1).max(1))
+    }
+
+    /// True when the live table is on screen with nothing drawn over it, so the wheel and
+    /// PgUp/PgDn scroll it.
+    pub fn table_in_front(&self, now: Instant) -> bool {
+        !self.history.visible
+            && !self.show_settings
+            && !self.show_logs
+            && !self.show_help
+            && self.about.is_none()
+            && self.confirm.is_none()
+            && !(self.show_idle_overlay && self.is_idle_at(now))
+    }
+
+    /// Closes the help overlay; returns false when it wasn't open.
+    pub fn dismiss_help(&mut self) -> bool {
+        std::mem::replace(&mut self.show_help, false)
+    }
+
+    pub fn toggle_anonymize(&mut self) {
+        self.settings.anonymize = !self.settings.anonymize;
+    }
+
+    /// Flips compact mode and records it as the saved preference.
+    pub fn toggle_compact(&mut self) {
+        self.compact = !self.compact;
+        self.settings.compact = self.compact;
+    }
+
+    pub fn adjust_idle_seconds(&mut self, delta: i64) -> bool {
+        let current = self.settings.idle_seconds;
+        let raw = current as i64 + delta;
+        let adjusted = if raw < 0 { 0 } else { raw as u64 };
+        if adjusted != current {
+            self.settings.idle_seconds = adjusted;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Enter on the idle timeout opens a text entry seeded with the current value.
+    pub fn settings_begin_input(&mut self) {
+        if self.show_settings && self.settings_cursor == SettingsField::IdleTimeout {
+            self.settings_input = Some(self.settings.idle_seconds.to_string());
+        }
+    }
+
+    /// True while keystrokes should be routed into the idle timeout entry.
+    pub fn settings_input_editing(&self) -> bool {
+        self.show_settings && self.settings_input.is_some()
+    }
+
+    /// Appends a digit to the entry; anything else is ignored.
+    pub fn settings_input_push(&mut self, ch: char) {
+        if let Some(input) = self.settings_input.as_mut() {
+            if ch.is_ascii_digit() && input.len() < MAX_IDLE_SECONDS.to_string().len() {
+                input.push(ch);
+            }
+        }
+    }
+
+    pub fn settings_input_pop(&mut self) {
+        if let Some(input) = self.settings_input.as_mut() {
+            input.pop();
+        }
+    }
+
+    pub fn settings_cancel_input(&mut self) {
+        self.settings_input = None;
+    }
+
+    /// Applies the typed idle timeout. Out-of-range or empty input keeps the entry open with a
+    /// status message; returns whether the setting changed and needs saving.
+    pub fn settings_commit_input(&mut self) -> bool {
+        let Some(input) = self.settings_input.as_deref() else {
+            return false;
+        };
+        match input.parse::<u64>() {
+            Ok(seconds) if seconds <= MAX_IDLE_SECONDS => {
+                self.settings_input = None;
+                let changed = seconds != self.settings.idle_seconds;
+                self.settings.idle_seconds = seconds;
+                changed
+            }
+            _ => {
+                self.apply(AppEvent::StatusMessage {
+                    message: format!("Idle timeout must be 0–{MAX_IDLE_SECONDS} seconds"),
+                });
+                false
+            }
+        }
+    }
+
+    pub fn adjust_stale_seconds(&mut self, delta: i64) -> bool {
+        let current = self.settings.stale_seconds;
+        let adjusted = (current as i64 + delta).max(0) as u64;
+        if adjusted != current {
+            self.settings.stale_seconds = adjusted;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// PageUp/PageDown: moves the idle timeout in larger steps; other fields adjust as ←/→.
+    pub fn adjust_selected_setting_page(&mut self, forward: bool) -> bool {
+        if self.settings_cursor == SettingsField::IdleTimeout {
+            let step = if forward {
+                IDLE_TIMEOUT_PAGE_STEP
+            } else {
+                -IDLE_TIMEOUT_PAGE_STEP
+            };
+            self.adjust_idle_seconds(step)
+        } else {
+            self.adjust_selected_setting(forward)
+        }
+    }
+
+    pub fn adjust_selected_setting(&mut self, forward: bool) -> bool {
+        match self.settings_cursor {
+            SettingsField::IdleTimeout => self.adjust_idle_seconds(if forward { 1 } else { -1 }),
+            SettingsField::IdleRotate => {
+                let current = self.settings.idle_rotate_seconds;
+                let delta = if forward {
+                    IDLE_ROTATE_STEP
+                } else {
+                    -IDLE_ROTATE_STEP
+                };
+                self.settings.idle_rotate_seconds = (current as i64 + delta).max(0) as u64;
+                self.settings.idle_rotate_seconds != current
+            }
+            SettingsField::InputDelaysIdle => {
+                self.settings.input_delays_idle = !self.settings.input_delays_idle;
+                true
+            }
+            SettingsField::InputGrace => {
+                let current = self.settings.input_grace_seconds;
+                let delta = if forward {
+                    INPUT_GRACE_STEP
+                } else {
+                    -INPUT_GRACE_STEP
+                };
+                self.settings.input_grace_seconds = (current as i64 + delta).max(0) as u64;
+                self.settings.input_grace_seconds != current
+            }
+            SettingsField::StaleThreshold => {
+                self.adjust_stale_seconds(if forward { 1 } else { -1 })
+            }
+            SettingsField::DefaultDecoration => {
+                let changed = self.cycle_default_decoration(forward);
+                if changed {
+                    self.sync_current_with_defaults();
+                }
+                changed
+            }
+            SettingsField::BarScale => {
+                self.settings.bar_scale = self.settings.bar_scale.toggle();
+                true
+            }
+            SettingsField::BarMetric => {
+                let current = self.settings.bar_metric;
+                self.settings.bar_metric = if forward {
+                    current.next()
+                } else {
+                    current.prev()
+                };
+                true
+            }
+            SettingsField::DefaultMode => {
+                let changed = self.cycle_default_mode(forward);
+                if changed {
+                    self.sync_current_with_defaults();
+                }
+                changed
+            }
+            SettingsField::ViewLayout => {
+                self.settings.view_layout = self.settings.view_layout.toggle();
+                true
+            }
+            SettingsField::DefaultSort => {
+                let current = self.settings.default_sort;
+                self.settings.default_sort = if forward {
+                    current.next()
+                } else {
+                    current.prev()
+                };
+                self.sync_current_with_defaults();
+                self.settings.default_sort != current
+            }
+            SettingsField::Theme => {
+                let current = self.settings.theme;
+                self.settings.theme = if forward {
+                    current.next()
+                } else {
+                    current.prev()
+                };
+                theme::set_theme(self.settings.theme);
+                self.settings.theme != current
+            }
+            SettingsField::AbbreviateNumbers => {
+                self.settings.abbreviate_numbers = !self.settings.abbreviate_numbers;
+                true
+            }
+            SettingsField::RoleGlyphs => {
+                self.settings.role_glyphs = !self.settings.role_glyphs;
+                true
+            }
+            SettingsField::ShowTrends => {
+                self.settings.show_trends = !self.settings.show_trends;
+                true
+            }
+            SettingsField::ShowTotals => {
+                self.settings.show_totals = !self.settings.show_totals;
+                true
+            }
+            SettingsField::ShowTotalRow => {
+                self.settings.show_total_row = !self.settings.show_total_row;
+                true
+            }
+            SettingsField::ShowQuality => {
+                self.settings.show_quality = !self.settings.show_quality;
+                true
+            }
+            SettingsField::ShowCdh => {
+                self.settings.show_cdh = !self.settings.show_cdh;
+                true
+            }
+            SettingsField::ShowOverheal => {
+                self.settings.show_overheal = !self.settings.show_overheal;
+                true
+            }
+            SettingsField::ShowRollingDps => {
+                self.settings.show_rolling_dps = !self.settings.show_rolling_dps;
+                true
+            }
+            SettingsField::ShowActive => {
+                self.settings.show_active = !self.settings.show_active;
+                true
+            }
+            SettingsField::LiveTimer => {
+                self.settings.live_timer = !self.settings.live_timer;
+                true
+            }
+            SettingsField::HidePets => {
+                self.settings.hide_pets = !self.settings.hide_pets;
+                true
+            }
+            SettingsField::PartyOnly => {
+                self.settings.party_only = !self.settings.party_only;
+                true
+            }
+            SettingsField::HideZeroRows => {
+                self.settings.hide_zero_rows = !self.settings.hide_zero_rows;
+                true
+            }
+            SettingsField::KeepSelfRow => {
+                self.settings.keep_self_row = !self.settings.keep_self_row;
+                true
+            }
+            SettingsField::JsonExportFrames => {
+                self.settings.json_export_frames = !self.settings.json_export_frames;
+                true
+            }
+            SettingsField::MinRecordSeconds => {
+                let current = self.settings.min_record_seconds;
+                self.settings.min_record_seconds = if forward {
+                    current + 1
+                } else {
+                    current.saturating_sub(1)
+                };
+                self.settings.min_record_seconds != current
+            }
+            SettingsField::MinRecordDamage => {
+                let current = self.settings.min_record_damage;
+                self.settings.min_record_damage = if forward {
+                    current + MIN_RECORD_DAMAGE_STEP
+                } else {
+                    current.saturating_sub(MIN_RECORD_DAMAGE_STEP)
+                };
+                self.settings.min_record_damage != current
+            }
+            SettingsField::RetentionDays => {
+                let current = self.settings.retention_days;
+                self.settings.retention_days = if forward {
+                    current + 1
+                } else {
+                    current.saturating_sub(1)
+                };
+                self.settings.retention_days != current
+            }
+            SettingsField::MaxFrames => {
+                let current = self.settings.max_frames;
+                self.settings.max_frames = if forward {
+                    current + MAX_FRAMES_STEP
+                } else {
+                    current.saturating_sub(MAX_FRAMES_STEP)
+                };
+                self.settings.max_frames != current
+            }
+            SettingsField::StoreRawFrames => {
+                self.settings.store_raw_frames = !self.settings.store_raw_frames;
+                true
+            }
+        }
+    }
+
+    pub fn next_setting(&mut self) {
+        self.settings_cursor = self.settings_cursor.next();
+    }
+
+    pub fn prev_setting(&mut self) {
+        self.settings_cursor = self.settings_cursor.prev();
+    }
+
+    fn cycle_default_decoration(&mut self, forward: bool) -> bool {
+        let current = self.settings.default_decoration;
+        let next = if forward {
+            current.next()
+        } else {
+            current.prev()
+        };
+        if next != current {
+            self.settings.default_decoration = next;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn cycle_default_mode(&mut self, forward: bool) -> bool {
+        let current = self.settings.default_mode;
+        let next = if forward {
+            current.next()
+        } else {
+            current.prev()
+        };
+        if next != current {
+            self.settings.default_mode = next;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn sync_current_with_defaults(&mut self) {
+        self.decoration = self.settings.default_decoration;
+        self.mode = self.settings.default_mode;
+        self.sort_key = self.settings.default_sort;
+    }
+
+    /// Flags history entries that could not be read, so a partial list isn't mistaken for all.
+    fn note_skipped_history_entries(&mut self, skipped: usize) {
+        if skipped == 0 {
+            return;
+        }
+        let noun = if skipped == 1 { "entry" } else { "entries" };
+        self.status_message = Some((
+            format!("Skipped {skipped} unreadable history {noun} (see log)"),
+            Instant::now(),
+        ));
+    }
+
+    pub fn toggle_history(&mut self) -> bool {
+        if self.history.visible {
+            self.history.visible = false;
             self.history.reset();
             false
         } else {
@@ -434,19 +1806,23 @@ impl AppState {
             return;
         }
         match self.history.level {
-            HistoryPanelLevel::Dates => {
-                if self.history.days.is_empty() {
+            HistoryPanelLevel::Dates | HistoryPanelLevel::DaySummary => {
+                let visible = self.history.visible_day_indices();
+                if visible.is_empty() {
                     return;
                 }
-                let len = self.history.days.len() as i32;
-                let current = self.history.selected_day as i32;
-                let mut next = current + delta;
+                let len = visible.len() as i32;
+                let current = visible
+                    .iter()
+                    .position(|&idx| idx == self.history.selected_day)
+                    .unwrap_or(0) as i32;
+                let mut next = current.saturating_add(delta);
                 if next < 0 {
                     next = 0;
                 } else if next >= len {
                     next = len - 1;
                 }
-                self.history.selected_day = next as usize;
+                self.history.selected_day = visible[next as usize];
                 if let Some(day) = self.history.current_day() {
                     if day.encounters.is_empty() {
                         self.history.selected_encounter = 0;
@@ -455,6 +1831,15 @@ impl AppState {
                     }
                 }
             }
+            HistoryPanelLevel::Recent => self.history_move_recent_selection(delta),
+            HistoryPanelLevel::EncounterDetail if self.history.detail_from_recent => {
+                self.history_move_recent_selection(delta)
+            }
+            HistoryPanelLevel::NoteSearch => self.history_move_search_selection(delta),
+            HistoryPanelLevel::EncounterDetail if self.history.detail_from_search => {
+                self.history_move_search_selection(delta)
+            }
+            HistoryPanelLevel::JobStats => {}
             HistoryPanelLevel::Encounters | HistoryPanelLevel::EncounterDetail => {
                 if let Some(day) = self.history.current_day() {
                     if day.encounters.is_empty() {
@@ -462,7 +1847,7 @@ impl AppState {
                     }
                     let len = day.encounters.len() as i32;
                     let current = self.history.selected_encounter as i32;
-                    let mut next = current + delta;
+                    let mut next = current.saturating_add(delta);
                     if next < 0 {
                         next = 0;
                     } else if next >= len {
@@ -474,12 +1859,44 @@ impl AppState {
         }
     }
 
+    fn history_move_recent_selection(&mut self, delta: i32) {
+        let len = self.history.recent.as_ref().map_or(0, Vec::len) as i64;
+        if len == 0 {
+            return;
+        }
+        let next = (self.history.selected_recent as i64 + delta as i64).clamp(0, len - 1);
+        self.history.selected_recent = next as usize;
+    }
+
+    fn history_move_search_selection(&mut self, delta: i32) {
+        let Some(search) = self.history.note_search.as_mut() else {
+            return;
+        };
+        let len = search.results.as_ref().map_or(0, Vec::len) as i64;
+        if len == 0 {
+            return;
+        }
+        search.selected = (search.selected as i64 + delta as i64).clamp(0, len - 1) as usize;
+    }
+
+    /// Jumps to the first (`Home`) or last (`End`) entry of the current history list.
+    pub fn history_move_to_edge(&mut self, last: bool) {
+        self.history_move_selection(if last { i32::MAX } else { i32::MIN });
+    }
+
     pub fn history_enter(&mut self) {
         if !self.history.visible || self.history.loading {
             return;
         }
         match self.history.level {
             HistoryPanelLevel::Dates => {
+                if !self
+                    .history
+                    .visible_day_indices()
+                    .contains(&self.history.selected_day)
+                {
+                    return;
+                }
                 if let Some(day) = self.history.current_day() {
                     if day.encounters_loaded {
                         if !day.encounters.is_empty() {
@@ -497,26 +1914,491 @@ impl AppState {
                     self.history.level = HistoryPanelLevel::EncounterDetail;
                 }
             }
-            HistoryPanelLevel::EncounterDetail => {}
+            HistoryPanelLevel::Recent => {
+                let has_item = self
+                    .history
+                    .recent
+                    .as_ref()
+                    .is_some_and(|recent| self.history.selected_recent < recent.len());
+                if has_item {
+                    self.history.detail_from_recent = true;
+                    self.history.level = HistoryPanelLevel::EncounterDetail;
+                }
+            }
+            HistoryPanelLevel::NoteSearch => {
+                let has_item = self.history.note_search.as_ref().is_some_and(|search| {
+                    search
+                        .results
+                        .as_ref()
+                        .is_some_and(|results| search.selected < results.len())
+                });
+                if has_item {
+                    self.history.detail_from_search = true;
+                    self.history.level = HistoryPanelLevel::EncounterDetail;
+                }
+            }
+            HistoryPanelLevel::EncounterDetail
+            | HistoryPanelLevel::DaySummary
+            | HistoryPanelLevel::JobStats => {}
         }
     }
 
-    pub fn history_back(&mut self) {
-        if !self.history.visible {
+    /// Switches from the date list to the party-wide summary of the selected date.
+    pub fn history_open_day_summary(&mut self) {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::Dates
+            || !self
+                .history
+                .visible_day_indices()
+                .contains(&self.history.selected_day)
+        {
             return;
         }
-        match self.history.level {
-            HistoryPanelLevel::EncounterDetail => {
-                self.history.level = HistoryPanelLevel::Encounters;
-            }
-            HistoryPanelLevel::Encounters => {
-                self.history.level = HistoryPanelLevel::Dates;
-                self.history.selected_encounter = 0;
-            }
-            HistoryPanelLevel::Dates => {}
+        self.history.level = HistoryPanelLevel::DaySummary;
+    }
+
+    /// Switches from the date list to the per-job stats table.
+    pub fn history_open_job_stats(&mut self) {
+        if self.history.visible
+            && !self.history.loading
+            && self.history.level == HistoryPanelLevel::Dates
+        {
+            self.history.level = HistoryPanelLevel::JobStats;
         }
     }
-}
+
+    /// `L` in the date list: switches to the newest encounters across every date.
+    pub fn history_open_recent(&mut self) {
+        if self.history.visible
+            && !self.history.loading
+            && self.history.level == HistoryPanelLevel::Dates
+        {
+            self.history.level = HistoryPanelLevel::Recent;
+            self.history.selected_recent = 0;
+        }
+    }
+
+    /// `L` on the main view: opens the history panel straight on the recent list. Returns
+    /// false when the panel was already open.
+    pub fn open_recent_history(&mut self) -> bool {
+        if self.history.visible {
+            return false;
+        }
+        self.toggle_history();
+        self.history.level = HistoryPanelLevel::Recent;
+        true
+    }
+
+    /// `o` in the job stats view: orders the table by the next column.
+    pub fn history_cycle_job_stats_sort(&mut self) {
+        if !self.history.visible || self.history.level != HistoryPanelLevel::JobStats {
+            return;
+        }
+        let sort = self.history.job_stats_sort.next();
+        self.history.job_stats_sort = sort;
+        if let Some(stats) = self.history.job_stats.as_mut() {
+            sort.sort(stats);
+        }
+    }
+
+    pub fn history_open_filter(&mut self) {
+        if self.history.visible && self.history.level == HistoryPanelLevel::Dates {
+            self.history.filter.get_or_insert_with(String::new);
+        }
+    }
+
+    pub fn history_filter_push(&mut self, ch: char) {
+        if let Some(filter) = self.history.filter.as_mut() {
+            filter.push(ch);
+            self.history.sync_selection_with_filter();
+        }
+    }
+
+    pub fn history_filter_pop(&mut self) {
+        if let Some(filter) = self.history.filter.as_mut() {
+            filter.pop();
+            self.history.sync_selection_with_filter();
+        }
+    }
+
+    pub fn history_clear_filter(&mut self) {
+        self.history.filter = None;
+    }
+
+    /// Tab in the date filter: carries the typed text over into the note search.
+    pub fn history_filter_to_note_search(&mut self) {
+        let query = self.history.filter.take().unwrap_or_default();
+        self.history_open_note_search();
+        if let Some(search) = self.history.note_search.as_mut() {
+            search.query = query;
+        }
+    }
+
+    /// `/` outside the date list (where it filters dates): switches to the note search with
+    /// the query line focused, keeping the previous query and results.
+    pub fn history_open_note_search(&mut self) {
+        if !self.history.visible || self.history.loading {
+            return;
+        }
+        self.history.detail_from_recent = false;
+        self.history.detail_from_search = false;
+        self.history.level = HistoryPanelLevel::NoteSearch;
+        self.history
+            .note_search
+            .get_or_insert_with(NoteSearch::default)
+            .editing = true;
+    }
+
+    /// True while keystrokes should be routed into the note search query.
+    pub fn history_note_search_editing(&self) -> bool {
+        self.history.visible
+            && self.history.level == HistoryPanelLevel::NoteSearch
+            && self.confirm.is_none()
+            && self
+                .history
+                .note_search
+                .as_ref()
+                .is_some_and(|search| search.editing)
+    }
+
+    pub fn history_note_search_push(&mut self, ch: char) {
+        if let Some(search) = self.history.note_search.as_mut() {
+            search.query.push(ch);
+        }
+    }
+
+    pub fn history_note_search_pop(&mut self) {
+        if let Some(search) = self.history.note_search.as_mut() {
+            search.query.pop();
+        }
+    }
+
+    /// Enter on the query line: hands focus to the results and drops the old ones, so the
+    /// history task loop runs the search. A blank query leaves the line open.
+    pub fn history_submit_note_search(&mut self) {
+        if let Some(search) = self
+            .history
+            .note_search
+            .as_mut()
+            .filter(|search| !search.query.trim().is_empty())
+        {
+            search.editing = false;
+            search.results = None;
+            search.selected = 0;
+        }
+    }
+
+    /// Esc on the query line: back to the results when there are some, else to the dates.
+    pub fn history_cancel_note_search(&mut self) {
+        let Some(search) = self.history.note_search.as_mut() else {
+            return;
+        };
+        if search.results.is_some() {
+            search.editing = false;
+        } else {
+            self.history.note_search = None;
+            self.history.level = HistoryPanelLevel::Dates;
+        }
+    }
+
+    /// True while keystrokes should be routed into the date filter input.
+    pub fn history_filter_editing(&self) -> bool {
+        self.history.visible
+            && self.history.filter.is_some()
+            && self.history.level == HistoryPanelLevel::Dates
+            && self.confirm.is_none()
+    }
+
+    /// `n` in the detail view: opens the note editor on the encounter's current note.
+    pub fn history_begin_note(&mut self) {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::EncounterDetail
+        {
+            return;
+        }
+        if let Some(item) = self.history.current_encounter() {
+            self.history.note_draft = Some(NoteDraft {
+                key: item.key.clone(),
+                text: item.note.clone().unwrap_or_default(),
+            });
+        }
+    }
+
+    /// True while keystrokes should be routed into the note editor.
+    pub fn history_note_editing(&self) -> bool {
+        self.history.visible
+            && self.history.note_draft.is_some()
+            && self.history.level == HistoryPanelLevel::EncounterDetail
+            && self.confirm.is_none()
+    }
+
+    pub fn history_note_push(&mut self, ch: char) {
+        if let Some(draft) = self.history.note_draft.as_mut() {
+            if !ch.is_control() && draft.text.chars().count() < NOTE_MAX_CHARS {
+                draft.text.push(ch);
+            }
+        }
+    }
+
+    pub fn history_note_pop(&mut self) {
+        if let Some(draft) = self.history.note_draft.as_mut() {
+            draft.text.pop();
+        }
+    }
+
+    /// Ctrl-W: drops the last word and the spaces before it.
+    pub fn history_note_delete_word(&mut self) {
+        if let Some(draft) = self.history.note_draft.as_mut() {
+            let kept = draft.text.trim_end().rfind(' ').map_or(0, |idx| idx + 1);
+            draft.text.truncate(kept);
+        }
+    }
+
+    /// Ctrl-U: empties the line.
+    pub fn history_note_clear(&mut self) {
+        if let Some(draft) = self.history.note_draft.as_mut() {
+            draft.text.clear();
+        }
+    }
+
+    pub fn history_cancel_note(&mut self) {
+        self.history.note_draft = None;
+    }
+
+    /// Enter in the note editor: closes it and returns the key of the encounter the editor was
+    /// opened on with the trimmed note to store (an empty note removes it). The loaded copies
+    /// pick up the note once the store confirms the write.
+    pub fn history_commit_note(&mut self) -> Option<(Vec<u8>, Option<String>)> {
+        if !self.history_note_editing() {
+            return None;
+        }
+        let draft = self.history.note_draft.take()?;
+        let note = Some(draft.text.trim().to_string()).filter(|note| !note.is_empty());
+        Some((draft.key, note))
+    }
+
+    /// `X` in the date list: opens the typed confirmation for wiping all history.
+    pub fn history_request_clear_all(&mut self) {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::Dates
+            || self.history.days.is_empty()
+        {
+            return;
+        }
+        self.confirm = Some(Confirm::clear_all(false, String::new()));
+    }
+
+    /// Asks to confirm deleting the highlighted encounter.
+    pub fn history_request_delete(&mut self) {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::Encounters
+        {
+            return;
+        }
+        let Some(date_id) = self.history.current_day().map(|day| day.iso_date.clone()) else {
+            return;
+        };
+        let Some(item) = self.history.current_encounter() else {
+            return;
+        };
+        let message = format!("Delete {}?", item.display_title);
+        let action = PendingAction::DeleteEncounter {
+            date_id,
+            key: item.key.clone(),
+        };
+        self.request_confirm(message, action);
+    }
+
+    /// `P` in the date list: asks to confirm pruning history past the retention setting now
+    /// instead of at the next launch.
+    pub fn history_request_prune(&mut self) {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::Dates
+        {
+            return;
+        }
+        let retention_days = self.settings.retention_days;
+        if retention_days == 0 {
+            self.apply(AppEvent::StatusMessage {
+                message: "History is kept forever; set a retention period to prune".to_string(),
+            });
+            return;
+        }
+        self.request_confirm(
+            format!("Delete unpinned encounters older than {retention_days} days?"),
+            PendingAction::PruneHistory { retention_days },
+        );
+    }
+
+    /// Opens the confirmation modal; the action only runs once it is accepted.
+    pub fn request_confirm(&mut self, message: String, action: PendingAction) {
+        self.confirm = Some(Confirm {
+            message,
+            action,
+            typed: None,
+        });
+    }
+
+    /// Asks whether to keep recording the encounter a crashed session left unsaved.
+    pub fn request_resume(&mut self, title: &str) {
+        self.request_confirm(
+            format!("Resume \"{title}\" from the last session? No saves it as finished."),
+            PendingAction::ResumeEncounter,
+        );
+    }
+
+    /// True while the open modal takes text for its confirmation word.
+    pub fn confirm_typing(&self) -> bool {
+        self.confirm
+            .as_ref()
+            .is_some_and(|confirm| confirm.typed.is_some())
+    }
+
+    pub fn confirm_push(&mut self, ch: char) {
+        if let Some(typed) = self.confirm.as_mut().and_then(|c| c.typed.as_mut()) {
+            typed.push(ch);
+        }
+    }
+
+    pub fn confirm_pop(&mut self) {
+        if let Some(typed) = self.confirm.as_mut().and_then(|c| c.typed.as_mut()) {
+            typed.pop();
+        }
+    }
+
+    /// Tab in the clear-all modal: toggles sparing pinned encounters.
+    pub fn confirm_toggle_keep_pinned(&mut self) {
+        let Some(confirm) = self.confirm.as_mut() else {
+            return;
+        };
+        if let PendingAction::ClearAll { keep_pinned } = confirm.action {
+            let typed = confirm.typed.take().unwrap_or_default();
+            *confirm = Confirm::clear_all(!keep_pinned, typed);
+        }
+    }
+
+    /// True while the confirmation modal is open and takes every key.
+    pub fn confirm_open(&self) -> bool {
+        self.confirm.is_some()
+    }
+
+    /// Closes the modal, handing back the declined action for the ones that act on a "no".
+    pub fn confirm_cancel(&mut self) -> Option<PendingAction> {
+        self.confirm.take().map(|confirm| confirm.action)
+    }
+
+    /// Closes the modal and hands back its action for the event loop to run, after putting
+    /// the history panel into its loading state for the reload that follows. The modal stays
+    /// open while its confirmation word hasn't been typed.
+    pub fn confirm_accept(&mut self) -> Option<PendingAction> {
+        if !self.confirm.as_ref()?.word_typed() {
+            return None;
+        }
+        let action = self.confirm.take()?.action;
+        if self.history.visible && action != PendingAction::ResumeEncounter {
+            self.history.recent = None;
+            self.history.note_search = None;
+            if matches!(action, PendingAction::ClearAll { .. }) {
+                self.history.selected_day = 0;
+                self.history.selected_encounter = 0;
+            }
+            self.history_set_loading();
+        }
+        Some(action)
+    }
+
+    /// Flips the pin on the selected encounter and re-sorts the day so pinned entries lead,
+    /// keeping the selection on the same encounter. Returns `(key, pinned)` to persist.
+    pub fn history_toggle_pin(&mut self) -> Option<(Vec<u8>, bool)> {
+        if !self.history.visible
+            || self.history.loading
+            || self.history.level != HistoryPanelLevel::Encounters
+        {
+            return None;
+        }
+        let selected = self.history.selected_encounter;
+        let day = self.history.days.get_mut(self.history.selected_day)?;
+        let item = day.encounters.get_mut(selected)?;
+        item.pinned = !item.pinned;
+        let (key, pinned) = (item.key.clone(), item.pinned);
+        day.encounters.sort_by_key(|enc| {
+            (
+                std::cmp::Reverse(enc.pinned),
+                std::cmp::Reverse(enc.last_seen_ms),
+            )
+        });
+        if let Some(idx) = day.encounters.iter().position(|enc| enc.key == key) {
+            self.history.selected_encounter = idx;
+        }
+        Some((key, pinned))
+    }
+
+    /// `z` in the date or encounter list: clears an active zone filter, or returns the zone of
+    /// the selected encounter (the newest of the selected date in the date list) to filter by.
+    pub fn history_toggle_zone_filter(&mut self) -> Option<String> {
+        if !self.history.visible
+            || self.history.loading
+            || !matches!(
+                self.history.level,
+                HistoryPanelLevel::Dates | HistoryPanelLevel::Encounters
+            )
+        {
+            return None;
+        }
+        if self.history.zone_filter.take().is_some() {
+            self.history.invalidate_encounter_lists();
+            return None;
+        }
+        let zone = self
+            .history
+            .current_day()
+            .and_then(|day| day.encounters.get(self.history.selected_encounter))
+            .map(|enc| enc.zone.clone());
+        if zone.is_none() {
+            self.apply(AppEvent::StatusMessage {
+                message: "Open a date's encounters first to filter by zone".to_string(),
+            });
+        } else {
+            self.history_set_loading();
+        }
+        zone
+    }
+
+    pub fn history_back(&mut self) {
+        if !self.history.visible {
+            return;
+        }
+        match self.history.level {
+            HistoryPanelLevel::EncounterDetail if self.history.detail_from_recent => {
+                self.history.detail_from_recent = false;
+                self.history.level = HistoryPanelLevel::Recent;
+            }
+            HistoryPanelLevel::EncounterDetail if self.history.detail_from_search => {
+                self.history.detail_from_search = false;
+                self.history.level = HistoryPanelLevel::NoteSearch;
+            }
+            HistoryPanelLevel::EncounterDetail => {
+                self.history.level = HistoryPanelLevel::Encounters;
+            }
+            HistoryPanelLevel::Encounters => {
+                self.history.level = HistoryPanelLevel::Dates;
+                self.history.selected_encounter = 0;
+            }
+            HistoryPanelLevel::DaySummary
+            | HistoryPanelLevel::JobStats
+            | HistoryPanelLevel::Recent
+            | HistoryPanelLevel::NoteSearch => {
+                self.history.level = HistoryPanelLevel::Dates;
+            }
+            HistoryPanelLevel::Dates => {}
+        }
+    }
+}
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct EncounterSummary {
@@ -527,9 +2409,61 @@ pub struct EncounterSummary {
     pub damage: String,
     pub enchps: String,
     pub healed: String,
+    #[serde(default)]
+    pub damage_taken: String,
     pub is_active: bool,
 }
 
+impl EncounterSummary {
+    /// The encounter totals shaped as a table row, for the pinned party-total line.
+    pub fn total_row(&self) -> CombatantRow {
+        CombatantRow {
+            name: "Party total".to_string(),
+            encdps: to_f64_any(&self.encdps),
+            encdps_str: self.encdps.clone(),
+            damage: to_f64_any(&self.damage),
+            damage_str: self.damage.clone(),
+            share: 1.0,
+            share_str: "100%".to_string(),
+            enchps: to_f64_any(&self.enchps),
+            enchps_str: self.enchps.clone(),
+            healed: to_f64_any(&self.healed),
+            healed_str: self.healed.clone(),
+            heal_share: 1.0,
+            heal_share_str: "100%".to_string(),
+            damage_taken: to_f64_any(&self.damage_taken),
+            damage_taken_str: self.damage_taken.clone(),
+            ..CombatantRow::default()
+        }
+    }
+}
+
+/// The party-total line for the rows left after hiding some combatants, so its numbers and
+/// shares match what the table shows.
+fn summed_total_row(rows: &[CombatantRow]) -> CombatantRow {
+    let sum = |value: fn(&CombatantRow) -> f64| rows.iter().map(value).sum::<f64>();
+    let share = sum(|row| row.share);
+    let heal_share = sum(|row| row.heal_share);
+    CombatantRow {
+        name: "Party total".to_string(),
+        encdps: sum(|row| row.encdps),
+        encdps_str: group_thousands(sum(|row| row.encdps)),
+        damage: sum(|row| row.damage),
+        damage_str: group_thousands(sum(|row| row.damage)),
+        share,
+        share_str: format!("{:.1}%", share * 100.0),
+        enchps: sum(|row| row.enchps),
+        enchps_str: group_thousands(sum(|row| row.enchps)),
+        healed: sum(|row| row.healed),
+        healed_str: group_thousands(sum(|row| row.healed)),
+        heal_share,
+        heal_share_str: format!("{:.1}%", heal_share * 100.0),
+        damage_taken: sum(|row| row.damage_taken),
+        damage_taken_str: group_thousands(sum(|row| row.damage_taken)),
+        ..CombatantRow::default()
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CombatantRow {
     pub name: String,
@@ -547,33 +2481,344 @@ pub struct CombatantRow {
     pub heal_share: f64,
     pub heal_share_str: String,
     pub overheal_pct: String,
+    /// Absolute healing lost to overheal (`overHeal`), to weigh a high Overheal% on small heals.
+    #[serde(default)]
+    pub overheal: f64,
+    #[serde(default)]
+    pub overheal_str: String,
+    #[serde(default)]
+    pub damage_taken: f64,
+    #[serde(default)]
+    pub damage_taken_str: String,
     pub crit: String,
     pub dh: String,
+    /// Critical direct hit rate as reported, e.g. "7%"; empty when the payload lacks it.
+    #[serde(default)]
+    pub cdh: String,
+    /// How long this combatant has been in the fight as ACT reports it, e.g. "05:12"; empty
+    /// when the payload lacks it.
+    #[serde(default)]
+    pub duration: String,
     pub deaths: String,
+    /// Biggest single hit as reported, e.g. "Fire IV-12,345".
+    #[serde(default)]
+    pub max_hit_str: String,
+    #[serde(default)]
+    pub max_hit: f64,
+    /// How `encdps` moved since the previous update; `None` for combatants that just appeared.
+    #[serde(skip)]
+    pub trend: Option<Ordering>,
+    /// Joined the current encounter within the last couple of seconds; set on snapshots.
+    #[serde(skip)]
+    pub recently_joined: bool,
+    /// Damage per second over roughly the last 15 seconds; `None` until enough samples arrive.
+    #[serde(skip)]
+    pub rolling_dps: Option<f64>,
+    /// The local player: ACT's "YOU" alias, or a row named like `character_name`.
+    #[serde(default)]
+    pub is_self: bool,
+    /// Party membership, for the few payloads that report it; `None` when not sent.
+    #[serde(default)]
+    pub in_party: Option<bool>,
+}
+
+impl CombatantRow {
+    /// The damage part of `max_hit_str`, without the ability name.
+    pub fn max_hit_value(&self) -> &str {
+        self.max_hit_str
+            .rsplit_once('-')
+            .map(|(_, value)| value)
+            .unwrap_or(&self.max_hit_str)
+            .trim()
+    }
+
+    /// Crit/DH "quality": the mean of crit% and DH%, or just the one that was reported.
+    pub fn quality_pct(&self) -> Option<f64> {
+        match (parse_percent(&self.crit), parse_percent(&self.dh)) {
+            (Some(crit), Some(dh)) => Some((crit + dh) / 2.0),
+            (one, other) => one.or(other),
+        }
+    }
+}
+
+/// ENCDPS summed per role as `(tank, healer, dps)`, for judging party balance at a glance.
+pub fn role_encdps_totals(rows: &[CombatantRow]) -> (f64, f64, f64) {
+    rows.iter().fold(
+        (0.0, 0.0, 0.0),
+        |(tank, healer, dps), row| match theme::job_role(&row.job) {
+            Role::Tank => (tank + row.encdps, healer, dps),
+            Role::Healer => (tank, healer + row.encdps, dps),
+            Role::Dps => (tank, healer, dps + row.encdps),
+        },
+    )
+}
+
+/// Shown instead of a name that has no placeholder, such as a death of someone not in the table.
+pub const ANONYMOUS_NAME: &str = "Player";
+
+/// Per-role placeholder for every name in `rows` ("Tank 1", "Healer 2", "DPS 3"), numbered in
+/// name order so a player keeps theirs however the table is sorted; the local player maps to
+/// "YOU".
+pub fn placeholder_names(rows: &[CombatantRow]) -> HashMap<String, String> {
+    let mut sorted: Vec<&CombatantRow> = rows.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    let (mut tanks, mut healers, mut dps) = (0, 0, 0);
+    let mut names = HashMap::new();
+    for row in sorted {
+        if names.contains_key(&row.name) {
+            continue;
+        }
+        let placeholder = if row.is_self || row.name == SELF_ALIAS {
+            SELF_ALIAS.to_string()
+        } else {
+            match theme::job_role(&row.job) {
+                Role::Tank => {
+                    tanks += 1;
+                    format!("Tank {tanks}")
+                }
+                Role::Healer => {
+                    healers += 1;
+                    format!("Healer {healers}")
+                }
+                Role::Dps => {
+                    dps += 1;
+                    format!("DPS {dps}")
+                }
+            }
+        };
+        names.insert(row.name.clone(), placeholder);
+    }
+    names
+}
+
+/// `name` as [`placeholder_names`] maps it, or [`ANONYMOUS_NAME`] when it is not in the map.
+pub fn anonymized_name(names: &HashMap<String, String>, name: &str) -> String {
+    names
+        .get(name)
+        .cloned()
+        .unwrap_or_else(|| ANONYMOUS_NAME.to_string())
+}
+
+/// Replaces names with their [`placeholder_names`]. Applied to copies for display only.
+pub fn anonymize_rows(rows: &mut [CombatantRow]) {
+    let names = placeholder_names(rows);
+    for row in rows {
+        row.name = anonymized_name(&names, &row.name);
+    }
+}
+
+/// Keeps the party: rows flagged as members when the payload says who is in the party (plus
+/// the local player), otherwise the `PARTY_SIZE` biggest damage dealers.
+fn keep_party(rows: &mut Vec<CombatantRow>) {
+    if rows.iter().any(|row| row.in_party.is_some()) {
+        rows.retain(|row| row.in_party == Some(true) || row.is_self);
+    } else if rows.len() > PARTY_SIZE {
+        rows.sort_by(|a, b| b.damage.partial_cmp(&a.damage).unwrap_or(Ordering::Equal));
+        rows.truncate(PARTY_SIZE);
+    }
+}
+
+/// Matches `pet_names` case-insensitively, ignoring an owner suffix such as "Eos (Alice)";
+/// anything named like "Limit Break" counts too, whatever job ACT reports for it.
+fn is_pet_or_limit_break(name: &str, pet_names: &[String]) -> bool {
+    let base = name.split(" (").next().unwrap_or(name).trim();
+    base.to_ascii_lowercase().contains("limit break")
+        || pet_names.iter().any(|pet| pet.eq_ignore_ascii_case(base))
+}
+
+/// Damage per second between the oldest and newest buffered samples, once they span
+/// `ROLLING_DPS_MIN_SPAN`.
+fn rolling_dps(samples: &VecDeque<(Instant, f64)>) -> Option<f64> {
+    let (&(first_at, first), &(last_at, last)) = (samples.front()?, samples.back()?);
+    let span = last_at.saturating_duration_since(first_at);
+    if span < ROLLING_DPS_MIN_SPAN {
+        return None;
+    }
+    Some((last - first).max(0.0) / span.as_secs_f64())
+}
+
+/// Tags each incoming row with how its ENCDPS moved relative to `previous`, matched by name.
+fn with_trends(previous: &[CombatantRow], mut rows: Vec<CombatantRow>) -> Vec<CombatantRow> {
+    let before: HashMap<&str, f64> = previous
+        .iter()
+        .map(|row| (row.name.as_str(), row.encdps))
+        .collect();
+    for row in &mut rows {
+        row.trend = before
+            .get(row.name.as_str())
+            .and_then(|prev| row.encdps.partial_cmp(prev));
+    }
+    rows
 }
 
 #[derive(Debug)]
 pub enum AppEvent {
     Connected,
     Disconnected,
+    /// The WebSocket client will dial again after `delay_ms`; `attempt` counts from 1.
+    Reconnecting {
+        attempt: u32,
+        delay_ms: u64,
+    },
     CombatData {
         encounter: EncounterSummary,
         rows: Vec<CombatantRow>,
     },
+    /// `skipped` counts corrupt index entries left out of `days`.
     HistoryDatesLoaded {
         days: Vec<HistoryDay>,
+        skipped: usize,
     },
     HistoryEncountersLoaded {
         date_id: String,
         encounters: Vec<HistoryEncounterItem>,
+        skipped: usize,
     },
     HistoryEncounterLoaded {
         key: Vec<u8>,
         record: EncounterRecord,
     },
+    HistoryDayAggregated {
+        date_id: String,
+        aggregate: DayAggregate,
+    },
+    /// The store accepted the note of encounter `key`.
+    HistoryNoteSaved {
+        key: Vec<u8>,
+        note: Option<String>,
+    },
+    /// Note search matches for `query`, newest first.
+    HistoryNotesSearched {
+        query: String,
+        encounters: Vec<HistoryEncounterItem>,
+        skipped: usize,
+    },
+    /// The newest encounters across all dates, for the recent list.
+    HistoryRecentLoaded {
+        encounters: Vec<HistoryEncounterItem>,
+        skipped: usize,
+    },
+    /// The local player's per-job stats across all of history.
+    HistoryJobStatsLoaded {
+        stats: Vec<JobStat>,
+        skipped: usize,
+    },
+    /// Answer to a zone filter request: every known zone, to pick `zone` from.
+    HistoryZonesLoaded {
+        zone: String,
+        zones: Vec<ZoneSummary>,
+        skipped: usize,
+    },
     HistoryError {
         message: String,
     },
+    StatusMessage {
+        message: String,
+    },
+    ConfigError {
+        message: String,
+    },
+    /// Stored encounter count for the about panel.
+    HistoryEncounterCount {
+        count: usize,
+    },
+    DeathLogged {
+        name: String,
+        ms: u64,
+    },
+    /// Game language reported in answer to the handshake's `getLanguage` call.
+    GameLanguage {
+        language: String,
+    },
+    RecentEncounterLoaded {
+        record: Option<EncounterRecord>,
+    },
+    /// A recorded frame played back by the replay driver; dropped unless a replay is running.
+    ReplayFrame {
+        encounter: EncounterSummary,
+        rows: Vec<CombatantRow>,
+    },
+    ReplayFinished,
+}
+
+/// Progress of the WebSocket reconnect loop, for the disconnected status.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReconnectStatus {
+    pub attempt: u32,
+    /// Time left until the next attempt; 0 while it is being made.
+    pub retry_in_ms: u64,
+}
+
+/// The history encounter currently being replayed into the live table.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplayInfo {
+    pub title: String,
+    /// Playback multiplier over the recorded cadence.
+    pub speed: u32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecentDeath {
+    pub name: String,
+    /// Unix milliseconds, from the log line when available.
+    pub ms: u64,
+}
+
+/// Idle-screen highlights derived from the most recent stored encounter.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IdleHighlights {
+    pub title: String,
+    pub crit_leaders: Vec<CritLeader>,
+    /// Party ENCDPS per elapsed second, for the Status scene sparkline.
+    pub dps_timeline: Vec<(u64, f64)>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CritLeader {
+    pub name: String,
+    /// Shown instead of `name` while names are anonymized.
+    pub placeholder: String,
+    pub job: String,
+    pub crit: f64,
+    pub crit_str: String,
+}
+
+impl IdleHighlights {
+    const CRIT_LEADERS: usize = 3;
+
+    pub fn from_record(record: &EncounterRecord) -> Self {
+        let title = [&record.encounter.title, &record.encounter.zone]
+            .into_iter()
+            .map(|s| s.trim())
+            .find(|s| !s.is_empty())
+            .unwrap_or("Unknown Encounter")
+            .to_string();
+        let placeholders = placeholder_names(&record.rows);
+        let mut crit_leaders: Vec<CritLeader> = record
+            .rows
+            .iter()
+            .filter(|row| !row.crit.trim().is_empty())
+            .map(|row| CritLeader {
+                name: row.name.clone(),
+                placeholder: anonymized_name(&placeholders, &row.name),
+                job: row.job.clone(),
+                crit: to_f64_any(&row.crit),
+                crit_str: row.crit.clone(),
+            })
+            .collect();
+        crit_leaders.sort_by(|a, b| {
+            b.crit
+                .partial_cmp(&a.crit)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        crit_leaders.truncate(Self::CRIT_LEADERS);
+        Self {
+            title,
+            crit_leaders,
+            dps_timeline: record.dps_timeline(),
+        }
+    }
 }
 
 // Known job codes for party filtering and color mapping
@@ -601,24 +2846,114 @@ pub fn known_jobs() -> &'static HashSet<&'static str> {
 pub enum SettingsField {
     #[default]
     IdleTimeout,
+    IdleRotate,
+    InputDelaysIdle,
+    InputGrace,
+    StaleThreshold,
     DefaultDecoration,
+    BarScale,
+    BarMetric,
     DefaultMode,
+    ViewLayout,
+    DefaultSort,
+    Theme,
+    AbbreviateNumbers,
+    RoleGlyphs,
+    ShowTrends,
+    ShowTotals,
+    ShowTotalRow,
+    ShowQuality,
+    ShowCdh,
+    ShowOverheal,
+    ShowRollingDps,
+    ShowActive,
+    LiveTimer,
+    HidePets,
+    PartyOnly,
+    HideZeroRows,
+    KeepSelfRow,
+    JsonExportFrames,
+    MinRecordSeconds,
+    MinRecordDamage,
+    RetentionDays,
+    MaxFrames,
+    StoreRawFrames,
 }
 
 impl SettingsField {
     pub fn next(self) -> Self {
         match self {
-            SettingsField::IdleTimeout => SettingsField::DefaultDecoration,
-            SettingsField::DefaultDecoration => SettingsField::DefaultMode,
-            SettingsField::DefaultMode => SettingsField::IdleTimeout,
+            SettingsField::IdleTimeout => SettingsField::IdleRotate,
+            SettingsField::IdleRotate => SettingsField::InputDelaysIdle,
+            SettingsField::InputDelaysIdle => SettingsField::InputGrace,
+            SettingsField::InputGrace => SettingsField::StaleThreshold,
+            SettingsField::StaleThreshold => SettingsField::DefaultDecoration,
+            SettingsField::DefaultDecoration => SettingsField::BarScale,
+            SettingsField::BarScale => SettingsField::BarMetric,
+            SettingsField::BarMetric => SettingsField::DefaultMode,
+            SettingsField::DefaultMode => SettingsField::ViewLayout,
+            SettingsField::ViewLayout => SettingsField::DefaultSort,
+            SettingsField::DefaultSort => SettingsField::Theme,
+            SettingsField::Theme => SettingsField::AbbreviateNumbers,
+            SettingsField::AbbreviateNumbers => SettingsField::RoleGlyphs,
+            SettingsField::RoleGlyphs => SettingsField::ShowTrends,
+            SettingsField::ShowTrends => SettingsField::ShowTotals,
+            SettingsField::ShowTotals => SettingsField::ShowTotalRow,
+            SettingsField::ShowTotalRow => SettingsField::ShowQuality,
+            SettingsField::ShowQuality => SettingsField::ShowCdh,
+            SettingsField::ShowCdh => SettingsField::ShowOverheal,
+            SettingsField::ShowOverheal => SettingsField::ShowRollingDps,
+            SettingsField::ShowRollingDps => SettingsField::ShowActive,
+            SettingsField::ShowActive => SettingsField::LiveTimer,
+            SettingsField::LiveTimer => SettingsField::HidePets,
+            SettingsField::HidePets => SettingsField::PartyOnly,
+            SettingsField::PartyOnly => SettingsField::HideZeroRows,
+            SettingsField::HideZeroRows => SettingsField::KeepSelfRow,
+            SettingsField::KeepSelfRow => SettingsField::JsonExportFrames,
+            SettingsField::JsonExportFrames => SettingsField::MinRecordSeconds,
+            SettingsField::MinRecordSeconds => SettingsField::MinRecordDamage,
+            SettingsField::MinRecordDamage => SettingsField::RetentionDays,
+            SettingsField::RetentionDays => SettingsField::MaxFrames,
+            SettingsField::MaxFrames => SettingsField::StoreRawFrames,
+            SettingsField::StoreRawFrames => SettingsField::IdleTimeout,
         }
     }
 
     pub fn prev(self) -> Self {
         match self {
-            SettingsField::IdleTimeout => SettingsField::DefaultMode,
-            SettingsField::DefaultDecoration => SettingsField::IdleTimeout,
-            SettingsField::DefaultMode => SettingsField::DefaultDecoration,
+            SettingsField::IdleTimeout => SettingsField::StoreRawFrames,
+            SettingsField::IdleRotate => SettingsField::IdleTimeout,
+            SettingsField::InputDelaysIdle => SettingsField::IdleRotate,
+            SettingsField::InputGrace => SettingsField::InputDelaysIdle,
+            SettingsField::StaleThreshold => SettingsField::InputGrace,
+            SettingsField::DefaultDecoration => SettingsField::StaleThreshold,
+            SettingsField::BarScale => SettingsField::DefaultDecoration,
+            SettingsField::BarMetric => SettingsField::BarScale,
+            SettingsField::DefaultMode => SettingsField::BarMetric,
+            SettingsField::ViewLayout => SettingsField::DefaultMode,
+            SettingsField::DefaultSort => SettingsField::ViewLayout,
+            SettingsField::Theme => SettingsField::DefaultSort,
+            SettingsField::AbbreviateNumbers => SettingsField::Theme,
+            SettingsField::RoleGlyphs => SettingsField::AbbreviateNumbers,
+            SettingsField::ShowTrends => SettingsField::RoleGlyphs,
+            SettingsField::ShowTotals => SettingsField::ShowTrends,
+            SettingsField::ShowTotalRow => SettingsField::ShowTotals,
+            SettingsField::ShowQuality => SettingsField::ShowTotalRow,
+            SettingsField::ShowCdh => SettingsField::ShowQuality,
+            SettingsField::ShowOverheal => SettingsField::ShowCdh,
+            SettingsField::ShowRollingDps => SettingsField::ShowOverheal,
+            SettingsField::ShowActive => SettingsField::ShowRollingDps,
+            SettingsField::LiveTimer => SettingsField::ShowActive,
+            SettingsField::HidePets => SettingsField::LiveTimer,
+            SettingsField::PartyOnly => SettingsField::HidePets,
+            SettingsField::HideZeroRows => SettingsField::PartyOnly,
+            SettingsField::KeepSelfRow => SettingsField::HideZeroRows,
+            SettingsField::JsonExportFrames => SettingsField::KeepSelfRow,
+            SettingsField::MinRecordSeconds => SettingsField::JsonExportFrames,
+            SettingsField::MinRecordDamage => SettingsField::MinRecordSeconds,
+            SettingsField::RetentionDays => SettingsField::MinRecordDamage,
+            SettingsField::MaxFrames => SettingsField::RetentionDays,
+            SettingsField::StoreRawFrames => SettingsField::MaxFrames,
         }
     }
 }
@@ -627,7 +2962,90 @@ impl SettingsField {
 pub struct AppSettings {
     pub idle_seconds: u64,
     pub default_decoration: Decoration,
+    pub bar_scale: BarScale,
+    /// What the bars measure, independently of the view mode and sort column.
+    pub bar_metric: BarMetric,
     pub default_mode: ViewMode,
+    /// One table in the current mode, or DPS and HEAL side by side.
+    pub view_layout: ViewLayout,
+    pub default_sort: SortKey,
+    pub ws_url: String,
+    /// Not editable in the settings pane; marks the local player when ACT doesn't send "YOU".
+    pub character_name: String,
+    /// Not editable in the settings pane; sent on the WebSocket handshake when set.
+    pub ws_auth_token: Option<String>,
+    /// Send `ws_auth_token` as a `?token=` query parameter instead of an Authorization header.
+    pub ws_auth_query: bool,
+    /// Not editable in the settings pane; link copied with `w` in the history detail view.
+    pub search_url_template: String,
+    /// Not editable in the settings pane; decimal separator of the numbers IINACT sends.
+    pub number_locale: NumberLocale,
+    pub stale_seconds: u64,
+    /// Seconds each idle scene stays up before the next one is shown; 0 disables rotation.
+    pub idle_rotate_seconds: u64,
+    /// Key presses, clicks and scrolls within `input_grace_seconds` keep idle mode from starting.
+    pub input_delays_idle: bool,
+    pub input_grace_seconds: u64,
+    /// Not editable in the settings pane; milliseconds between redraws, see `redraw_interval`.
+    pub redraw_ms: u64,
+    pub theme: Theme,
+    /// Render large totals as `12.3M` instead of the raw `12,345,678`; storage is unaffected.
+    pub abbreviate_numbers: bool,
+    /// Put a role-colored glyph (■ tank, ◆ healer, ● DPS; T/H/D without Unicode) before names.
+    pub role_glyphs: bool,
+    /// Show a ▲/▼ next to ENCDPS when a combatant's DPS rose or fell since the last update.
+    pub show_trends: bool,
+    /// Add an absolute Damage (DPS mode) or Healed (Heal mode) column to the wider table layouts.
+    pub show_totals: bool,
+    /// Pin a party-total row (ENCDPS/Damage, ENCHPS/Healed or ENCHPS/Taken) below the live table.
+    pub show_total_row: bool,
+    /// Add a Q% column (mean of crit% and DH%) to the wide DPS tables.
+    pub show_quality: bool,
+    /// Add a CDH% column (critical direct hit rate) to the wide DPS tables.
+    pub show_cdh: bool,
+    /// Add the absolute overheal amount next to Overheal% in the wide Heal tables.
+    pub show_overheal: bool,
+    /// Add an rDPS column (damage per second over the last 15 seconds) after ENCDPS.
+    pub show_rolling_dps: bool,
+    /// Add an Active column with each combatant's time in the fight to the wide tables.
+    pub show_active: bool,
+    /// Show a locally ticking combat timer in the header instead of ACT's duration string.
+    pub live_timer: bool,
+    /// Show role placeholders instead of combatant names, for screenshots and streams.
+    pub anonymize: bool,
+    /// Start in compact mode (table only, no header or footer); toggled from the main screen.
+    pub compact: bool,
+    /// Draw the three-line header above the live table; toggled from the main screen.
+    pub show_header: bool,
+    /// Draw the key hints and status footer below the live table; toggled from the main screen.
+    pub show_footer: bool,
+    /// Drop `pet_names` entries and the Limit Break pseudo-combatant from the live table.
+    pub hide_pets: bool,
+    /// Show only party members (alliance and open-world players are dropped); see `keep_party`.
+    pub party_only: bool,
+    /// Drop rows whose metric for the current mode is zero; display only, after share math.
+    pub hide_zero_rows: bool,
+    /// With `hide_zero_rows`, still show the local player's row at zero.
+    pub keep_self_row: bool,
+    /// Not editable in the settings pane; edit `pet_names` in the config file.
+    pub pet_names: Vec<String>,
+    /// Not editable in the settings pane; OverlayPlugin events to subscribe to on connect.
+    pub subscribe_events: Vec<String>,
+    /// Whether JSON exports keep every recorded frame (large) or just the final table.
+    pub json_export_frames: bool,
+    /// Encounters shorter than this (seconds) or below `min_record_damage` are not stored.
+    pub min_record_seconds: u64,
+    pub min_record_damage: u64,
+    /// History older than this many days is pruned at startup (pinned encounters excepted).
+    pub retention_days: u64,
+    /// Frames kept per recorded encounter before they are thinned out; 0 keeps all of them.
+    pub max_frames: u64,
+    /// Keep every frame's raw CombatData JSON; off stores only the parsed data plus the last payload.
+    pub store_raw_frames: bool,
+    /// Not editable in the settings pane; carried through so saving keeps the user's keys.
+    pub keybindings: KeybindingsConfig,
+    /// Not editable in the settings pane; custom live table columns from the config file.
+    pub columns: ColumnsConfig,
 }
 
 impl Default for AppSettings {
@@ -635,7 +3053,52 @@ impl Default for AppSettings {
         Self {
             idle_seconds: 5,
             default_decoration: Decoration::Underline,
+            bar_scale: BarScale::default(),
+            bar_metric: BarMetric::default(),
             default_mode: ViewMode::Dps,
+            view_layout: ViewLayout::default(),
+            default_sort: SortKey::Metric,
+            ws_url: WS_URL_DEFAULT.to_string(),
+            character_name: String::new(),
+            ws_auth_token: None,
+            ws_auth_query: false,
+            search_url_template: config::default_search_url_template(),
+            number_locale: NumberLocale::default(),
+            stale_seconds: 10,
+            idle_rotate_seconds: 15,
+            input_delays_idle: true,
+            input_grace_seconds: 30,
+            redraw_ms: 100,
+            theme: Theme::default(),
+            abbreviate_numbers: false,
+            role_glyphs: false,
+            show_trends: true,
+            show_totals: false,
+            show_total_row: false,
+            show_quality: false,
+            show_cdh: false,
+            show_overheal: false,
+            show_rolling_dps: false,
+            show_active: false,
+            live_timer: false,
+            anonymize: false,
+            compact: false,
+            show_header: true,
+            show_footer: true,
+            hide_pets: true,
+            party_only: false,
+            hide_zero_rows: false,
+            keep_self_row: true,
+            pet_names: config::default_pet_names(),
+            json_export_frames: true,
+            min_record_seconds: 0,
+            min_record_damage: 0,
+            retention_days: 0,
+            max_frames: DEFAULT_MAX_FRAMES as u64,
+            store_raw_frames: false,
+            keybindings: KeybindingsConfig::default(),
+            columns: ColumnsConfig::default(),
+            subscribe_events: config::default_subscribe_events(),
         }
     }
 }
@@ -648,14 +3111,83 @@ impl AppSettings {
             Some(Duration::from_secs(self.idle_seconds))
         }
     }
-}
 
-impl From<AppConfig> for AppSettings {
-    fn from(value: AppConfig) -> Self {
-        Self {
+    pub fn recording_filter(&self) -> RecordingFilter {
+        RecordingFilter {
+            min_duration_secs: self.min_record_seconds,
+            min_damage: self.min_record_damage,
+        }
+    }
+
+    /// `redraw_ms` clamped so time-based changes such as entering idle still show promptly.
+    pub fn redraw_interval(&self) -> Duration {
+        Duration::from_millis(self.redraw_ms.clamp(MIN_REDRAW_MS, MAX_REDRAW_MS))
+    }
+
+    pub fn input_grace(&self) -> Duration {
+        Duration::from_secs(self.input_grace_seconds)
+    }
+
+    pub fn stale_duration(&self) -> Option<Duration> {
+        if self.stale_seconds == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(self.stale_seconds))
+        }
+    }
+}
+
+impl From<AppConfig> for AppSettings {
+    fn from(value: AppConfig) -> Self {
+        Self {
             idle_seconds: value.idle_seconds,
             default_decoration: Decoration::from_config_key(&value.default_decoration),
+            bar_scale: BarScale::from_config_key(&value.bar_scale),
+            bar_metric: BarMetric::from_config_key(&value.bar_metric),
             default_mode: ViewMode::from_config_key(&value.default_mode),
+            view_layout: ViewLayout::from_config_key(&value.view_layout),
+            number_locale: NumberLocale::from_config_key(&value.number_locale),
+            default_sort: SortKey::from_config_key(&value.default_sort),
+            ws_url: value.ws_url,
+            character_name: value.character_name,
+            ws_auth_token: value.ws_auth_token,
+            ws_auth_query: value.ws_auth_query,
+            search_url_template: value.search_url_template,
+            stale_seconds: value.stale_seconds,
+            idle_rotate_seconds: value.idle_rotate_seconds,
+            input_delays_idle: value.input_delays_idle,
+            input_grace_seconds: value.input_grace_seconds,
+            redraw_ms: value.redraw_ms,
+            theme: Theme::from_config_key(&value.theme),
+            abbreviate_numbers: value.abbreviate_numbers,
+            role_glyphs: value.role_glyphs,
+            show_trends: value.show_trends,
+            show_totals: value.show_totals,
+            show_total_row: value.show_total_row,
+            show_quality: value.show_quality,
+            show_cdh: value.show_cdh,
+            show_overheal: value.show_overheal,
+            show_rolling_dps: value.show_rolling_dps,
+            show_active: value.show_active,
+            live_timer: value.live_timer,
+            compact: value.compact,
+            show_header: value.show_header,
+            show_footer: value.show_footer,
+            anonymize: value.anonymize,
+            hide_pets: value.hide_pets,
+            party_only: value.party_only,
+            hide_zero_rows: value.hide_zero_rows,
+            keep_self_row: value.keep_self_row,
+            pet_names: value.pet_names,
+            json_export_frames: value.json_export_frames,
+            min_record_seconds: value.min_record_seconds,
+            min_record_damage: value.min_record_damage,
+            retention_days: value.retention_days,
+            max_frames: value.max_frames,
+            store_raw_frames: value.store_raw_frames,
+            keybindings: value.keybindings,
+            columns: value.columns,
+            subscribe_events: value.subscribe_events,
         }
     }
 }
@@ -665,7 +3197,52 @@ impl From<AppSettings> for AppConfig {
         AppConfig {
             idle_seconds: value.idle_seconds,
             default_decoration: value.default_decoration.config_key().to_string(),
+            bar_scale: value.bar_scale.config_key().to_string(),
+            bar_metric: value.bar_metric.config_key().to_string(),
             default_mode: value.default_mode.config_key().to_string(),
+            view_layout: value.view_layout.config_key().to_string(),
+            number_locale: value.number_locale.config_key().to_string(),
+            default_sort: value.default_sort.config_key().to_string(),
+            ws_url: value.ws_url,
+            character_name: value.character_name,
+            ws_auth_token: value.ws_auth_token,
+            ws_auth_query: value.ws_auth_query,
+            search_url_template: value.search_url_template,
+            stale_seconds: value.stale_seconds,
+            idle_rotate_seconds: value.idle_rotate_seconds,
+            input_delays_idle: value.input_delays_idle,
+            input_grace_seconds: value.input_grace_seconds,
+            redraw_ms: value.redraw_ms,
+            theme: value.theme.config_key().to_string(),
+            abbreviate_numbers: value.abbreviate_numbers,
+            role_glyphs: value.role_glyphs,
+            show_trends: value.show_trends,
+            show_totals: value.show_totals,
+            show_total_row: value.show_total_row,
+            show_quality: value.show_quality,
+            show_cdh: value.show_cdh,
+            show_overheal: value.show_overheal,
+            show_rolling_dps: value.show_rolling_dps,
+            show_active: value.show_active,
+            live_timer: value.live_timer,
+            compact: value.compact,
+            show_header: value.show_header,
+            show_footer: value.show_footer,
+            anonymize: value.anonymize,
+            hide_pets: value.hide_pets,
+            party_only: value.party_only,
+            hide_zero_rows: value.hide_zero_rows,
+            keep_self_row: value.keep_self_row,
+            pet_names: value.pet_names,
+            keybindings: value.keybindings,
+            columns: value.columns,
+            subscribe_events: value.subscribe_events,
+            json_export_frames: value.json_export_frames,
+            min_record_seconds: value.min_record_seconds,
+            min_record_damage: value.min_record_damage,
+            retention_days: value.retention_days,
+            max_frames: value.max_frames,
+            store_raw_frames: value.store_raw_frames,
         }
     }
 }
@@ -739,29 +3316,184 @@ impl Decoration {
     }
 }
 
+/// What a full-width decoration bar represents.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BarScale {
+    /// The top row fills the width; the others are relative to it.
+    #[default]
+    RelativeToMax,
+    /// Each bar is the row's share of the party total, so all bars add up to the full width.
+    ShareOfTotal,
+}
+
+impl BarScale {
+    pub fn toggle(self) -> Self {
+        match self {
+            BarScale::RelativeToMax => BarScale::ShareOfTotal,
+            BarScale::ShareOfTotal => BarScale::RelativeToMax,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BarScale::RelativeToMax => "Relative to top",
+            BarScale::ShareOfTotal => "Share of total",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            BarScale::RelativeToMax => "max",
+            BarScale::ShareOfTotal => "share",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "share" => BarScale::ShareOfTotal,
+            _ => BarScale::RelativeToMax,
+        }
+    }
+}
+
+/// How the live view arranges its tables.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ViewLayout {
+    /// One table in the current view mode.
+    #[default]
+    Single,
+    /// DPS and HEAL tables side by side; TANK mode and narrow terminals fall back to one table.
+    Split,
+}
+
+impl ViewLayout {
+    pub fn toggle(self) -> Self {
+        match self {
+            ViewLayout::Single => ViewLayout::Split,
+            ViewLayout::Split => ViewLayout::Single,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ViewLayout::Single => "Single table",
+            ViewLayout::Split => "DPS + HEAL split",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            ViewLayout::Single => "single",
+            ViewLayout::Split => "split",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "split" => ViewLayout::Split,
+            _ => ViewLayout::Single,
+        }
+    }
+}
+
+/// The per-row value the decoration bars are drawn from.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BarMetric {
+    /// The view mode's own metric: ENCDPS, ENCHPS in HEAL mode, or damage taken in TANK mode.
+    #[default]
+    Mode,
+    DamageShare,
+    Crit,
+    Overheal,
+}
+
+impl BarMetric {
+    const ALL: [BarMetric; 4] = [
+        BarMetric::Mode,
+        BarMetric::DamageShare,
+        BarMetric::Crit,
+        BarMetric::Overheal,
+    ];
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BarMetric::Mode => "Mode metric",
+            BarMetric::DamageShare => "Damage share",
+            BarMetric::Crit => "Crit%",
+            BarMetric::Overheal => "Overheal",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            BarMetric::Mode => "mode",
+            BarMetric::DamageShare => "share",
+            BarMetric::Crit => "crit",
+            BarMetric::Overheal => "overheal",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        let key = key.as_ref().trim();
+        Self::ALL
+            .into_iter()
+            .find(|metric| metric.config_key().eq_ignore_ascii_case(key))
+            .unwrap_or_default()
+    }
+
+    /// The row's value under this metric; `Mode` defers to the view mode.
+    fn value(self, mode: ViewMode, row: &CombatantRow) -> f64 {
+        match self {
+            BarMetric::Mode => mode.metric_value(row),
+            BarMetric::DamageShare => row.share,
+            BarMetric::Crit => parse_percent(&row.crit).unwrap_or(0.0),
+            BarMetric::Overheal => row.overheal,
+        }
+    }
+}
+
 // High-level view mode of the table
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum ViewMode {
     #[default]
     Dps,
     Heal,
+    // Damage taken, for checking tank/healer mitigation load
+    Tank,
 }
 
 impl ViewMode {
     pub fn next(self) -> Self {
         match self {
             ViewMode::Dps => ViewMode::Heal,
-            ViewMode::Heal => ViewMode::Dps,
+            ViewMode::Heal => ViewMode::Tank,
+            ViewMode::Tank => ViewMode::Dps,
         }
     }
 
     pub fn prev(self) -> Self {
-        self.next()
+        match self {
+            ViewMode::Dps => ViewMode::Tank,
+            ViewMode::Heal => ViewMode::Dps,
+            ViewMode::Tank => ViewMode::Heal,
+        }
     }
+
     pub fn short_label(self) -> &'static str {
         match self {
             ViewMode::Dps => "mode:DPS",
             ViewMode::Heal => "mode:HEAL",
+            ViewMode::Tank => "mode:TANK",
         }
     }
 
@@ -769,6 +3501,7 @@ impl ViewMode {
         match self {
             ViewMode::Dps => "DPS",
             ViewMode::Heal => "HEAL",
+            ViewMode::Tank => "TANK",
         }
     }
 
@@ -776,13 +3509,1576 @@ impl ViewMode {
         match self {
             ViewMode::Dps => "dps",
             ViewMode::Heal => "heal",
+            ViewMode::Tank => "tank",
         }
     }
 
     pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
         match key.as_ref().to_ascii_lowercase().as_str() {
             "heal" => ViewMode::Heal,
+            "tank" => ViewMode::Tank,
             _ => ViewMode::Dps,
         }
     }
+
+    /// Orders rows for display; DPS and HEAL keep the parser's ENCDPS ordering.
+    pub fn sort_rows(self, rows: &mut [CombatantRow]) {
+        if self == ViewMode::Tank {
+            rows.sort_by(|a, b| {
+                b.damage_taken
+                    .partial_cmp(&a.damage_taken)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+        }
+    }
+
+    /// The mode's headline metric: ENCDPS, ENCHPS or damage taken.
+    pub fn metric_value(self, row: &CombatantRow) -> f64 {
+        match self {
+            ViewMode::Dps => row.encdps,
+            ViewMode::Heal => row.enchps,
+            ViewMode::Tank => row.damage_taken,
+        }
+    }
+
+    /// Fill fraction (0..=1) of each row's decoration bar for the given metric and scaling.
+    pub fn bar_ratios(self, rows: &[CombatantRow], scale: BarScale, metric: BarMetric) -> Vec<f64> {
+        match (scale, metric, self) {
+            (BarScale::ShareOfTotal, BarMetric::Mode, ViewMode::Dps) => {
+                rows.iter().map(|row| row.share.clamp(0.0, 1.0)).collect()
+            }
+            (BarScale::ShareOfTotal, BarMetric::Mode, ViewMode::Heal) => rows
+                .iter()
+                .map(|row| row.heal_share.clamp(0.0, 1.0))
+                .collect(),
+            _ => {
+                let values: Vec<f64> = rows.iter().map(|row| metric.value(self, row)).collect();
+                let full = match scale {
+                    BarScale::RelativeToMax => values.iter().copied().fold(0.0_f64, f64::max),
+                    BarScale::ShareOfTotal => values.iter().sum(),
+                };
+                if full <= 0.0 {
+                    return vec![0.0; values.len()];
+                }
+                values
+                    .iter()
+                    .map(|value| (value / full).clamp(0.0, 1.0))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A live table column that can be listed in the config's `columns` section.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TableColumn {
+    Name,
+    Job,
+    Share,
+    HealShare,
+    Encdps,
+    Enchps,
+    Crit,
+    Dh,
+    Quality,
+    Cdh,
+    Deaths,
+    MaxHit,
+    Overheal,
+    OverhealAmount,
+    Taken,
+    Damage,
+    Healed,
+    RollingDps,
+    Active,
+}
+
+impl TableColumn {
+    pub const ALL: [TableColumn; 19] = [
+        TableColumn::Name,
+        TableColumn::Job,
+        TableColumn::Share,
+        TableColumn::HealShare,
+        TableColumn::Encdps,
+        TableColumn::Enchps,
+        TableColumn::Crit,
+        TableColumn::Dh,
+        TableColumn::Quality,
+        TableColumn::Cdh,
+        TableColumn::Deaths,
+        TableColumn::MaxHit,
+        TableColumn::Overheal,
+        TableColumn::OverhealAmount,
+        TableColumn::Taken,
+        TableColumn::Damage,
+        TableColumn::Healed,
+        TableColumn::RollingDps,
+        TableColumn::Active,
+    ];
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            TableColumn::Name => "name",
+            TableColumn::Job => "job",
+            TableColumn::Share => "share",
+            TableColumn::HealShare => "heal_share",
+            TableColumn::Encdps => "encdps",
+            TableColumn::Enchps => "enchps",
+            TableColumn::Crit => "crit",
+            TableColumn::Dh => "dh",
+            TableColumn::Quality => "quality",
+            TableColumn::Cdh => "cdh",
+            TableColumn::Deaths => "deaths",
+            TableColumn::MaxHit => "max_hit",
+            TableColumn::Overheal => "overheal",
+            TableColumn::OverhealAmount => "overheal_amount",
+            TableColumn::Taken => "taken",
+            TableColumn::Damage => "damage",
+            TableColumn::Healed => "healed",
+            TableColumn::RollingDps => "rdps",
+            TableColumn::Active => "active",
+        }
+    }
+
+    pub fn from_config_key(key: &str) -> Option<Self> {
+        let key = key.trim();
+        Self::ALL
+            .into_iter()
+            .find(|column| column.config_key().eq_ignore_ascii_case(key))
+    }
+}
+
+/// Resolves configured column names in order, returning a warning for every unknown or repeated
+/// name; those are left out of the table.
+pub fn parse_table_columns(names: &[String]) -> (Vec<TableColumn>, Vec<String>) {
+    let mut columns = Vec::with_capacity(names.len());
+    let mut warnings = Vec::new();
+    for name in names {
+        match TableColumn::from_config_key(name) {
+            Some(column) if columns.contains(&column) => {
+                warnings.push(format!("Column {name:?} is listed more than once"));
+            }
+            Some(column) => columns.push(column),
+            None => warnings.push(format!("Unknown column {name:?}")),
+        }
+    }
+    (columns, warnings)
+}
+
+/// Live table columns per view mode, resolved from `ColumnsConfig` once per settings change.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfiguredColumns {
+    dps: Vec<TableColumn>,
+    heal: Vec<TableColumn>,
+    tank: Vec<TableColumn>,
+}
+
+impl ConfiguredColumns {
+    pub fn parse(config: &ColumnsConfig) -> Self {
+        Self {
+            dps: parse_table_columns(&config.dps).0,
+            heal: parse_table_columns(&config.heal).0,
+            tank: parse_table_columns(&config.tank).0,
+        }
+    }
+
+    /// The configured columns for `mode`; empty when the built-in layouts apply.
+    pub fn for_mode(&self, mode: ViewMode) -> &[TableColumn] {
+        match mode {
+            ViewMode::Dps => &self.dps,
+            ViewMode::Heal => &self.heal,
+            ViewMode::Tank => &self.tank,
+        }
+    }
+}
+
+// Column the live table is ordered by; `Metric` defers to the view mode's own ordering.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Metric,
+    Name,
+    Damage,
+    Crit,
+    Deaths,
+    /// Tanks, healers, then DPS, alphabetical within each role; rows stay put during a pull.
+    PartyOrder,
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Metric => SortKey::Name,
+            SortKey::Name => SortKey::Damage,
+            SortKey::Damage => SortKey::Crit,
+            SortKey::Crit => SortKey::Deaths,
+            SortKey::Deaths => SortKey::PartyOrder,
+            SortKey::PartyOrder => SortKey::Metric,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            SortKey::Metric => SortKey::PartyOrder,
+            SortKey::Name => SortKey::Metric,
+            SortKey::Damage => SortKey::Name,
+            SortKey::Crit => SortKey::Damage,
+            SortKey::Deaths => SortKey::Crit,
+            SortKey::PartyOrder => SortKey::Deaths,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Metric => "Mode metric",
+            SortKey::Name => "Name",
+            SortKey::Damage => "Damage",
+            SortKey::Crit => "Crit%",
+            SortKey::Deaths => "Deaths",
+            SortKey::PartyOrder => "Party order",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            SortKey::Metric => "metric",
+            SortKey::Name => "name",
+            SortKey::Damage => "damage",
+            SortKey::Crit => "crit",
+            SortKey::Deaths => "deaths",
+            SortKey::PartyOrder => "party",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "name" => SortKey::Name,
+            "damage" => SortKey::Damage,
+            "crit" => SortKey::Crit,
+            "deaths" => SortKey::Deaths,
+            "party" => SortKey::PartyOrder,
+            _ => SortKey::Metric,
+        }
+    }
+
+    /// Name sorts ascending; every numeric column sorts highest first.
+    pub fn ascending(self) -> bool {
+        matches!(self, SortKey::Name | SortKey::PartyOrder)
+    }
+
+    pub fn sort_rows(self, mode: ViewMode, rows: &mut [CombatantRow]) {
+        let numeric: fn(&CombatantRow) -> f64 = match self {
+            SortKey::Metric => return mode.sort_rows(rows),
+            SortKey::Name => {
+                rows.sort_by_cached_key(|row| row.name.to_lowercase());
+                return;
+            }
+            SortKey::PartyOrder => {
+                rows.sort_by_cached_key(|row| {
+                    let rank = match theme::job_role(&row.job) {
+                        Role::Tank => 0,
+                        Role::Healer => 1,
+                        Role::Dps => 2,
+                    };
+                    (rank, row.name.to_lowercase())
+                });
+                return;
+            }
+            SortKey::Damage => |row| row.damage,
+            SortKey::Crit => |row| to_f64_any(&row.crit),
+            SortKey::Deaths => |row| to_f64_any(&row.deaths),
+        };
+        rows.sort_by(|a, b| {
+            numeric(b)
+                .partial_cmp(&numeric(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(iso_date: &str, label: &str) -> HistoryDay {
+        HistoryDay {
+            iso_date: iso_date.into(),
+            label: label.into(),
+            encounter_count: 1,
+            encounters: Vec::new(),
+            encounter_ids: vec![iso_date.as_bytes().to_vec()],
+            encounters_loaded: false,
+            aggregate: None,
+        }
+    }
+
+    /// A loaded encounter keyed and titled `key`, with every other field empty.
+    fn encounter_item(key: &str) -> HistoryEncounterItem {
+        HistoryEncounterItem {
+            key: key.as_bytes().to_vec(),
+            display_title: key.into(),
+            base_title: key.into(),
+            occurrence: 1,
+            time_label: String::new(),
+            last_seen_ms: 0,
+            timestamp_label: String::new(),
+            pinned: false,
+            note: None,
+            zone: String::new(),
+            self_encdps: None,
+            personal_best: false,
+            record: None,
+        }
+    }
+
+    fn state_with_days() -> AppState {
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.history.days = vec![
+            day("2025-03-03", "2025-03-03 (Mon) · 1 encounters"),
+            day("2025-03-02", "2025-03-02 (Sun) · 1 encounters"),
+            day("2025-02-28", "2025-02-28 (Fri) · 1 encounters"),
+            day("2025-02-23", "2025-02-23 (Sun) · 1 encounters"),
+        ];
+        state
+    }
+
+    #[test]
+    fn clear_all_needs_the_typed_confirmation() {
+        let mut state = state_with_days();
+        state.history.selected_day = 2;
+        state.history_request_clear_all();
+        assert!(state.confirm_typing());
+        assert!(!state.history_filter_editing());
+
+        for ch in "wip".chars() {
+            state.confirm_push(ch);
+        }
+        assert_eq!(state.confirm_accept(), None);
+        assert!(state.confirm_typing());
+
+        state.confirm_push('e');
+        state.confirm_toggle_keep_pinned();
+        assert_eq!(
+            state.confirm.as_ref().map(|c| c.message.as_str()),
+            Some("Wipe all unpinned history?")
+        );
+        assert_eq!(
+            state.confirm_accept(),
+            Some(PendingAction::ClearAll { keep_pinned: true })
+        );
+        assert!(!state.confirm_open());
+        assert!(state.history.loading);
+        assert_eq!(state.history.selected_day, 0);
+    }
+
+    #[test]
+    fn destructive_actions_wait_for_the_confirmation() {
+        let mut state = state_with_days();
+        state.settings.retention_days = 0;
+        state.history_request_prune();
+        assert!(!state.confirm_open());
+        assert!(state.clone_snapshot().status_message.is_some());
+
+        state.settings.retention_days = 30;
+        state.history_request_prune();
+        assert!(state.confirm_open());
+        assert!(!state.history_filter_editing());
+        state.confirm_cancel();
+        assert_eq!(state.confirm_accept(), None);
+        assert!(!state.history.loading);
+
+        state.history.days[1].encounters = vec![HistoryEncounterItem {
+            display_title: "Dummy".into(),
+            base_title: "Dummy".into(),
+            ..encounter_item("enc")
+        }];
+        state.history.selected_day = 1;
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.history_request_delete();
+        assert_eq!(
+            state.clone_snapshot().confirm.map(|c| c.message),
+            Some("Delete Dummy?".to_string())
+        );
+        assert_eq!(
+            state.confirm_accept(),
+            Some(PendingAction::DeleteEncounter {
+                date_id: "2025-03-02".into(),
+                key: b"enc".to_vec(),
+            })
+        );
+        assert!(!state.confirm_open());
+        assert!(state.history.loading);
+    }
+
+    fn row(name: &str, encdps: f64) -> CombatantRow {
+        CombatantRow {
+            name: name.into(),
+            job: "NIN".into(),
+            encdps,
+            ..CombatantRow::default()
+        }
+    }
+
+    #[test]
+    fn live_timer_starts_from_act_duration_and_stops_when_inactive() {
+        let mut state = AppState::default();
+        let encounter = |duration: &str, is_active: bool| EncounterSummary {
+            duration: duration.into(),
+            is_active,
+            ..EncounterSummary::default()
+        };
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("01:05", true),
+            rows: vec![row("Alice", 100.0)],
+        });
+        assert_eq!(state.clone_snapshot().combat_elapsed, None);
+
+        state.settings.live_timer = true;
+        assert_eq!(state.clone_snapshot().combat_elapsed, Some(65));
+        assert!(state.timer_running());
+
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("01:06", false),
+            rows: vec![row("Alice", 100.0)],
+        });
+        assert_eq!(state.clone_snapshot().combat_elapsed, None);
+        assert!(!state.timer_running());
+    }
+
+    #[test]
+    fn header_and_footer_toggles_persist_and_help_closes_once() {
+        let mut state = AppState::default();
+        state.toggle_footer();
+        assert!(state.settings.show_header);
+        assert!(!state.settings.show_footer);
+        let saved = AppConfig::from(state.settings.clone());
+        assert!(!saved.show_footer);
+        assert!(!AppSettings::from(saved).show_footer);
+
+        state.key_help = vec![('?', "this help".to_string())];
+        assert!(state.clone_snapshot().key_help.is_empty());
+        state.show_help = true;
+        assert_eq!(state.clone_snapshot().key_help.len(), 1);
+        assert!(state.dismiss_help());
+        assert!(!state.dismiss_help());
+    }
+
+    #[test]
+    fn encounter_total_row_carries_the_header_totals() {
+        let encounter = EncounterSummary {
+            encdps: "12,345.6".into(),
+            damage: "1,234,567".into(),
+            healed: "98,765".into(),
+            ..EncounterSummary::default()
+        };
+        let total = encounter.total_row();
+        assert_eq!(total.encdps_str, "12,345.6");
+        assert_eq!(total.damage, 1_234_567.0);
+        assert_eq!(total.healed_str, "98,765");
+        assert_eq!(total.share_str, "100%");
+        assert!(total.job.is_empty());
+    }
+
+    #[test]
+    fn log_pane_reads_the_buffer_only_while_open() {
+        let mut state = AppState::default();
+        for idx in 0..3 {
+            log_buffer::push_line(&state.log_lines, format!("line {idx}"));
+        }
+        assert!(state.clone_snapshot().logs.is_empty());
+
+        state.toggle_logs();
+        assert_eq!(state.clone_snapshot().logs.len(), 3);
+        state.scroll_logs(10);
+        assert_eq!(state.log_scroll, 2);
+        state.scroll_logs(-1);
+        assert_eq!(state.log_scroll, 1);
+        state.toggle_logs();
+        assert!(!state.show_logs);
+        assert_eq!(state.log_scroll, 0);
+    }
+
+    #[test]
+    fn about_panel_fills_in_the_encounter_count_later() {
+        let mut state = AppState::default();
+        state.apply(AppEvent::HistoryEncounterCount { count: 3 });
+        assert!(state.about.is_none());
+
+        state.open_about("ws://127.0.0.1:10501/ws");
+        let about = state.clone_snapshot().about.expect("about open");
+        assert_eq!(about.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(about.source, "ws://127.0.0.1:10501/ws");
+        assert_eq!(about.encounters, None);
+
+        state.apply(AppEvent::HistoryEncounterCount { count: 3 });
+        assert_eq!(state.about.as_ref().and_then(|a| a.encounters), Some(3));
+    }
+
+    #[test]
+    fn game_language_is_kept_and_shown_in_about() {
+        let mut state = AppState::default();
+        state.open_about("ws://127.0.0.1:10501/ws");
+        assert_eq!(state.about.as_ref().unwrap().language, None);
+
+        state.apply(AppEvent::GameLanguage {
+            language: "German".into(),
+        });
+        assert_eq!(
+            state.about.as_ref().unwrap().language.as_deref(),
+            Some("German")
+        );
+        state.about = None;
+        state.apply(AppEvent::Disconnected);
+        state.open_about("ws://127.0.0.1:10501/ws");
+        assert_eq!(
+            state.about.as_ref().unwrap().language.as_deref(),
+            Some("German")
+        );
+    }
+
+    #[test]
+    fn combat_data_marks_state_dirty() {
+        let mut state = AppState::default();
+        assert!(state.take_dirty());
+        assert!(!state.take_dirty());
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Alice", 100.0)],
+        });
+        assert!(state.take_dirty());
+        assert!(!state.take_dirty());
+    }
+
+    #[test]
+    fn redraw_interval_is_clamped() {
+        let with_ms = |redraw_ms| AppSettings {
+            redraw_ms,
+            ..AppSettings::default()
+        };
+        assert_eq!(with_ms(250).redraw_interval(), Duration::from_millis(250));
+        assert_eq!(with_ms(0).redraw_interval(), Duration::from_millis(16));
+        assert_eq!(with_ms(60_000).redraw_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn home_and_end_jump_to_list_edges() {
+        let mut state = state_with_days();
+        state.history.selected_day = 1;
+        state.history_move_to_edge(true);
+        assert_eq!(state.history.selected_day, 3);
+        state.history_move_selection(1);
+        assert_eq!(state.history.selected_day, 3);
+        state.history_move_to_edge(false);
+        assert_eq!(state.history.selected_day, 0);
+    }
+
+    #[test]
+    fn remembered_date_is_reselected_on_load() {
+        let mut state = state_with_days();
+        state.history.selected_day = 2;
+        let session = state.session_state();
+        assert_eq!(session.last_history_date.as_deref(), Some("2025-02-28"));
+
+        let mut restored = AppState::default();
+        restored.restore_session(session);
+        restored.apply(AppEvent::HistoryDatesLoaded {
+            days: state_with_days().history.days,
+            skipped: 0,
+        });
+        assert_eq!(restored.history.selected_day, 2);
+        assert_eq!(restored.history.remembered_date, None);
+    }
+
+    #[test]
+    fn anonymize_numbers_roles_and_keeps_self() {
+        let with_job = |name: &str, job: &str| CombatantRow {
+            job: job.into(),
+            ..row(name, 0.0)
+        };
+        let mut rows = vec![
+            with_job("Nin", "NIN"),
+            with_job("YOU", "WHM"),
+            with_job("Tia", "PLD"),
+            with_job("Sage", "SGE"),
+            with_job("Brd", "BRD"),
+        ];
+        let mut reversed = rows.clone();
+        reversed.reverse();
+        anonymize_rows(&mut rows);
+        let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["DPS 2", "YOU", "Tank 1", "Healer 1", "DPS 1"]);
+        // Placeholders follow the name, not the row order
+        anonymize_rows(&mut reversed);
+        let names: Vec<&str> = reversed.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["DPS 1", "Healer 1", "Tank 1", "YOU", "DPS 2"]);
+
+        let mut state = AppState::default();
+        state.settings.anonymize = true;
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![with_job("Tia", "PLD")],
+        });
+        state.apply(AppEvent::DeathLogged {
+            name: "Tia".into(),
+            ms: 0,
+        });
+        state.apply(AppEvent::DeathLogged {
+            name: "Stranger".into(),
+            ms: 0,
+        });
+        let snapshot = state.clone_snapshot();
+        assert_eq!(snapshot.rows[0].name, "Tank 1");
+        let deaths: Vec<&str> = snapshot
+            .recent_deaths
+            .iter()
+            .map(|death| death.name.as_str())
+            .collect();
+        assert_eq!(deaths, [ANONYMOUS_NAME, "Tank 1"]);
+        assert_eq!(state.rows[0].name, "Tia");
+    }
+
+    #[test]
+    fn role_encdps_totals_group_by_job_role() {
+        let with_job = |name: &str, job: &str, encdps: f64| CombatantRow {
+            job: job.into(),
+            ..row(name, encdps)
+        };
+        let rows = vec![
+            with_job("Tia", "PLD", 5000.0),
+            with_job("Hal", "WHM", 3000.0),
+            with_job("Sage", "SGE", 2500.0),
+            with_job("Nin", "NIN", 12000.0),
+            with_job("Brd", "BRD", 9000.0),
+        ];
+        assert_eq!(role_encdps_totals(&rows), (5000.0, 5500.0, 21000.0));
+        assert_eq!(role_encdps_totals(&[]), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn bar_ratios_follow_scale_setting() {
+        let mut rows = vec![row("Alice", 300.0), row("Bob", 100.0)];
+        rows[0].share = 0.75;
+        rows[1].share = 0.25;
+        rows[0].damage_taken = 600.0;
+        rows[1].damage_taken = 200.0;
+
+        assert_eq!(
+            ViewMode::Dps.bar_ratios(&rows, BarScale::RelativeToMax, BarMetric::Mode),
+            [1.0, 100.0 / 300.0]
+        );
+        assert_eq!(
+            ViewMode::Dps.bar_ratios(&rows, BarScale::ShareOfTotal, BarMetric::Mode),
+            [0.75, 0.25]
+        );
+        assert_eq!(
+            ViewMode::Tank.bar_ratios(&rows, BarScale::ShareOfTotal, BarMetric::Mode),
+            [0.75, 0.25]
+        );
+        assert_eq!(
+            ViewMode::Heal.bar_ratios(&rows, BarScale::ShareOfTotal, BarMetric::Mode),
+            [0.0, 0.0]
+        );
+
+        rows[0].enchps = 50.0;
+        rows[1].enchps = 200.0;
+        assert_eq!(
+            ViewMode::Heal.bar_ratios(&rows, BarScale::RelativeToMax, BarMetric::Mode),
+            [0.25, 1.0]
+        );
+    }
+
+    #[test]
+    fn bar_metric_replaces_the_mode_metric() {
+        let mut rows = vec![row("Alice", 300.0), row("Bob", 100.0)];
+        rows[0].crit = "20%".into();
+        rows[1].crit = "40%".into();
+        rows[0].overheal = 0.0;
+        rows[1].overheal = 500.0;
+
+        assert_eq!(
+            ViewMode::Dps.bar_ratios(&rows, BarScale::RelativeToMax, BarMetric::Crit),
+            [0.5, 1.0]
+        );
+        assert_eq!(
+            ViewMode::Heal.bar_ratios(&rows, BarScale::ShareOfTotal, BarMetric::Overheal),
+            [0.0, 1.0]
+        );
+        assert_eq!(BarMetric::Mode.prev(), BarMetric::Overheal);
+        assert_eq!(BarMetric::from_config_key("CRIT"), BarMetric::Crit);
+        assert_eq!(BarMetric::from_config_key("bogus"), BarMetric::Mode);
+    }
+
+    #[test]
+    fn view_layout_round_trips_through_the_config() {
+        let mut state = AppState {
+            settings_cursor: SettingsField::ViewLayout,
+            ..AppState::default()
+        };
+        assert!(state.adjust_selected_setting(true));
+        assert_eq!(state.settings.view_layout, ViewLayout::Split);
+
+        let saved = AppConfig::from(state.settings.clone());
+        assert_eq!(saved.view_layout, "split");
+        assert_eq!(AppSettings::from(saved).view_layout, ViewLayout::Split);
+        assert_eq!(ViewLayout::from_config_key("bogus"), ViewLayout::Single);
+    }
+
+    #[test]
+    fn sort_key_orders_string_columns_numerically() {
+        let mut rows = vec![row("Alice", 100.0), row("bob", 300.0), row("Cara", 200.0)];
+        rows[0].crit = "9%".into();
+        rows[1].crit = "25%".into();
+        rows[2].crit = "12%".into();
+
+        SortKey::Crit.sort_rows(ViewMode::Dps, &mut rows);
+        let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["bob", "Cara", "Alice"]);
+
+        SortKey::Name.sort_rows(ViewMode::Dps, &mut rows);
+        let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "bob", "Cara"]);
+    }
+
+    #[test]
+    fn party_order_groups_by_role_then_name() {
+        let mut rows = vec![
+            row("Zed", 900.0),
+            row("Mia", 100.0),
+            row("Ann", 500.0),
+            row("Bo", 300.0),
+            row("Kit", 200.0),
+        ];
+        for (row, job) in rows.iter_mut().zip(["NIN", "WHM", "BLM", "WAR", "GNB"]) {
+            row.job = job.into();
+        }
+
+        SortKey::PartyOrder.sort_rows(ViewMode::Dps, &mut rows);
+        let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Bo", "Kit", "Mia", "Ann", "Zed"]);
+        assert_eq!(SortKey::from_config_key("party"), SortKey::PartyOrder);
+    }
+
+    #[test]
+    fn rolling_dps_covers_the_last_window_and_resets_on_rollover() {
+        let mut state = AppState::default();
+        let start = Instant::now();
+        let damaged = |damage: f64| CombatantRow {
+            damage,
+            ..row("Alice", 0.0)
+        };
+        let step = |state: &mut AppState, secs: u64, damage: f64, restarted: bool| {
+            let mut rows = vec![damaged(damage)];
+            state.track_rolling_dps(&mut rows, restarted, start + Duration::from_secs(secs));
+            rows[0].rolling_dps
+        };
+        assert_eq!(step(&mut state, 0, 0.0, true), None);
+        assert_eq!(step(&mut state, 10, 10_000.0, false), Some(1_000.0));
+        // At 30s the 0s sample falls out; the 10s one becomes the baseline.
+        assert_eq!(step(&mut state, 30, 10_000.0, false), Some(0.0));
+        assert_eq!(step(&mut state, 31, 12_000.0, false), Some(2_000.0 / 21.0));
+        assert_eq!(step(&mut state, 40, 20_000.0, false), Some(10_000.0 / 30.0));
+        assert_eq!(step(&mut state, 41, 5_000.0, false), None);
+        assert_eq!(step(&mut state, 42, 100.0, true), None);
+        assert_eq!(step(&mut state, 44, 300.0, false), Some(100.0));
+    }
+
+    #[test]
+    fn combat_data_tags_encdps_trend_by_name() {
+        let mut state = AppState::default();
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Alice", 100.0), row("Bob", 300.0), row("Cara", 50.0)],
+        });
+        assert!(state.rows.iter().all(|r| r.trend.is_none()));
+
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![
+                row("Bob", 250.0),
+                row("Alice", 120.0),
+                row("Cara", 50.0),
+                row("Dan", 80.0),
+            ],
+        });
+        let trend = |name: &str| state.rows.iter().find(|r| r.name == name).unwrap().trend;
+        assert_eq!(trend("Alice"), Some(Ordering::Greater));
+        assert_eq!(trend("Bob"), Some(Ordering::Less));
+        assert_eq!(trend("Cara"), Some(Ordering::Equal));
+        assert_eq!(trend("Dan"), None);
+    }
+
+    #[test]
+    fn rows_joining_mid_encounter_are_flagged_until_rollover() {
+        let mut state = AppState::default();
+        let encounter = |duration: &str| EncounterSummary {
+            duration: duration.into(),
+            ..EncounterSummary::default()
+        };
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("00:01"),
+            rows: vec![row("Alice", 100.0)],
+        });
+        std::thread::sleep(Duration::from_millis(5));
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("00:02"),
+            rows: vec![row("Alice", 100.0), row("Bob", 50.0)],
+        });
+        let joined = |state: &AppState, name: &str| {
+            state
+                .clone_snapshot()
+                .rows
+                .into_iter()
+                .find(|r| r.name == name)
+                .unwrap()
+                .recently_joined
+        };
+        assert!(!joined(&state, "Alice"));
+        assert!(joined(&state, "Bob"));
+
+        std::thread::sleep(Duration::from_millis(5));
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("00:00"),
+            rows: vec![row("Bob", 10.0)],
+        });
+        assert!(!joined(&state, "Bob"));
+    }
+
+    #[test]
+    fn table_scroll_is_bounded_and_resets_on_rollover() {
+        let mut state = AppState::default();
+        let encounter = |duration: &str| EncounterSummary {
+            duration: duration.into(),
+            ..EncounterSummary::default()
+        };
+        let rows: Vec<CombatantRow> = (0..24).map(|i| row(&format!("P{i}"), 100.0)).collect();
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("00:10"),
+            rows: rows.clone(),
+        });
+        state.scroll_table(-5);
+        assert_eq!(state.table_scroll, 0);
+        state.scroll_table(5);
+        state.scroll_table(100);
+        assert_eq!(state.table_scroll, 23);
+        // Once the page size is known, scrolling stops at the last full page
+        state.set_table_page(10);
+        assert_eq!(state.table_scroll, 14);
+        state.scroll_table(100);
+        assert_eq!(state.table_scroll, 14);
+        state.scroll_table(-5);
+        assert_eq!(state.clone_snapshot().table_scroll, 9);
+
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("00:11"),
+            rows: rows.clone(),
+        });
+        assert_eq!(state.table_scroll, 9);
+        assert!(state.table_in_front(Instant::now()));
+        state.show_settings = true;
+        assert!(!state.table_in_front(Instant::now()));
+        state.show_settings = false;
+        state.apply(AppEvent::CombatData {
+            encounter: encounter("00:01"),
+            rows,
+        });
+        assert_eq!(state.table_scroll, 0);
+    }
+
+    #[test]
+    fn reconnect_progress_shows_until_connected() {
+        let mut state = AppState::default();
+        state.apply(AppEvent::Reconnecting {
+            attempt: 3,
+            delay_ms: 2_000,
+        });
+        let status = state.clone_snapshot().reconnect.unwrap();
+        assert_eq!(status.attempt, 3);
+        assert!(status.retry_in_ms <= 2_000 && status.retry_in_ms > 1_000);
+
+        state.apply(AppEvent::Connected);
+        assert!(state.clone_snapshot().reconnect.is_none());
+    }
+
+    #[test]
+    fn awaiting_combatants_only_when_connected_and_active() {
+        let mut snapshot = AppSnapshot::default();
+        assert!(!snapshot.awaiting_combatants());
+
+        snapshot.connected = true;
+        assert!(snapshot.awaiting_combatants());
+
+        snapshot.is_idle = true;
+        assert!(!snapshot.awaiting_combatants());
+
+        snapshot.is_idle = false;
+        snapshot.rows.push(row("Alice", 100.0));
+        assert!(!snapshot.awaiting_combatants());
+    }
+
+    #[test]
+    fn feed_rate_averages_frame_gaps() {
+        let start = Instant::now();
+        let mut rate = FeedRate::default();
+        rate.reset(Some(start));
+        rate.record(start + Duration::from_millis(300));
+        assert_eq!(rate.first_frame_latency(), Some(Duration::from_millis(300)));
+        assert!(rate.per_second().is_none());
+
+        for i in 1..=20 {
+            rate.record(start + Duration::from_millis(300 + i * 500));
+        }
+        let per_second = rate.per_second().unwrap();
+        assert!((per_second - 2.0).abs() < 0.01, "{per_second}");
+
+        rate.reset(None);
+        assert!(rate.per_second().is_none());
+        assert!(rate.first_frame_latency().is_none());
+    }
+
+    #[test]
+    fn feed_rate_hidden_while_disconnected() {
+        let mut state = AppState::default();
+        state.apply(AppEvent::Connected);
+        for _ in 0..2 {
+            state.apply(AppEvent::CombatData {
+                encounter: EncounterSummary::default(),
+                rows: Vec::new(),
+            });
+        }
+        assert!(state.clone_snapshot().first_frame_ms.is_some());
+
+        state.apply(AppEvent::Disconnected);
+        let snapshot = state.clone_snapshot();
+        assert!(snapshot.update_rate.is_none());
+        assert!(snapshot.first_frame_ms.is_none());
+    }
+
+    #[test]
+    fn replay_holds_live_data_aside_and_restores_it() {
+        let mut state = AppState::default();
+        let names = |state: &AppState| -> Vec<String> {
+            state.rows.iter().map(|r| r.name.clone()).collect()
+        };
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Live", 100.0)],
+        });
+
+        state.start_replay("Pull".into());
+        assert!(state.rows.is_empty());
+        state.apply(AppEvent::ReplayFrame {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Replayed", 50.0)],
+        });
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Newer", 120.0)],
+        });
+        assert_eq!(names(&state), ["Replayed"]);
+        assert_eq!(state.change_replay_speed(true), Some(2));
+        assert_eq!(state.change_replay_speed(false), Some(1));
+        assert_eq!(state.change_replay_speed(false), Some(1));
+
+        state.apply(AppEvent::ReplayFinished);
+        assert!(state.replay.is_none());
+        assert_eq!(names(&state), ["Newer"]);
+        state.apply(AppEvent::ReplayFrame {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Late", 10.0)],
+        });
+        assert_eq!(names(&state), ["Newer"]);
+        assert_eq!(state.change_replay_speed(true), None);
+    }
+
+    #[test]
+    fn hide_zero_rows_filters_display_only_and_can_keep_self() {
+        let mut state = AppState::default();
+        state.settings.hide_zero_rows = true;
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![
+                CombatantRow {
+                    share: 1.0,
+                    share_str: "100%".into(),
+                    ..row("Alice", 300.0)
+                },
+                row("Bob", 0.0),
+                CombatantRow {
+                    is_self: true,
+                    ..row("YOU", 0.0)
+                },
+            ],
+        });
+        let names = |state: &AppState| -> Vec<String> {
+            state
+                .clone_snapshot()
+                .rows
+                .iter()
+                .map(|r| r.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&state), ["Alice", "YOU"]);
+        assert_eq!(state.clone_snapshot().rows[0].share_str, "100%");
+        assert_eq!(state.rows.len(), 3);
+
+        state.settings.keep_self_row = false;
+        assert_eq!(names(&state), ["Alice"]);
+        state.mode = ViewMode::Heal;
+        assert!(names(&state).is_empty());
+    }
+
+    #[test]
+    fn party_only_uses_membership_flags_or_top_damage() {
+        let member = |name: &str, damage: f64, in_party: Option<bool>| CombatantRow {
+            damage,
+            in_party,
+            ..row(name, damage)
+        };
+        let names = |rows: &[CombatantRow]| -> Vec<String> {
+            let mut names: Vec<String> = rows.iter().map(|r| r.name.clone()).collect();
+            names.sort();
+            names
+        };
+
+        let mut flagged = vec![
+            member("Alice", 100.0, Some(true)),
+            member("Bob", 900.0, Some(false)),
+            CombatantRow {
+                is_self: true,
+                ..member("YOU", 50.0, None)
+            },
+        ];
+        keep_party(&mut flagged);
+        assert_eq!(names(&flagged), ["Alice", "YOU"]);
+
+        let mut unflagged: Vec<CombatantRow> = (0..10)
+            .map(|i| member(&format!("P{i}"), f64::from(i), None))
+            .collect();
+        keep_party(&mut unflagged);
+        assert_eq!(unflagged.len(), PARTY_SIZE);
+        assert!(!unflagged.iter().any(|r| r.name == "P0" || r.name == "P1"));
+
+        let mut state = AppState::default();
+        state.settings.party_only = true;
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![
+                member("Alice", 300.0, Some(true)),
+                member("Bob", 100.0, Some(true)),
+                member("Carol", 600.0, Some(false)),
+            ],
+        });
+        let rows = state.clone_snapshot().rows;
+        assert_eq!(names(&rows), ["Alice", "Bob"]);
+        assert_eq!(rows[0].share_str, "75.0%");
+    }
+
+    #[test]
+    fn hidden_pets_are_dropped_and_shares_rebased() {
+        let mut state = AppState::default();
+        let with_damage = |name: &str, damage: f64| CombatantRow {
+            damage,
+            share: 0.25,
+            ..row(name, damage)
+        };
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary {
+                damage: "1,000".into(),
+                ..EncounterSummary::default()
+            },
+            rows: vec![
+                with_damage("Alice", 600.0),
+                with_damage("Bob", 200.0),
+                with_damage("Eos (Bob)", 100.0),
+                with_damage("Limit Break", 100.0),
+            ],
+        });
+
+        let snapshot = state.clone_snapshot();
+        let names: Vec<&str> = snapshot.rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "Bob"]);
+        assert_eq!(snapshot.rows[0].share_str, "75.0%");
+        assert_eq!(snapshot.rows[1].share_str, "25.0%");
+        // The total line adds up the shown rows rather than the encounter's raw total.
+        let total = snapshot.total_row.expect("total row");
+        assert_eq!(total.damage_str, "800");
+        assert_eq!(total.share_str, "100.0%");
+
+        state.settings.hide_pets = false;
+        let snapshot = state.clone_snapshot();
+        assert_eq!(snapshot.rows.len(), 4);
+        assert_eq!(
+            snapshot.total_row.map(|row| row.damage_str).as_deref(),
+            Some("1,000")
+        );
+    }
+
+    #[test]
+    fn paused_snapshot_keeps_frozen_rows() {
+        let mut state = AppState::default();
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Alice", 100.0)],
+        });
+        state.toggle_pause();
+        state.apply(AppEvent::CombatData {
+            encounter: EncounterSummary {
+                duration: "00:10".into(),
+                ..EncounterSummary::default()
+            },
+            rows: vec![row("Bob", 300.0), row("Alice", 200.0)],
+        });
+
+        let snapshot = state.clone_snapshot();
+        assert!(snapshot.paused);
+        assert_eq!(snapshot.rows.len(), 1);
+        assert_eq!(snapshot.rows[0].encdps, 100.0);
+        assert_eq!(snapshot.encounter.unwrap().duration, "00:10");
+
+        state.toggle_pause();
+        let snapshot = state.clone_snapshot();
+        assert_eq!(snapshot.rows.len(), 2);
+        assert_eq!(snapshot.rows[0].name, "Bob");
+    }
+
+    #[test]
+    fn history_filter_narrows_selection_to_matches() {
+        let mut state = state_with_days();
+        state.history_open_filter();
+        for ch in "SUN".chars() {
+            state.history_filter_push(ch);
+        }
+        assert_eq!(state.history.visible_day_indices(), vec![1, 3]);
+        assert_eq!(state.history.selected_day, 1);
+
+        state.history_move_selection(1);
+        assert_eq!(state.history.selected_day, 3);
+        state.history_move_selection(5);
+        assert_eq!(state.history.selected_day, 3);
+        state.history_move_selection(-1);
+        assert_eq!(state.history.selected_day, 1);
+    }
+
+    #[test]
+    fn history_filter_clear_restores_all_days() {
+        let mut state = state_with_days();
+        state.history_open_filter();
+        state.history_filter_push('x');
+        assert!(state.history.visible_day_indices().is_empty());
+        state.history_enter();
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+
+        state.history_filter_pop();
+        assert_eq!(state.history.visible_day_indices().len(), 4);
+        state.history_clear_filter();
+        assert!(state.history.filter.is_none());
+        assert_eq!(state.history.visible_day_indices(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn recent_list_opens_detail_and_returns_to_itself() {
+        let mut state = AppState::default();
+        assert!(state.open_recent_history());
+        assert!(!state.open_recent_history());
+        assert_eq!(state.history.level, HistoryPanelLevel::Recent);
+
+        state.apply(AppEvent::HistoryRecentLoaded {
+            encounters: vec![encounter_item("newest"), encounter_item("older")],
+            skipped: 0,
+        });
+        assert!(!state.history.loading);
+
+        state.history_move_selection(1);
+        state.history_enter();
+        assert_eq!(state.history.level, HistoryPanelLevel::EncounterDetail);
+        assert_eq!(
+            state.history.current_encounter().map(|enc| enc.key.clone()),
+            Some(b"older".to_vec())
+        );
+        state.history_move_selection(-1);
+        assert_eq!(
+            state.history.current_encounter().map(|enc| enc.key.clone()),
+            Some(b"newest".to_vec())
+        );
+
+        state.history_back();
+        assert_eq!(state.history.level, HistoryPanelLevel::Recent);
+        assert!(!state.history.detail_from_recent);
+        state.history_back();
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+    }
+
+    #[test]
+    fn note_editor_updates_every_loaded_copy() {
+        let mut state = state_with_days();
+        let item = HistoryEncounterItem {
+            display_title: "Dummy".into(),
+            base_title: "Dummy".into(),
+            note: Some("old".into()),
+            ..encounter_item("enc")
+        };
+        let other = encounter_item("other");
+        state.history.days[0].encounters = vec![item.clone(), other];
+        state.history.recent = Some(vec![item]);
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.history_begin_note();
+        assert!(!state.history_note_editing());
+
+        state.history.level = HistoryPanelLevel::EncounterDetail;
+        state.history_begin_note();
+        assert_eq!(
+            state
+                .history
+                .note_draft
+                .as_ref()
+                .map(|draft| draft.text.as_str()),
+            Some("old")
+        );
+        state.history_note_clear();
+        for ch in "healer died P2 #prog oops".chars() {
+            state.history_note_push(ch);
+        }
+        state.history_note_delete_word();
+        assert_eq!(
+            state
+                .history
+                .note_draft
+                .as_ref()
+                .map(|draft| draft.text.as_str()),
+            Some("healer died P2 #prog ")
+        );
+
+        // The note stays with the encounter the editor was opened on
+        state.history.selected_encounter = 1;
+        let (key, note) = state.history_commit_note().expect("note saved");
+        assert_eq!(key, b"enc".to_vec());
+        assert_eq!(note.as_deref(), Some("healer died P2 #prog"));
+        assert!(!state.history_note_editing());
+        // Nothing changes in memory until the store confirms the write
+        assert_eq!(
+            state.history.days[0].encounters[0].note.as_deref(),
+            Some("old")
+        );
+        state.apply(AppEvent::HistoryNoteSaved {
+            key,
+            note: note.clone(),
+        });
+        assert_eq!(state.history.days[0].encounters[0].note, note);
+        assert_eq!(state.history.recent.as_ref().unwrap()[0].note, note);
+
+        state.history.selected_encounter = 0;
+        state.history_begin_note();
+        state.history_note_clear();
+        let (key, note) = state.history_commit_note().unwrap();
+        assert_eq!(note, None);
+        state.apply(AppEvent::HistoryNoteSaved { key, note });
+        assert_eq!(state.history.days[0].encounters[0].note, None);
+    }
+
+    #[test]
+    fn note_search_runs_on_enter_and_opens_details() {
+        let mut state = state_with_days();
+        state.history_open_note_search();
+        assert_eq!(state.history.level, HistoryPanelLevel::NoteSearch);
+        assert!(state.history_note_search_editing());
+
+        state.history_submit_note_search();
+        assert!(state.history_note_search_editing());
+        for ch in "#clear".chars() {
+            state.history_note_search_push(ch);
+        }
+        state.history_submit_note_search();
+        assert!(!state.history_note_search_editing());
+
+        let item = |key: &str| HistoryEncounterItem {
+            note: Some("#clear".into()),
+            ..encounter_item(key)
+        };
+        state.apply(AppEvent::HistoryNotesSearched {
+            query: "#clear".into(),
+            encounters: vec![item("new"), item("old")],
+            skipped: 0,
+        });
+        state.history_move_selection(1);
+        state.history_enter();
+        assert_eq!(state.history.level, HistoryPanelLevel::EncounterDetail);
+        assert_eq!(state.history.current_encounter().unwrap().key, b"old");
+
+        state.history_back();
+        assert_eq!(state.history.level, HistoryPanelLevel::NoteSearch);
+        state.history_open_note_search();
+        state.history_cancel_note_search();
+        assert!(!state.history_note_search_editing());
+        state.history_back();
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+    }
+
+    #[test]
+    fn large_frames_export_warns_before_exporting() {
+        let mut state = state_with_days();
+        let frame = EncounterFrame {
+            received_ms: 0,
+            encounter: EncounterSummary::default(),
+            rows: vec![row("Alice", 100.0)],
+            raw: None,
+        };
+        let record = EncounterRecord {
+            version: 1,
+            stored_ms: 0,
+            first_seen_ms: 0,
+            last_seen_ms: 0,
+            encounter: EncounterSummary::default(),
+            rows: Vec::new(),
+            raw_last: None,
+            snapshots: 0,
+            saw_active: true,
+            frames: vec![frame; FRAMES_CSV_WARN_FRAMES + 1],
+        };
+        state.history.days[0].encounters = vec![HistoryEncounterItem {
+            display_title: "Big".into(),
+            base_title: "Big".into(),
+            record: Some(record),
+            ..encounter_item("big")
+        }];
+        state.history.level = HistoryPanelLevel::EncounterDetail;
+
+        assert!(state.history_request_frames_export().is_none());
+        assert!(state.clone_snapshot().status_message.is_some());
+        let exported = state
+            .history_request_frames_export()
+            .expect("second press exports");
+        assert_eq!(exported.frames.len(), FRAMES_CSV_WARN_FRAMES + 1);
+        assert!(state.history.frames_export_armed.is_none());
+    }
+
+    #[test]
+    fn zone_filter_narrows_dates_and_encounter_lists() {
+        let mut state = state_with_days();
+        let item = |key: &str, zone: &str| HistoryEncounterItem {
+            zone: zone.into(),
+            ..encounter_item(key)
+        };
+        let encounters = || vec![item("a", "Sastasha"), item("b", "Haukke Manor")];
+        state.apply(AppEvent::HistoryEncountersLoaded {
+            date_id: "2025-03-03".into(),
+            encounters: encounters(),
+            skipped: 0,
+        });
+
+        let zone = state.history_toggle_zone_filter();
+        assert_eq!(zone.as_deref(), Some("Sastasha"));
+        state.apply(AppEvent::HistoryZonesLoaded {
+            zone: "Sastasha".into(),
+            zones: vec![ZoneSummary {
+                zone: "Sastasha".into(),
+                encounter_count: 2,
+                date_ids: vec!["2025-03-03".into(), "2025-02-28".into()],
+            }],
+            skipped: 0,
+        });
+        assert_eq!(state.history.visible_day_indices(), vec![0, 2]);
+        assert!(!state.history.days[0].encounters_loaded);
+
+        state.apply(AppEvent::HistoryEncountersLoaded {
+            date_id: "2025-03-03".into(),
+            encounters: encounters(),
+            skipped: 0,
+        });
+        assert_eq!(state.history.days[0].encounters.len(), 1);
+        assert_eq!(state.history.days[0].encounter_count, 2);
+
+        assert_eq!(state.history_toggle_zone_filter(), None);
+        assert!(state.history.zone_filter.is_none());
+        assert_eq!(state.history.visible_day_indices().len(), 4);
+    }
+
+    #[test]
+    fn zone_filter_from_encounters_queues_the_day_reload() {
+        let mut state = state_with_days();
+        state.history.level = HistoryPanelLevel::Encounters;
+        state.apply(AppEvent::HistoryEncountersLoaded {
+            date_id: "2025-03-03".into(),
+            encounters: vec![HistoryEncounterItem {
+                zone: "Sastasha".into(),
+                ..encounter_item("a")
+            }],
+            skipped: 0,
+        });
+        assert_eq!(state.history.pending_encounter_load(), None);
+
+        assert!(state.history_toggle_zone_filter().is_some());
+        state.apply(AppEvent::HistoryZonesLoaded {
+            zone: "Sastasha".into(),
+            zones: vec![ZoneSummary {
+                zone: "Sastasha".into(),
+                encounter_count: 1,
+                date_ids: vec!["2025-03-03".into()],
+            }],
+            skipped: 0,
+        });
+        assert!(!state.history.loading);
+        assert_eq!(state.history.level, HistoryPanelLevel::Encounters);
+        assert_eq!(
+            state.history.pending_encounter_load().as_deref(),
+            Some("2025-03-03")
+        );
+    }
+
+    #[test]
+    fn idle_timeout_text_entry_validates_before_committing() {
+        let mut state = AppState {
+            show_settings: true,
+            ..AppState::default()
+        };
+        state.settings_begin_input();
+        assert_eq!(state.settings_input.as_deref(), Some("5"));
+        state.settings_input_pop();
+        for ch in ['1', 'x', '2', '0'] {
+            state.settings_input_push(ch);
+        }
+        assert!(state.settings_commit_input());
+        assert_eq!(state.settings.idle_seconds, 120);
+        assert!(!state.settings_input_editing());
+
+        state.settings_begin_input();
+        state.settings_input = Some("99999".into());
+        assert!(!state.settings_commit_input());
+        assert!(state.settings_input_editing());
+        assert_eq!(state.settings.idle_seconds, 120);
+        state.settings_cancel_input();
+
+        assert!(state.adjust_selected_setting_page(false));
+        assert_eq!(state.settings.idle_seconds, 110);
+    }
+
+    #[test]
+    fn configured_columns_keep_order_and_drop_unknown_names() {
+        let names: Vec<String> = ["Name", "encdps", " crit ", "bogus", "crit", "max_hit"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let (columns, warnings) = parse_table_columns(&names);
+        assert_eq!(
+            columns,
+            [
+                TableColumn::Name,
+                TableColumn::Encdps,
+                TableColumn::Crit,
+                TableColumn::MaxHit
+            ]
+        );
+        assert_eq!(warnings.len(), 2);
+
+        let mut state = AppState::default();
+        assert!(state.table_columns.for_mode(ViewMode::Dps).is_empty());
+        let mut settings = AppSettings::default();
+        settings.columns.heal = vec!["name".into(), "enchps".into()];
+        state.apply_settings(settings);
+        let snapshot = state.clone_snapshot();
+        assert!(snapshot.table_columns.for_mode(ViewMode::Dps).is_empty());
+        assert_eq!(
+            snapshot.table_columns.for_mode(ViewMode::Heal),
+            [TableColumn::Name, TableColumn::Enchps]
+        );
+        for column in TableColumn::ALL {
+            assert_eq!(
+                TableColumn::from_config_key(column.config_key()),
+                Some(column)
+            );
+        }
+    }
+
+    #[test]
+    fn job_stats_sort_cycles_and_resets_with_date_reloads() {
+        let mut state = AppState::default();
+        state.history.visible = true;
+        state.apply(AppEvent::HistoryDatesLoaded {
+            days: Vec::new(),
+            skipped: 0,
+        });
+        state.history_open_job_stats();
+        assert_eq!(state.history.level, HistoryPanelLevel::JobStats);
+
+        let stat = |job: &str, encounters: u32, best_encdps: f64| JobStat {
+            job: job.into(),
+            encounters,
+            average_encdps: best_encdps / 2.0,
+            best_encdps,
+        };
+        state.apply(AppEvent::HistoryJobStatsLoaded {
+            stats: vec![stat("SAM", 2, 9000.0), stat("WHM", 5, 3000.0)],
+            skipped: 0,
+        });
+        let jobs = |state: &AppState| -> Vec<String> {
+            let stats = state.history.job_stats.as_ref().expect("stats loaded");
+            stats.iter().map(|stat| stat.job.clone()).collect()
+        };
+        assert_eq!(jobs(&state), ["WHM", "SAM"]);
+        state.history_cycle_job_stats_sort();
+        assert_eq!(state.history.job_stats_sort, JobStatsSort::Average);
+        assert_eq!(jobs(&state), ["SAM", "WHM"]);
+
+        state.apply(AppEvent::HistoryDatesLoaded {
+            days: Vec::new(),
+            skipped: 0,
+        });
+        assert!(state.history.job_stats.is_none());
+        state.history_back();
+        assert_eq!(state.history.level, HistoryPanelLevel::Dates);
+    }
+
+    #[test]
+    fn idle_scene_rotates_after_interval_and_resets_on_manual_change() {
+        let start = Instant::now();
+        let mut state = AppState {
+            connected: true,
+            connected_since: Some(start),
+            ..AppState::default()
+        };
+        state.settings.idle_seconds = 1;
+        state.settings.idle_rotate_seconds = 10;
+
+        let idle_at = start + Duration::from_secs(1);
+        assert!(state.observe_idle(idle_at));
+        assert!(!state.idle_rotation_due(idle_at + Duration::from_secs(9)));
+        assert!(state.idle_rotation_due(idle_at + Duration::from_secs(10)));
+
+        state.set_idle_scene(IdleScene::AsciiArt, idle_at + Duration::from_secs(8));
+        assert!(!state.idle_rotation_due(idle_at + Duration::from_secs(12)));
+
+        state.settings.idle_rotate_seconds = 0;
+        assert!(!state.idle_rotation_due(idle_at + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn recent_input_holds_off_idle_until_it_begins() {
+        let start = Instant::now();
+        let mut state = AppState {
+            connected: true,
+            connected_since: Some(start),
+            ..AppState::default()
+        };
+        state.settings.idle_seconds = 5;
+        state.note_input(start + Duration::from_secs(4));
+
+        let due = start + Duration::from_secs(5);
+        assert!(!state.observe_idle(due));
+        assert!(state.observe_idle(start + Duration::from_secs(4) + state.settings.input_grace()));
+
+        // Keys pressed while idle (scene cycling, peeking) leave idle mode alone.
+        let later = start + Duration::from_secs(60);
+        state.note_input(later);
+        assert!(state.is_idle_at(later));
+
+        state.was_idle = false;
+        state.settings.input_grace_seconds = 10;
+        assert!(!state.is_idle_at(later + Duration::from_secs(9)));
+        assert!(state.is_idle_at(later + Duration::from_secs(10)));
+
+        state.settings.input_delays_idle = false;
+        assert!(state.is_idle_at(later));
+    }
 }
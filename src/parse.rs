@@ -1,7 +1,14 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 use crate::model::{known_jobs, CombatantRow, EncounterSummary};
+use crate::util::format_clock;
+
+/// Combatant key ACT uses for the local player when it doesn't send the character's name.
+pub const SELF_ALIAS: &str = "YOU";
 
 fn get_ci<'a>(obj: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
     if let Some(v) = obj.get(key) {
@@ -21,15 +28,143 @@ fn val_to_string(v: &Value) -> String {
     }
 }
 
-fn clean_number_str(s: &str) -> String {
+/// Truthiness of flags that IINACT builds send as `"true"`, `true`, or `1`.
+fn value_is_true(v: &Value) -> bool {
+    match v {
+        Value::Bool(b) => *b,
+        Value::String(s) => {
+            let s = s.trim();
+            s.eq_ignore_ascii_case("true") || s == "1"
+        }
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0).unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Which separator marks decimals in the numbers IINACT sends; the rest is digit grouping.
+/// Process-wide like the theme, set from the `number_locale` config option.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// Guessed per number: the last of `.` and `,` when both appear, a lone `,` unless three
+    /// digits follow it, and `.` otherwise.
+    #[default]
+    Auto,
+    /// `12,345.6`
+    DotDecimal,
+    /// `12.345,6`
+    CommaDecimal,
+}
+
+impl NumberLocale {
+    pub fn config_key(self) -> &'static str {
+        match self {
+            NumberLocale::Auto => "auto",
+            NumberLocale::DotDecimal => "dot",
+            NumberLocale::CommaDecimal => "comma",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "dot" => NumberLocale::DotDecimal,
+            "comma" => NumberLocale::CommaDecimal,
+            _ => NumberLocale::Auto,
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        match index {
+            1 => NumberLocale::DotDecimal,
+            2 => NumberLocale::CommaDecimal,
+            _ => NumberLocale::Auto,
+        }
+    }
+
+    fn index(self) -> u8 {
+        match self {
+            NumberLocale::Auto => 0,
+            NumberLocale::DotDecimal => 1,
+            NumberLocale::CommaDecimal => 2,
+        }
+    }
+
+    /// The decimal separator of `s` under this locale.
+    fn decimal_separator(self, s: &str) -> char {
+        match self {
+            NumberLocale::DotDecimal => '.',
+            NumberLocale::CommaDecimal => ',',
+            NumberLocale::Auto => match (s.rfind('.'), s.rfind(',')) {
+                (Some(dot), Some(comma)) if comma > dot => ',',
+                (None, Some(comma)) => {
+                    let frac_digits = s[comma + 1..]
+                        .chars()
+                        .take_while(char::is_ascii_digit)
+                        .count();
+                    if s.matches(',').count() == 1 && frac_digits != 3 {
+                        ','
+                    } else {
+                        '.'
+                    }
+                }
+                // Several dots can only be grouping.
+                (Some(_), None) if s.matches('.').count() > 1 => ',',
+                _ => '.',
+            },
+        }
+    }
+}
+
+static NUMBER_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_number_locale(locale: NumberLocale) {
+    NUMBER_LOCALE.store(locale.index(), Ordering::Relaxed);
+}
+
+pub fn number_locale() -> NumberLocale {
+    NumberLocale::from_index(NUMBER_LOCALE.load(Ordering::Relaxed))
+}
+
+/// Rewrites `s` with `.` as the only decimal mark and the grouping separators dropped.
+fn normalize_separators(s: &str, locale: NumberLocale) -> String {
+    let decimal = locale.decimal_separator(s);
+    s.chars()
+        .filter_map(|ch| match ch {
+            '.' | ',' if ch == decimal => Some('.'),
+            '.' | ',' => None,
+            _ => Some(ch),
+        })
+        .collect()
+}
+
+fn clean_number_str(s: &str, locale: NumberLocale) -> String {
     // Keep digits, dot, plus, minus
     static RE: once_cell::sync::Lazy<Regex> =
         once_cell::sync::Lazy::new(|| Regex::new(r"[^0-9.+-]").unwrap());
-    RE.replace_all(s, "").into_owned()
+    RE.replace_all(&normalize_separators(s, locale), "")
+        .into_owned()
+}
+
+/// Parses a percentage such as `"23.5%"`, `"23,5%"` (decimal comma) or `"23"`; `None` when
+/// blank or not a number.
+pub fn parse_percent(s: &str) -> Option<f64> {
+    let trimmed = s.trim().trim_end_matches('%').trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    normalize_separators(trimmed, number_locale())
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
 }
 
-fn to_f64_any<S: AsRef<str>>(s: S) -> f64 {
-    let cleaned = clean_number_str(s.as_ref());
+/// Best-effort number from whatever IINACT sent, under the configured `NumberLocale`;
+/// 0 when nothing numeric is left.
+pub fn to_f64_any<S: AsRef<str>>(s: S) -> f64 {
+    number_in_locale(s.as_ref(), number_locale())
+}
+
+fn number_in_locale(s: &str, locale: NumberLocale) -> f64 {
+    let cleaned = clean_number_str(s, locale);
     if cleaned.is_empty() {
         return 0.0;
     }
@@ -40,6 +175,116 @@ fn upper<S: AsRef<str>>(s: S) -> String {
     s.as_ref().to_uppercase()
 }
 
+const LOG_TYPE_CHAT: &str = "00";
+const LOG_TYPE_ZONE_CHANGE: &str = "01";
+const LOG_TYPE_DEATH: &str = "25";
+const LOG_TYPE_DIRECTOR: &str = "33";
+const LOG_TYPE_IN_COMBAT: &str = "260";
+
+/// Message kind of the "X was defeated" system lines in a chat line's channel code (e.g.
+/// `0B3A`); the low seven bits hold the kind, the higher ones whose defeat it was.
+const CHAT_KIND_DEFEAT: u16 = 0x3A;
+
+/// Director commands (type 33) that begin a pull: the initial commence and the recommence
+/// after a wipe.
+const DIRECTOR_COMMENCE: [&str; 2] = ["40000001", "40000006"];
+/// Director commands that close a pull: victory and the wipe fade-out.
+const DIRECTOR_FINISH: [&str; 2] = ["40000003", "40000005"];
+
+/// An explicit encounter edge announced in the log, as opposed to one inferred from numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterBoundary {
+    CombatStart,
+    CombatEnd,
+    ZoneChange,
+}
+
+/// The `|`-separated fields of a LogLine message, or `None` for any other message type.
+fn log_line_fields(value: &Value) -> Option<Vec<String>> {
+    let root = value.as_object()?;
+    if get_ci(root, "type")?.as_str()? != "LogLine" {
+        return None;
+    }
+
+    // OverlayPlugin sends the split fields in `line`, though some builds send it unsplit;
+    // fall back to splitting `rawLine`.
+    let split = |raw: &str| raw.split('|').map(str::to_string).collect();
+    match get_ci(root, "line") {
+        Some(Value::Array(items)) => Some(items.iter().map(val_to_string).collect()),
+        Some(Value::String(raw)) => Some(split(raw)),
+        _ => Some(split(get_ci(root, "rawLine")?.as_str()?)),
+    }
+}
+
+/// Recognizes log lines that mark where an encounter starts or stops: zone changes (type 01),
+/// director commence/victory/wipe commands (type 33) and ACT's own combat toggle (type 260,
+/// `260|time|inACTCombat|inGameCombat|isACTChanged|isGameChanged`).
+pub fn parse_encounter_boundary(value: &Value) -> Option<EncounterBoundary> {
+    let fields = log_line_fields(value)?;
+    let field = |idx: usize| fields.get(idx).map(|s| s.trim()).unwrap_or("");
+    let is_any = |value: &str, set: &[&str]| set.iter().any(|s| s.eq_ignore_ascii_case(value));
+
+    match field(0) {
+        LOG_TYPE_ZONE_CHANGE => Some(EncounterBoundary::ZoneChange),
+        LOG_TYPE_DIRECTOR if is_any(field(3), &DIRECTOR_COMMENCE) => {
+            Some(EncounterBoundary::CombatStart)
+        }
+        LOG_TYPE_DIRECTOR if is_any(field(3), &DIRECTOR_FINISH) => {
+            Some(EncounterBoundary::CombatEnd)
+        }
+        LOG_TYPE_IN_COMBAT if field(4) == "1" => match field(2) {
+            "1" => Some(EncounterBoundary::CombatStart),
+            "0" => Some(EncounterBoundary::CombatEnd),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Recognizes death log lines: network death (type 25, `25|time|targetId|targetName|…`) and
+/// the "X was defeated by Y." system chat line (only on the defeat channel, so players typing
+/// it in chat don't count). Returns the victim and a Unix-ms timestamp, using `fallback_ms`
+/// when the line's own timestamp can't be read.
+pub fn parse_log_line(value: &Value, fallback_ms: u64) -> Option<(String, u64)> {
+    let fields = log_line_fields(value)?;
+    let field = |idx: usize| fields.get(idx).map(|s| s.trim()).unwrap_or("");
+
+    let name = match field(0) {
+        LOG_TYPE_DEATH => field(3).to_string(),
+        LOG_TYPE_CHAT => {
+            let code = u16::from_str_radix(field(2), 16).ok()?;
+            if code & 0x7F != CHAT_KIND_DEFEAT {
+                return None;
+            }
+            let message = field(4);
+            let (victim, _) = message.split_once(" was defeated")?;
+            victim.trim().to_string()
+        }
+        _ => return None,
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    let ms = chrono::DateTime::parse_from_rfc3339(field(1))
+        .ok()
+        .and_then(|dt| u64::try_from(dt.timestamp_millis()).ok())
+        .unwrap_or(fallback_ms);
+    Some((name, ms))
+}
+
+/// Recognizes the answer to the `getLanguage` handshake call, e.g.
+/// `{"language":"English","languageId":1,"region":"International","regionId":1}`, and returns
+/// the game language. Event messages carry a `type` and never match.
+pub fn parse_language(value: &Value) -> Option<String> {
+    let root = value.as_object()?;
+    if root.contains_key("type") {
+        return None;
+    }
+    let language = get_ci(root, "language")?.as_str()?.trim();
+    (!language.is_empty()).then(|| language.to_string())
+}
+
 pub fn parse_combat_data(value: &Value) -> Option<(EncounterSummary, Vec<CombatantRow>)> {
     let root = value.as_object()?;
     if root.get("type")?.as_str()? != "CombatData" {
@@ -103,12 +348,11 @@ fn parse_encounter(root: &Map<String, Value>) -> EncounterSummary {
     let healed = get_ci(&enc_obj, "healed")
         .map(val_to_string)
         .unwrap_or_default();
+    let damage_taken = get_ci(&enc_obj, "damagetaken")
+        .map(val_to_string)
+        .unwrap_or_default();
 
-    let is_active = root
-        .get("isActive")
-        .and_then(|v| v.as_str())
-        .map(|s| s.eq_ignore_ascii_case("true"))
-        .unwrap_or(false);
+    let is_active = get_ci(root, "isActive").map(value_is_true).unwrap_or(false);
 
     EncounterSummary {
         title,
@@ -118,6 +362,7 @@ fn parse_encounter(root: &Map<String, Value>) -> EncounterSummary {
         damage,
         enchps,
         healed,
+        damage_taken,
         is_active,
     }
 }
@@ -168,6 +413,20 @@ fn parse_combatant(name: &str, stats: &Map<String, Value>) -> Option<CombatantRo
         .map(val_to_string)
         .unwrap_or_default();
 
+    // Share of hits that were both critical and direct; IINACT sends CritDirectHitPct.
+    let cdh = get_ci(stats, "CritDirectHitPct")
+        .or_else(|| get_ci(stats, "CritDirectHit%"))
+        .or_else(|| get_ci(stats, "CritDirectHit"))
+        .or_else(|| get_ci(stats, "CDH%"))
+        .map(val_to_string)
+        .unwrap_or_default();
+
+    // ACT sends "duration" as "MM:SS" and "DURATION" as whole seconds.
+    let duration = get_ci(stats, "duration")
+        .map(val_to_string)
+        .map(|raw| normalize_duration(&raw))
+        .unwrap_or_default();
+
     let deaths = get_ci(stats, "deaths")
         .or_else(|| get_ci(stats, "Deaths"))
         .map(val_to_string)
@@ -187,6 +446,26 @@ fn parse_combatant(name: &str, stats: &Map<String, Value>) -> Option<CombatantRo
     let overheal_pct = get_ci(stats, "OverHealPct")
         .map(val_to_string)
         .unwrap_or_default();
+    let overheal_str = get_ci(stats, "overHeal")
+        .map(val_to_string)
+        .unwrap_or_else(|| "0".into());
+    let overheal = to_f64_any(&overheal_str);
+
+    let damage_taken_str = get_ci(stats, "damagetaken")
+        .map(val_to_string)
+        .unwrap_or_else(|| "0".into());
+    let damage_taken = to_f64_any(&damage_taken_str);
+
+    // Usually "Ability-12,345"; some builds send only the number.
+    let max_hit_str = get_ci(stats, "maxhit")
+        .or_else(|| get_ci(stats, "MAXHIT"))
+        .or_else(|| get_ci(stats, "MaxHit"))
+        .map(val_to_string)
+        .unwrap_or_default();
+    let max_hit = max_hit_str
+        .rsplit_once('-')
+        .map(|(_, value)| to_f64_any(value))
+        .unwrap_or_else(|| to_f64_any(&max_hit_str));
 
     Some(CombatantRow {
         name: name.to_string(),
@@ -204,12 +483,57 @@ fn parse_combatant(name: &str, stats: &Map<String, Value>) -> Option<CombatantRo
         heal_share: 0.0,
         heal_share_str: String::new(),
         overheal_pct,
+        overheal,
+        overheal_str,
+        damage_taken,
+        damage_taken_str,
         crit,
         dh,
+        cdh,
+        duration,
         deaths,
+        max_hit_str,
+        max_hit,
+        trend: None,
+        recently_joined: false,
+        rolling_dps: None,
+        is_self: name == SELF_ALIAS,
+        in_party: get_ci(stats, "isPartyMember")
+            .or_else(|| get_ci(stats, "InParty"))
+            .or_else(|| get_ci(stats, "PartyMember"))
+            .map(value_is_true),
     })
 }
 
+/// Shows a bare seconds count like the `MM:SS` form; other strings pass through trimmed.
+fn normalize_duration(raw: &str) -> String {
+    let raw = raw.trim();
+    match raw.parse::<u64>() {
+        Ok(secs) => format_clock(secs),
+        Err(_) => raw.to_string(),
+    }
+}
+
+/// Flags rows named `character_name` (case-insensitively) as the local player, for payloads
+/// that list the player by name rather than as "YOU". An empty name changes nothing.
+pub fn mark_self(rows: &mut [CombatantRow], character_name: &str) {
+    let character_name = character_name.trim();
+    if character_name.is_empty() {
+        return;
+    }
+    for row in rows {
+        if row.name.eq_ignore_ascii_case(character_name) {
+            row.is_self = true;
+        }
+    }
+}
+
+/// Recomputes damage and heal shares from the rows' own totals, e.g. after some were hidden.
+pub fn rebase_shares(rows: &mut [CombatantRow]) {
+    compute_damage_shares(rows, &Map::new(), "");
+    compute_heal_shares(rows, &Map::new(), "");
+}
+
 fn compute_damage_shares(
     rows: &mut [CombatantRow],
     combatants: &Map<String, Value>,
@@ -281,6 +605,262 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn parses_get_language_response() {
+        let response = json!({
+            "language": "Japanese",
+            "languageId": 2,
+            "region": "Japan",
+            "regionId": 2,
+        });
+        assert_eq!(parse_language(&response), Some("Japanese".to_string()));
+        assert_eq!(parse_language(&json!({ "language": "" })), None);
+        assert_eq!(
+            parse_language(&json!({ "type": "ChangeZone", "language": "English" })),
+            None
+        );
+        assert_eq!(parse_language(&json!({ "type": "CombatData" })), None);
+    }
+
+    #[test]
+    fn parses_death_log_lines() {
+        let network = json!({
+            "type": "LogLine",
+            "line": ["25", "2024-05-01T20:15:30.1230000+00:00", "10ABCDEF", "Alice", "40001234", "Boss"],
+        });
+        assert_eq!(
+            parse_log_line(&network, 7),
+            Some(("Alice".to_string(), 1_714_594_530_123))
+        );
+
+        let chat = json!({
+            "type": "LogLine",
+            "rawLine": "00|not-a-time|0b3a||Bob was defeated by the Boss.|hash",
+        });
+        assert_eq!(parse_log_line(&chat, 7), Some(("Bob".to_string(), 7)));
+
+        // The same words typed in say or party chat are not a death.
+        for channel in ["000a", "000e"] {
+            let typed = json!({
+                "type": "LogLine",
+                "line": ["00", "2024-05-01T20:15:30.1230000+00:00", channel, "Carol", "Bob was defeated lol", "hash"],
+            });
+            assert_eq!(parse_log_line(&typed, 7), None);
+        }
+
+        let unsplit = json!({
+            "type": "LogLine",
+            "line": "25|2024-05-01T20:15:30.1230000+00:00|10ABCDEF|Alice|40001234|Boss",
+        });
+        assert_eq!(
+            parse_log_line(&unsplit, 7),
+            Some(("Alice".to_string(), 1_714_594_530_123))
+        );
+
+        let other = json!({ "type": "LogLine", "line": ["21", "time", "10ABCDEF", "Alice"] });
+        assert_eq!(parse_log_line(&other, 7), None);
+        assert_eq!(parse_log_line(&json!({ "type": "LogLine" }), 7), None);
+    }
+
+    #[test]
+    fn parses_encounter_boundary_log_lines() {
+        let line = |fields: &[&str]| json!({ "type": "LogLine", "line": fields });
+        let boundary = |fields: &[&str]| parse_encounter_boundary(&line(fields));
+
+        assert_eq!(
+            boundary(&["01", "time", "3E8", "The Omega Protocol"]),
+            Some(EncounterBoundary::ZoneChange)
+        );
+        assert_eq!(
+            boundary(&["33", "time", "80037586", "40000001", "1E00"]),
+            Some(EncounterBoundary::CombatStart)
+        );
+        assert_eq!(
+            boundary(&["33", "time", "80037586", "40000005", "0"]),
+            Some(EncounterBoundary::CombatEnd)
+        );
+        assert_eq!(boundary(&["33", "time", "80037586", "8000000C", "0"]), None);
+        assert_eq!(
+            boundary(&["260", "time", "1", "1", "1", "1"]),
+            Some(EncounterBoundary::CombatStart)
+        );
+        assert_eq!(
+            boundary(&["260", "time", "0", "0", "1", "1"]),
+            Some(EncounterBoundary::CombatEnd)
+        );
+        // Only the game's combat flag changed; ACT's encounter carries on.
+        assert_eq!(boundary(&["260", "time", "1", "0", "0", "1"]), None);
+
+        let raw = json!({ "type": "LogLine", "rawLine": "33|time|80037586|40000003|0|hash" });
+        assert_eq!(
+            parse_encounter_boundary(&raw),
+            Some(EncounterBoundary::CombatEnd)
+        );
+        let unsplit = json!({ "type": "LogLine", "line": "01|time|3E8|The Omega Protocol|hash" });
+        assert_eq!(
+            parse_encounter_boundary(&unsplit),
+            Some(EncounterBoundary::ZoneChange)
+        );
+        assert_eq!(
+            parse_encounter_boundary(&json!({ "type": "CombatData" })),
+            None
+        );
+    }
+
+    #[test]
+    fn numbers_follow_the_decimal_separator_of_the_locale() {
+        use NumberLocale::*;
+        assert_eq!(number_in_locale("12,345.6", Auto), 12_345.6);
+        assert_eq!(number_in_locale("12.345,6", Auto), 12_345.6);
+        assert_eq!(number_in_locale("12,345.6", DotDecimal), 12_345.6);
+        assert_eq!(number_in_locale("12.345,6", CommaDecimal), 12_345.6);
+        assert_eq!(number_in_locale("1.234.567", Auto), 1_234_567.0);
+        assert_eq!(number_in_locale("1,234,567", Auto), 1_234_567.0);
+        assert_eq!(number_in_locale("1,000", Auto), 1_000.0);
+        assert_eq!(number_in_locale("812,5", Auto), 812.5);
+        assert_eq!(number_in_locale("1.234", Auto), 1.234);
+        assert_eq!(number_in_locale("1.234", CommaDecimal), 1_234.0);
+        assert_eq!(number_in_locale("1 234,5 DPS", CommaDecimal), 1_234.5);
+        assert_eq!(number_in_locale("", Auto), 0.0);
+        assert_eq!(NumberLocale::from_config_key("Comma"), CommaDecimal);
+        assert_eq!(NumberLocale::from_config_key("bogus"), Auto);
+    }
+
+    #[test]
+    fn parses_percentages_with_symbols_and_decimal_commas() {
+        assert_eq!(parse_percent("23.5%"), Some(23.5));
+        assert_eq!(parse_percent(" 23,5% "), Some(23.5));
+        assert_eq!(parse_percent("40"), Some(40.0));
+        assert_eq!(parse_percent("1,000%"), Some(1000.0));
+        assert_eq!(parse_percent(""), None);
+        assert_eq!(parse_percent("%"), None);
+        assert_eq!(parse_percent("n/a"), None);
+    }
+
+    #[test]
+    fn parses_absolute_overheal_in_any_key_casing() {
+        let payload = json!({
+            "type": "CombatData",
+            "Encounter": { "title": "Dummy" },
+            "Combatant": {
+                "Alice": { "Job": "WHM", "overHeal": "12,345", "OverHealPct": "40%" },
+                "Bob": { "Job": "SCH", "OVERHEAL": 500 },
+                "Carol": { "Job": "AST" }
+            }
+        });
+        let (_, rows) = parse_combat_data(&payload).expect("parsed");
+        let overheal = |name: &str| {
+            let row = rows.iter().find(|r| r.name == name).expect("row");
+            (row.overheal, row.overheal_str.clone())
+        };
+        assert_eq!(overheal("Alice"), (12_345.0, "12,345".to_string()));
+        assert_eq!(overheal("Bob"), (500.0, "500".to_string()));
+        assert_eq!(overheal("Carol"), (0.0, "0".to_string()));
+    }
+
+    #[test]
+    fn parses_crit_direct_hit_rate_across_key_variants() {
+        let payload = json!({
+            "type": "CombatData",
+            "Encounter": { "title": "Dummy" },
+            "Combatant": {
+                "Alice": { "Job": "NIN", "CritDirectHitPct": "7%" },
+                "Bob": { "Job": "DRG", "critdirecthit%": "5.5%" },
+                "Carol": { "Job": "BRD", "CDH%": "3%" },
+                "Dan": { "Job": "MCH" }
+            }
+        });
+        let (_, rows) = parse_combat_data(&payload).expect("parsed");
+        let cdh = |name: &str| {
+            rows.iter()
+                .find(|r| r.name == name)
+                .expect("row")
+                .cdh
+                .clone()
+        };
+        assert_eq!(cdh("Alice"), "7%");
+        assert_eq!(cdh("Bob"), "5.5%");
+        assert_eq!(cdh("Carol"), "3%");
+        assert_eq!(cdh("Dan"), "");
+    }
+
+    #[test]
+    fn parses_combatant_duration_as_clock() {
+        let payload = json!({
+            "type": "CombatData",
+            "Encounter": { "title": "Dummy" },
+            "Combatant": {
+                "Alice": { "Job": "NIN", "duration": "05:12", "DURATION": "312" },
+                "Bob": { "Job": "DRG", "DURATION": "95" },
+                "Carol": { "Job": "BRD" }
+            }
+        });
+        let (_, rows) = parse_combat_data(&payload).expect("parsed");
+        let duration = |name: &str| {
+            rows.iter()
+                .find(|r| r.name == name)
+                .expect("row")
+                .duration
+                .clone()
+        };
+        assert_eq!(duration("Alice"), "05:12");
+        assert_eq!(duration("Bob"), "1:35");
+        assert_eq!(duration("Carol"), "");
+    }
+
+    #[test]
+    fn marks_self_by_alias_or_character_name() {
+        let payload = json!({
+            "type": "CombatData",
+            "Encounter": { "title": "Dummy" },
+            "Combatant": {
+                "YOU": { "Job": "NIN" },
+                "Alice": { "Job": "WHM" },
+                "Bob": { "Job": "PLD" }
+            }
+        });
+        let (_, mut rows) = parse_combat_data(&payload).expect("parsed");
+        let self_names = |rows: &[CombatantRow]| -> Vec<String> {
+            let mut names: Vec<String> = rows
+                .iter()
+                .filter(|r| r.is_self)
+                .map(|r| r.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(self_names(&rows), ["YOU"]);
+
+        mark_self(&mut rows, "  ");
+        assert_eq!(self_names(&rows), ["YOU"]);
+        mark_self(&mut rows, "alice");
+        assert_eq!(self_names(&rows), ["Alice", "YOU"]);
+    }
+
+    #[test]
+    fn accepts_bool_string_and_numeric_is_active() {
+        let encounter_with = |is_active: Option<Value>| {
+            let mut payload = json!({
+                "type": "CombatData",
+                "Encounter": { "title": "Dummy" },
+                "Combatant": {}
+            });
+            if let Some(flag) = is_active {
+                payload["isActive"] = flag;
+            }
+            parse_combat_data(&payload).expect("parsed").0
+        };
+
+        assert!(encounter_with(Some(json!(true))).is_active);
+        assert!(encounter_with(Some(json!("true"))).is_active);
+        assert!(encounter_with(Some(json!("True"))).is_active);
+        assert!(encounter_with(Some(json!(1))).is_active);
+        assert!(!encounter_with(Some(json!(false))).is_active);
+        assert!(!encounter_with(Some(json!("false"))).is_active);
+        assert!(!encounter_with(Some(json!(0))).is_active);
+        assert!(!encounter_with(None).is_active);
+    }
+
     #[test]
     fn parses_basic_combat_data() {
         let payload = json!({
@@ -304,7 +884,9 @@ mod tests {
                     "deaths": "0",
                     "enchps": "100",
                     "healed": "500",
-                    "OverHealPct": "5%"
+                    "OverHealPct": "5%",
+                    "damagetaken": "12,500",
+                    "maxhit": "Ten-Chi-Jin-21,345"
                 },
                 "Bob": {
                     "Job": "WHM",
@@ -315,7 +897,8 @@ mod tests {
                     "Deaths": "1",
                     "ENCHPS": "900",
                     "healed": "1,500",
-                    "OverHealPct": "15%"
+                    "OverHealPct": "15%",
+                    "MAXHIT": "9876"
                 }
             },
             "isActive": "true"
@@ -331,6 +914,13 @@ mod tests {
         assert_eq!(rows[0].share_str, "60.0%");
         assert_eq!(rows[1].name, "Bob");
         assert_eq!(rows[1].heal_share_str, "75.0%");
+        assert_eq!(rows[0].damage_taken, 12500.0);
+        assert_eq!(rows[0].damage_taken_str, "12,500");
+        assert_eq!(rows[1].damage_taken, 0.0);
+        assert_eq!(rows[0].max_hit, 21345.0);
+        assert_eq!(rows[0].max_hit_value(), "21,345");
+        assert_eq!(rows[1].max_hit, 9876.0);
+        assert_eq!(rows[1].max_hit_value(), "9876");
     }
 
     #[test]
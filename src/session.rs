@@ -0,0 +1,65 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::profile_dir;
+use crate::model::IdleScene;
+
+/// Transient UI state restored across launches (unlike `AppConfig`, never edited by hand).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionState {
+    #[serde(default)]
+    pub idle_scene: IdleScene,
+    #[serde(default = "default_show_idle_overlay")]
+    pub show_idle_overlay: bool,
+    #[serde(default)]
+    pub last_history_date: Option<String>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            idle_scene: IdleScene::default(),
+            show_idle_overlay: default_show_idle_overlay(),
+            last_history_date: None,
+        }
+    }
+}
+
+fn default_show_idle_overlay() -> bool {
+    true
+}
+
+pub fn load() -> Result<SessionState> {
+    let path = session_path();
+    match fs::read(&path) {
+        Ok(bytes) => {
+            let session: SessionState = serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to parse session at {}", path.display()))?;
+            Ok(session)
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(SessionState::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("Failed to read session at {}", path.display()))
+        }
+    }
+}
+
+pub fn save(session: &SessionState) -> Result<()> {
+    let path = session_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Unable to create config directory {}", parent.display()))?;
+    }
+    let data = serde_json::to_vec_pretty(session)?;
+    fs::write(&path, data)
+        .with_context(|| format!("Failed to write session to {}", path.display()))?;
+    Ok(())
+}
+
+pub fn session_path() -> PathBuf {
+    profile_dir().join("session.json")
+}
@@ -1,14 +1,497 @@
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
 use ratatui::style::{Color, Style};
+use serde::{Deserialize, Serialize};
 
-// Dark purple / cyberpunk palette (foreground-only to preserve terminal background)
-pub const ACCENT: Color = Color::Rgb(200, 60, 255); // neon purple
-pub const ACCENT_2: Color = Color::Rgb(0, 255, 200); // neon cyan-green
-pub const TEXT: Color = Color::Rgb(220, 210, 230);
-pub const STATUS_IDLE: Color = Color::Rgb(205, 102, 0); // dark orange
-pub const STATUS_DISCONNECTED: Color = Color::Rgb(220, 60, 60); // bright red
+// Color presets selectable from the settings pane; the active one is process-wide so the
+// style helpers below can stay free functions.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Theme {
+    // Dark purple / cyberpunk palette (the original look)
+    #[default]
+    Cyberpunk,
+    Monochrome,
+    // Only the 16 ANSI colors, for terminals without truecolor
+    HighContrast,
+    Solarized,
+}
 
-// Simple job color suggestions tuned toward purple/cyberpunk vibe
+impl Theme {
+    pub fn next(self) -> Self {
+        match self {
+            Theme::Cyberpunk => Theme::Monochrome,
+            Theme::Monochrome => Theme::HighContrast,
+            Theme::HighContrast => Theme::Solarized,
+            Theme::Solarized => Theme::Cyberpunk,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Theme::Cyberpunk => Theme::Solarized,
+            Theme::Monochrome => Theme::Cyberpunk,
+            Theme::HighContrast => Theme::Monochrome,
+            Theme::Solarized => Theme::HighContrast,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Cyberpunk => "Cyberpunk",
+            Theme::Monochrome => "Monochrome",
+            Theme::HighContrast => "High contrast",
+            Theme::Solarized => "Solarized",
+        }
+    }
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Theme::Cyberpunk => "cyberpunk",
+            Theme::Monochrome => "monochrome",
+            Theme::HighContrast => "high-contrast",
+            Theme::Solarized => "solarized",
+        }
+    }
+
+    pub fn from_config_key<S: AsRef<str>>(key: S) -> Self {
+        match key.as_ref().to_ascii_lowercase().as_str() {
+            "monochrome" => Theme::Monochrome,
+            "high-contrast" | "highcontrast" => Theme::HighContrast,
+            "solarized" => Theme::Solarized,
+            _ => Theme::Cyberpunk,
+        }
+    }
+
+    fn palette(self) -> &'static Palette {
+        match self {
+            Theme::Cyberpunk => &CYBERPUNK,
+            Theme::Monochrome => &MONOCHROME,
+            Theme::HighContrast => &HIGH_CONTRAST,
+            Theme::Solarized => &SOLARIZED,
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        match index {
+            1 => Theme::Monochrome,
+            2 => Theme::HighContrast,
+            3 => Theme::Solarized,
+            _ => Theme::Cyberpunk,
+        }
+    }
+
+    fn index(self) -> u8 {
+        match self {
+            Theme::Cyberpunk => 0,
+            Theme::Monochrome => 1,
+            Theme::HighContrast => 2,
+            Theme::Solarized => 3,
+        }
+    }
+}
+
+// How many colors the terminal can show; RGB palette entries are downsampled to fit.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorDepth::TrueColor => "Truecolor",
+            ColorDepth::Ansi256 => "256 colors",
+            ColorDepth::Ansi16 => "16 colors",
+        }
+    }
+
+    pub fn from_cli_value(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Ansi256),
+            "16" => Some(ColorDepth::Ansi16),
+            _ => None,
+        }
+    }
+
+    // Probe COLORTERM/TERM the same way most CLI tools do
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").ok();
+        let term = std::env::var("TERM").ok();
+        let windows_terminal = std::env::var_os("WT_SESSION").is_some();
+        Self::from_env(colorterm.as_deref(), term.as_deref(), windows_terminal)
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>, windows_terminal: bool) -> Self {
+        if let Some(value) = colorterm {
+            let value = value.to_ascii_lowercase();
+            if value == "truecolor" || value == "24bit" {
+                return ColorDepth::TrueColor;
+            }
+        }
+        if windows_terminal {
+            return ColorDepth::TrueColor;
+        }
+        match term {
+            Some(term) if term.contains("truecolor") || term.contains("direct") => {
+                ColorDepth::TrueColor
+            }
+            Some(term) if term.contains("256") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+
+    fn index(self) -> u8 {
+        match self {
+            ColorDepth::TrueColor => 0,
+            ColorDepth::Ansi256 => 1,
+            ColorDepth::Ansi16 => 2,
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        match index {
+            1 => ColorDepth::Ansi256,
+            2 => ColorDepth::Ansi16,
+            _ => ColorDepth::TrueColor,
+        }
+    }
+}
+
+/// Whether the terminal can be trusted with non-ASCII symbols such as the role glyphs.
+pub fn detect_unicode() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+    let term = std::env::var("TERM").ok();
+    let windows_terminal = std::env::var_os("WT_SESSION").is_some();
+    unicode_from_env(locale.as_deref(), term.as_deref(), windows_terminal)
+}
+
+// The Linux console and dumb terminals lack most symbol glyphs whatever the locale says
+fn unicode_from_env(locale: Option<&str>, term: Option<&str>, windows_terminal: bool) -> bool {
+    if windows_terminal {
+        return true;
+    }
+    if matches!(term, Some("linux" | "dumb")) {
+        return false;
+    }
+    locale
+        .map(|value| {
+            let value = value.to_ascii_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+        .unwrap_or(false)
+}
+
+static ACTIVE_THEME: AtomicU8 = AtomicU8::new(0);
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(0);
+static UNICODE_GLYPHS: AtomicBool = AtomicBool::new(true);
+
+pub fn set_theme(theme: Theme) {
+    ACTIVE_THEME.store(theme.index(), Ordering::Relaxed);
+}
+
+pub fn active_theme() -> Theme {
+    Theme::from_index(ACTIVE_THEME.load(Ordering::Relaxed))
+}
+
+pub fn set_color_depth(depth: ColorDepth) {
+    COLOR_DEPTH.store(depth.index(), Ordering::Relaxed);
+}
+
+pub fn color_depth() -> ColorDepth {
+    ColorDepth::from_index(COLOR_DEPTH.load(Ordering::Relaxed))
+}
+
+pub fn set_unicode_glyphs(enabled: bool) {
+    UNICODE_GLYPHS.store(enabled, Ordering::Relaxed);
+}
+
+/// Role marker drawn before names: a filled shape, or its letter on ASCII-only terminals.
+pub fn role_glyph(role: Role) -> &'static str {
+    if !UNICODE_GLYPHS.load(Ordering::Relaxed) {
+        return role.short_label();
+    }
+    match role {
+        Role::Tank => "■",
+        Role::Healer => "◆",
+        Role::Dps => "●",
+    }
+}
+
+// Downsample a palette color to what the terminal can render.
+fn adapt(color: Color) -> Color {
+    match (color_depth(), color) {
+        (ColorDepth::TrueColor, _) => color,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_xterm256(r, g, b)),
+        (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi16(r, g, b),
+        (ColorDepth::Ansi16, Color::Indexed(index)) if index >= 16 => {
+            let (r, g, b) = xterm256_to_rgb(index);
+            nearest_ansi16(r, g, b)
+        }
+        _ => color,
+    }
+}
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn cube_index(value: u8) -> u8 {
+    match value {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (value - 35) / 40,
+    }
+}
+
+fn rgb_to_xterm256(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+    let cube_rgb = (
+        CUBE_LEVELS[ri as usize],
+        CUBE_LEVELS[gi as usize],
+        CUBE_LEVELS[bi as usize],
+    );
+
+    // Near-neutral colors usually land closer on the grayscale ramp (232..=255)
+    let avg = ((r as u16 + g as u16 + b as u16) / 3) as u8;
+    let gray_step = if avg < 8 { 0 } else { ((avg - 8) / 10).min(23) };
+    let gray_value = 8 + gray_step * 10;
+    let gray = 232 + gray_step;
+
+    if distance((r, g, b), (gray_value, gray_value, gray_value)) < distance((r, g, b), cube_rgb) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn xterm256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[((i / 6) % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+// Typical xterm values for the 16 ANSI colors
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::White)
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+// Foreground-only colors (to preserve terminal background) plus role bar fills.
+struct Palette {
+    accent: Color,
+    accent_2: Color,
+    text: Color,
+    status_idle: Color,
+    status_disconnected: Color,
+    separator: Color,
+    tank_bar: Color,
+    healer_bar: Color,
+    dps_bar: Color,
+}
+
+const CYBERPUNK: Palette = Palette {
+    accent: Color::Rgb(200, 60, 255),  // neon purple
+    accent_2: Color::Rgb(0, 255, 200), // neon cyan-green
+    text: Color::Rgb(220, 210, 230),
+    status_idle: Color::Rgb(205, 102, 0),         // dark orange
+    status_disconnected: Color::Rgb(220, 60, 60), // bright red
+    separator: Color::Rgb(170, 170, 180),
+    // Role-based bar colors (xterm 256-indexed): tanks → blue, healers → green, DPS → red
+    tank_bar: Color::Indexed(75),
+    healer_bar: Color::Indexed(41),
+    dps_bar: Color::Indexed(124),
+};
+
+const MONOCHROME: Palette = Palette {
+    accent: Color::Rgb(240, 240, 240),
+    accent_2: Color::Rgb(205, 205, 205),
+    text: Color::Rgb(180, 180, 180),
+    status_idle: Color::Rgb(140, 140, 140),
+    status_disconnected: Color::Rgb(255, 255, 255),
+    separator: Color::Rgb(110, 110, 110),
+    tank_bar: Color::Indexed(250),
+    healer_bar: Color::Indexed(245),
+    dps_bar: Color::Indexed(240),
+};
+
+const HIGH_CONTRAST: Palette = Palette {
+    accent: Color::LightMagenta,
+    accent_2: Color::LightCyan,
+    text: Color::White,
+    status_idle: Color::Yellow,
+    status_disconnected: Color::LightRed,
+    separator: Color::DarkGray,
+    tank_bar: Color::Blue,
+    healer_bar: Color::Green,
+    dps_bar: Color::Red,
+};
+
+const SOLARIZED: Palette = Palette {
+    accent: Color::Rgb(108, 113, 196),            // violet
+    accent_2: Color::Rgb(42, 161, 152),           // cyan
+    text: Color::Rgb(147, 161, 161),              // base1
+    status_idle: Color::Rgb(203, 75, 22),         // orange
+    status_disconnected: Color::Rgb(220, 50, 47), // red
+    separator: Color::Rgb(88, 110, 117),          // base01
+    tank_bar: Color::Rgb(38, 139, 210),           // blue
+    healer_bar: Color::Rgb(133, 153, 0),          // green
+    dps_bar: Color::Rgb(220, 50, 47),             // red
+};
+
+fn palette() -> &'static Palette {
+    active_theme().palette()
+}
+
+pub fn accent() -> Color {
+    adapt(palette().accent)
+}
+
+pub fn accent_2() -> Color {
+    adapt(palette().accent_2)
+}
+
+pub fn text() -> Color {
+    adapt(palette().text)
+}
+
+pub fn status_idle() -> Color {
+    adapt(palette().status_idle)
+}
+
+pub fn status_disconnected() -> Color {
+    adapt(palette().status_disconnected)
+}
+
+pub fn separator() -> Color {
+    adapt(palette().separator)
+}
+
+/// ▲ next to a rising ENCDPS; fixed rather than per-theme so it always reads as "up".
+pub fn trend_up() -> Color {
+    adapt(Color::Rgb(80, 200, 120))
+}
+
+pub fn trend_down() -> Color {
+    adapt(Color::Rgb(230, 80, 80))
+}
+
+/// Q% column tiers, from strong to weak crit/DH rates.
+pub fn quality_good() -> Color {
+    adapt(Color::Rgb(80, 200, 120))
+}
+
+pub fn quality_fair() -> Color {
+    adapt(Color::Rgb(230, 200, 80))
+}
+
+pub fn quality_poor() -> Color {
+    adapt(Color::Rgb(230, 80, 80))
+}
+
+/// Brief background flash for combatants that joined mid-encounter.
+pub fn new_row_bg() -> Color {
+    adapt(Color::Rgb(40, 56, 84))
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    Tank,
+    Healer,
+    Dps,
+}
+
+impl Role {
+    /// One-letter tag used in the header's party composition badge.
+    pub fn short_label(self) -> &'static str {
+        match self {
+            Role::Tank => "T",
+            Role::Healer => "H",
+            Role::Dps => "D",
+        }
+    }
+}
+
+pub fn job_role(job: &str) -> Role {
+    match job {
+        "PLD" | "WAR" | "DRK" | "GNB" => Role::Tank,
+        "WHM" | "SCH" | "AST" | "SGE" => Role::Healer,
+        _ => Role::Dps,
+    }
+}
+
+// Per-job name colors; non-default themes fall back to role-tinted colors
 pub fn job_color(job: &str) -> Color {
+    adapt(match active_theme() {
+        Theme::Cyberpunk => cyberpunk_job_color(job),
+        Theme::Monochrome => match job_role(job) {
+            Role::Tank => Color::Rgb(235, 235, 235),
+            Role::Healer => Color::Rgb(205, 205, 205),
+            Role::Dps => Color::Rgb(175, 175, 175),
+        },
+        Theme::HighContrast => match job_role(job) {
+            Role::Tank => Color::LightBlue,
+            Role::Healer => Color::LightGreen,
+            Role::Dps => Color::LightRed,
+        },
+        Theme::Solarized => match job {
+            "PLD" | "WAR" | "DRK" | "GNB" => Color::Rgb(38, 139, 210),
+            "WHM" | "SCH" | "AST" | "SGE" => Color::Rgb(133, 153, 0),
+            "BRD" | "MCH" | "DNC" => Color::Rgb(181, 137, 0),
+            "BLM" | "SMN" | "RDM" | "PCT" | "BLU" => Color::Rgb(211, 54, 130),
+            _ => Color::Rgb(203, 75, 22),
+        },
+    })
+}
+
+// Simple job color suggestions tuned toward purple/cyberpunk vibe
+fn cyberpunk_job_color(job: &str) -> Color {
     match job {
         // Tanks
         "PLD" => Color::Rgb(180, 160, 255),
@@ -38,31 +521,83 @@ pub fn job_color(job: &str) -> Color {
         "PCT" => Color::Rgb(180, 220, 255),
         // Limited
         "BLU" => Color::Rgb(140, 200, 255),
-        _ => ACCENT,
+        _ => CYBERPUNK.accent,
     }
 }
 
 pub fn header_style() -> Style {
-    Style::default().fg(TEXT)
+    Style::default().fg(text())
 }
 pub fn title_style() -> Style {
-    Style::default().fg(ACCENT)
+    Style::default().fg(accent())
 }
 pub fn value_style() -> Style {
-    Style::default().fg(ACCENT_2)
+    Style::default().fg(accent_2())
 }
 
-// Role-based color for DPS bars (xterm 256-indexed colors)
-// Tanks → blue(75), Healers → green(41), DPS → red(124)
+// Role-based color for DPS bars
 pub fn role_bar_color(job: &str) -> Color {
-    match job {
-        // Tanks
-        "PLD" | "WAR" | "DRK" | "GNB" => Color::Indexed(75),
-        // Healers
-        "WHM" | "SCH" | "AST" | "SGE" => Color::Indexed(41),
-        // Everything else treated as DPS
-        _ => Color::Indexed(124),
-    }
+    role_color(job_role(job))
+}
+
+pub fn role_color(role: Role) -> Color {
+    let palette = palette();
+    adapt(match role {
+        Role::Tank => palette.tank_bar,
+        Role::Healer => palette.healer_bar,
+        Role::Dps => palette.dps_bar,
+    })
 }
 
 // Gradient helpers removed; we use solid role colors for bars.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_jobs_by_role() {
+        assert_eq!(job_role("GNB"), Role::Tank);
+        assert_eq!(job_role("SGE"), Role::Healer);
+        assert_eq!(job_role("PCT"), Role::Dps);
+    }
+
+    #[test]
+    fn detects_depth_from_env() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm-256color"), false),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-256color"), false),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm"), false),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, None, true),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[test]
+    fn detects_unicode_from_locale_and_term() {
+        assert!(unicode_from_env(Some("en_US.UTF-8"), Some("xterm"), false));
+        assert!(unicode_from_env(Some("de_DE.utf8"), None, false));
+        assert!(!unicode_from_env(Some("C"), Some("xterm-256color"), false));
+        assert!(!unicode_from_env(None, Some("xterm"), false));
+        assert!(!unicode_from_env(Some("en_US.UTF-8"), Some("linux"), false));
+        assert!(unicode_from_env(None, None, true));
+    }
+
+    #[test]
+    fn downsamples_rgb() {
+        assert_eq!(rgb_to_xterm256(255, 0, 0), 196);
+        assert_eq!(rgb_to_xterm256(128, 128, 128), 244);
+        assert_eq!(xterm256_to_rgb(196), (255, 0, 0));
+        assert_eq!(nearest_ansi16(220, 60, 60), Color::Red);
+        assert_eq!(nearest_ansi16(250, 250, 250), Color::White);
+    }
+}
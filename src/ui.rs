@@ -1,43 +1,267 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 
+use chrono::Local;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table};
+use ratatui::widgets::{
+    Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+    Table, Wrap,
+};
 use ratatui::Frame;
 
-use crate::model::{AppSnapshot, CombatantRow, Decoration, SettingsField, ViewMode};
-use crate::theme::{header_style, job_color, role_bar_color, title_style, value_style, TEXT};
+use crate::model::{
+    role_encdps_totals, AboutInfo, AppSnapshot, CombatantRow, Decoration, SettingsField, SortKey,
+    TableColumn, ViewLayout, ViewMode,
+};
+use crate::theme::{
+    self, header_style, job_color, job_role, role_bar_color, role_color, title_style, value_style,
+    Role,
+};
+use crate::ui_confirm;
 use crate::ui_history;
 use crate::ui_idle;
+use crate::ui_logs;
+use crate::util::{abbreviate_number, display_total, format_clock, group_thousands};
 
-pub fn draw(f: &mut Frame, s: &AppSnapshot) {
-    if s.history.visible {
+/// Draws the frame; returns how many live table rows fit on screen when the table was drawn,
+/// so the state can stop scrolling at the last full page.
+pub fn draw(f: &mut Frame, s: &AppSnapshot) -> Option<usize> {
+    let page = if s.history.visible {
         ui_history::draw_history(f, s);
-        return;
+        None
+    } else if s.compact {
+        draw_compact(f, s)
+    } else {
+        draw_live(f, s)
+    };
+
+    if let Some(confirm) = &s.confirm {
+        ui_confirm::draw_confirm(f, confirm);
     }
+    page
+}
 
-    // Split into header + table + footer/status
+/// Header, table (or idle scene) and footer, with the settings and help modals on top.
+fn draw_live(f: &mut Frame, s: &AppSnapshot) -> Option<usize> {
+    // Split into header + table + footer/status; hidden parts hand their rows to the table
+    let mut constraints = Vec::with_capacity(3);
+    if s.settings.show_header {
+        constraints.push(Constraint::Length(3));
+    }
+    constraints.push(Constraint::Min(4));
+    if s.settings.show_footer {
+        constraints.push(Constraint::Length(1));
+    }
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(4),
-            Constraint::Length(1),
-        ])
+        .constraints(constraints)
         .split(f.size());
+    let body = usize::from(s.settings.show_header);
 
-    draw_header(f, chunks[0], s);
-    if s.is_idle && s.show_idle_overlay {
-        ui_idle::draw_idle(f, chunks[1], s);
+    if s.settings.show_header {
+        draw_header(f, chunks[0], s);
+    }
+    let page = if s.is_idle && s.show_idle_overlay {
+        ui_idle::draw_idle(f, chunks[body], s);
+        None
     } else {
-        draw_table(f, chunks[1], s);
+        Some(draw_tables(f, chunks[body], s))
+    };
+    if s.settings.show_footer {
+        draw_status(f, chunks[body + 1], s);
     }
-    draw_status(f, chunks[2], s);
 
     if s.show_settings {
         draw_settings(f, s);
     }
+    if s.show_help {
+        draw_help(f, s);
+    }
+    if s.show_logs {
+        ui_logs::draw_logs(f, s);
+    }
+    if let Some(about) = &s.about {
+        draw_about(f, about);
+    }
+    page
+}
+
+/// Table only, filling the whole terminal; connection state shrinks to a dot in the top-right
+/// corner of the header separator.
+fn draw_compact(f: &mut Frame, s: &AppSnapshot) -> Option<usize> {
+    let area = f.size();
+    let page = if s.is_idle && s.show_idle_overlay {
+        ui_idle::draw_idle(f, area, s);
+        None
+    } else {
+        Some(draw_tables(f, area, s))
+    };
+    if area.width > 0 && area.height > 1 {
+        let corner = Rect {
+            x: area.right() - 1,
+            y: area.y + 1,
+            width: 1,
+            height: 1,
+        };
+        let (_, style) = connection_status(s);
+        f.render_widget(Paragraph::new(Span::styled("●", style)), corner);
+    }
+
+    if s.show_settings {
+        draw_settings(f, s);
+    }
+    if s.show_help {
+        draw_help(f, s);
+    }
+    if s.show_logs {
+        ui_logs::draw_logs(f, s);
+    }
+    if let Some(about) = &s.about {
+        draw_about(f, about);
+    }
+    page
+}
+
+/// Version and paths for bug reports, styled like the settings pane.
+fn draw_about(f: &mut Frame, about: &AboutInfo) {
+    let area = centered_rect(70, 40, f.size());
+    f.render_widget(Clear, area);
+
+    let encounters = about
+        .encounters
+        .map(|count| count.to_string())
+        .unwrap_or_else(|| "counting…".to_string());
+    let language = about
+        .language
+        .clone()
+        .unwrap_or_else(|| "not reported yet".to_string());
+    let entries = [
+        ("Version", about.version.clone()),
+        ("Source", about.source.clone()),
+        ("Language", language),
+        ("Config", about.config_path.clone()),
+        ("History", about.history_path.clone()),
+        ("Encounters", encounters),
+    ];
+    let mut lines = vec![
+        Line::from(Span::styled(
+            " iinact-tui",
+            title_style().add_modifier(Modifier::BOLD),
+        )),
+        Line::default(),
+    ];
+    lines.extend(entries.into_iter().map(|(label, value)| {
+        Line::from(vec![
+            Span::styled(format!(" {label:>10}  "), header_style()),
+            Span::styled(value, value_style()),
+        ])
+    }));
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        " Press Esc to close.",
+        header_style(),
+    )));
+
+    let block = Block::default().title("About").borders(Borders::ALL);
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false });
+    f.render_widget(widget, area);
+}
+
+/// Fixed main-screen keys listed in the help overlay after the remappable ones.
+const FIXED_KEY_HELP: [(&str, &str); 8] = [
+    ("o", "cycle the sort column"),
+    ("f", "end the current encounter"),
+    ("Space", "pause/resume the table"),
+    ("L", "recent encounters"),
+    ("[ ] Tab", "cycle idle scenes"),
+    ("+ -", "replay speed"),
+    ("PgUp/Dn", "scroll a long table"),
+    ("Esc", "quit / close the open panel"),
+];
+
+/// Modal listing the main-screen keys, so they can be recalled while the footer is hidden.
+fn draw_help(f: &mut Frame, s: &AppSnapshot) {
+    let area = centered_rect(50, 70, f.size());
+    f.render_widget(Clear, area);
+
+    let key_line = |key: String, description: &str| {
+        Line::from(vec![
+            Span::styled(format!(" {key:>7} "), title_style()),
+            Span::styled(description.to_string(), value_style()),
+        ])
+    };
+    let mut lines: Vec<Line> = s
+        .key_help
+        .iter()
+        .map(|(key, description)| key_line(key.to_string(), description))
+        .collect();
+    lines.extend(
+        FIXED_KEY_HELP
+            .iter()
+            .map(|(key, description)| key_line(key.to_string(), description)),
+    );
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(
+        " Press any key to close.",
+        header_style(),
+    )));
+
+    let block = Block::default().title("Keys").borders(Borders::ALL);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Party composition such as `2T 2H 4D`, each count tinted with its role bar color.
+fn role_badge(rows: &[CombatantRow]) -> Vec<Span<'static>> {
+    [Role::Tank, Role::Healer, Role::Dps]
+        .into_iter()
+        .enumerate()
+        .flat_map(|(idx, role)| {
+            let count = rows.iter().filter(|row| job_role(&row.job) == role).count();
+            let style = if count == 0 {
+                header_style()
+            } else {
+                Style::default().fg(role_color(role))
+            };
+            let separator = Span::raw(if idx == 0 { "" } else { " " });
+            [
+                separator,
+                Span::styled(format!("{count}{}", role.short_label()), style),
+            ]
+        })
+        .collect()
+}
+
+/// `Tank: 5,000 | Healer: 5,500 | DPS: 21,000`, each label tinted with its role color.
+fn role_totals_spans(s: &AppSnapshot) -> Vec<Span<'static>> {
+    let (tank, healer, dps) = role_encdps_totals(&s.rows);
+    let format_total = |value: f64| {
+        if s.settings.abbreviate_numbers {
+            abbreviate_number(value)
+        } else {
+            group_thousands(value)
+        }
+    };
+    [
+        (Role::Tank, "Tank", tank),
+        (Role::Healer, "Healer", healer),
+        (Role::Dps, "DPS", dps),
+    ]
+    .into_iter()
+    .enumerate()
+    .flat_map(|(idx, (role, label, total))| {
+        let separator = Span::raw(if idx == 0 { "" } else { " | " });
+        [
+            separator,
+            Span::styled(format!("{label}:"), Style::default().fg(role_color(role))),
+            Span::styled(format!(" {}", format_total(total)), value_style()),
+        ]
+    })
+    .collect()
 }
 
 fn right_align(text: &str, width: usize) -> String {
@@ -56,8 +280,30 @@ fn right_align(text: &str, width: usize) -> String {
     }
 }
 
-#[derive(Copy, Clone)]
+/// Width of the optional Damage/Healed column; the raw strings run to `12,345,678`.
+const TOTAL_COLUMN_WIDTH: usize = 11;
+/// Width of the optional absolute overheal column; sized like the Healed total.
+const OVERHEAL_COLUMN_WIDTH: usize = 11;
+/// Width of the optional rDPS column.
+const ROLLING_DPS_COLUMN_WIDTH: usize = 9;
+/// Width of the optional Q% column.
+const QUALITY_COLUMN_WIDTH: usize = 6;
+/// Width of the optional CDH% column.
+const CDH_COLUMN_WIDTH: usize = 6;
+/// Width of the optional Active column; fits `MM:SS` and the header.
+const ACTIVE_COLUMN_WIDTH: usize = 6;
+/// Narrowest terminal that gets the split layout: each half keeps the 58-column table.
+const SPLIT_MIN_WIDTH: u16 = 117;
+/// Narrowest the Name column of a configured layout gets before trailing columns are dropped.
+const CONFIGURED_NAME_MIN_WIDTH: usize = 16;
+/// Q% at or above this is drawn green; below `QUALITY_FAIR` it is red, yellow in between.
+const QUALITY_GOOD: f64 = 30.0;
+const QUALITY_FAIR: f64 = 20.0;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 enum TableVariant {
+    /// Full plus the max-hit column (DPS mode only).
+    Wide,
     Full,
     NoDeaths,
     NoDhDeaths,
@@ -66,8 +312,16 @@ enum TableVariant {
 }
 
 impl TableVariant {
+    /// Whether an optional column of `column_width` fits without pushing the table into a
+    /// narrower variant, so optional columns are always the first to drop as the terminal shrinks.
+    fn fits_extra_column(self, width: usize, column_width: usize) -> bool {
+        TableVariant::from_width(width.saturating_sub(column_width + 1)) == self
+    }
+
     fn from_width(width: usize) -> Self {
-        if width >= 90 {
+        if width >= 104 {
+            TableVariant::Wide
+        } else if width >= 90 {
             TableVariant::Full
         } else if width >= 72 {
             TableVariant::NoDeaths
@@ -95,6 +349,8 @@ impl Align {
     }
 }
 
+type CellValue = fn(&CombatantRow) -> String;
+
 struct ColumnSpec {
     header: &'static str,
     align: Align,
@@ -104,8 +360,29 @@ struct ColumnSpec {
 }
 
 impl ColumnSpec {
-    fn header_cell(&self) -> Cell<'static> {
-        Cell::from(self.align.format(self.header))
+    fn header_cell(&self, mode: ViewMode, sort_key: Option<SortKey>) -> Cell<'static> {
+        let Some(sort_key) =
+            sort_key.filter(|key| column_sort_key(self.header, mode) == Some(*key))
+        else {
+            return Cell::from(self.align.format(self.header));
+        };
+        let arrow = if sort_key.ascending() { "▲" } else { "▼" };
+        Cell::from(self.align.format(&format!("{}{}", self.header, arrow)))
+    }
+
+    /// The same column at a fixed width; the left-aligned Name column takes `width` as a
+    /// percentage of the table instead.
+    fn sized(mut self, width: u16) -> Self {
+        match self.align {
+            Align::Left => self.width = Constraint::Percentage(width),
+            Align::Right { .. } => {
+                self.align = Align::Right {
+                    width: width as usize,
+                };
+                self.width = Constraint::Length(width);
+            }
+        }
+        self
     }
 
     fn data_cell(&self, row: &CombatantRow) -> Cell<'static> {
@@ -139,14 +416,143 @@ impl LayoutSpec {
         self
     }
 
-    fn header_row(&self) -> Row<'static> {
-        Row::new(self.columns.iter().map(|col| col.header_cell()))
-            .style(header_style())
-            .height(self.header_height)
+    /// Adds the absolute Damage (after ENCDPS) or Healed (after ENCHPS) column; Tank mode
+    /// already shows its total and is unchanged.
+    fn with_total_column(mut self, mode: ViewMode, abbreviate: bool) -> Self {
+        let (anchor, column) = match mode {
+            ViewMode::Dps => ("ENCDPS", TableColumn::Damage),
+            ViewMode::Heal => ("ENCHPS", TableColumn::Healed),
+            ViewMode::Tank => return self,
+        };
+        if let Some(idx) = self.columns.iter().position(|col| col.header == anchor) {
+            self.columns
+                .insert(idx + 1, configured_column(column, abbreviate));
+        }
+        self
+    }
+
+    /// Adds the Q% column after DH%; layouts without DH% are unchanged.
+    fn with_quality_column(mut self) -> Self {
+        if let Some(idx) = self.columns.iter().position(|col| col.header == "DH%") {
+            self.columns
+                .insert(idx + 1, configured_column(TableColumn::Quality, false));
+        }
+        self
+    }
+
+    /// Adds the CDH% column after Q% (or DH% without it); layouts without DH% are unchanged.
+    fn with_cdh_column(mut self) -> Self {
+        let anchor = self
+            .columns
+            .iter()
+            .position(|col| col.header == "Q%")
+            .or_else(|| self.columns.iter().position(|col| col.header == "DH%"));
+        if let Some(idx) = anchor {
+            self.columns
+                .insert(idx + 1, configured_column(TableColumn::Cdh, false));
+        }
+        self
+    }
+
+    /// Adds the Active column before Deaths, or at the end of layouts without one.
+    fn with_active_column(mut self) -> Self {
+        let idx = self
+            .columns
+            .iter()
+            .position(|col| col.header == "Deaths")
+            .unwrap_or(self.columns.len());
+        self.columns
+            .insert(idx, configured_column(TableColumn::Active, false));
+        self
+    }
+
+    /// Adds the absolute Overheal column after Overheal%; layouts without Overheal% are unchanged.
+    fn with_overheal_column(mut self, abbreviate: bool) -> Self {
+        if let Some(idx) = self
+            .columns
+            .iter()
+            .position(|col| col.header == "Overheal%")
+        {
+            self.columns.insert(
+                idx + 1,
+                configured_column(TableColumn::OverhealAmount, abbreviate),
+            );
+        }
+        self
+    }
+
+    /// Adds the rolling rDPS column after ENCDPS; layouts without ENCDPS are unchanged.
+    fn with_rolling_dps_column(mut self, abbreviate: bool) -> Self {
+        if let Some(idx) = self.columns.iter().position(|col| col.header == "ENCDPS") {
+            self.columns.insert(
+                idx + 1,
+                configured_column(TableColumn::RollingDps, abbreviate),
+            );
+        }
+        self
+    }
+
+    /// Adds the ▲/▼ trend column right after ENCDPS; layouts without ENCDPS are unchanged.
+    fn with_trend_column(mut self) -> Self {
+        if let Some(idx) = self.columns.iter().position(|col| col.header == "ENCDPS") {
+            self.columns.insert(
+                idx + 1,
+                ColumnSpec {
+                    header: "",
+                    align: Align::Left,
+                    width: Constraint::Length(1),
+                    value: value_trend,
+                    style: Some(trend_style),
+                },
+            );
+        }
+        self
+    }
+
+    /// Header cells, with an arrow on the column `sort_key` orders by when there is one.
+    fn header_row(&self, mode: ViewMode, sort_key: Option<SortKey>) -> Row<'static> {
+        Row::new(
+            self.columns
+                .iter()
+                .map(|col| col.header_cell(mode, sort_key)),
+        )
+        .style(header_style())
+        .height(self.header_height)
+    }
+
+    /// Adds a role glyph column in front of Name, so the glyph's two cells come out of the
+    /// Name width and the header stays over the names. Layouts without Name are unchanged.
+    fn with_role_glyphs(mut self) -> Self {
+        if let Some(idx) = self.columns.iter().position(|col| col.header == "Name") {
+            self.columns.insert(
+                idx,
+                ColumnSpec {
+                    header: "",
+                    align: Align::Left,
+                    width: Constraint::Length(1),
+                    value: value_role_glyph,
+                    style: Some(role_glyph_style),
+                },
+            );
+        }
+        self
     }
 
     fn data_row(&self, row: &CombatantRow, row_height: u16) -> Row<'static> {
-        Row::new(self.columns.iter().map(|col| col.data_cell(row))).height(row_height)
+        let cells = self.columns.iter().map(|col| col.data_cell(row));
+        let data = Row::new(cells).height(row_height);
+        if row.recently_joined {
+            data.style(Style::default().bg(theme::new_row_bg()))
+        } else {
+            data
+        }
+    }
+
+    /// Plain cells for the party-total line: no role glyph and no per-row highlight.
+    fn total_row(&self, row: &CombatantRow, row_height: u16) -> Row<'static> {
+        Row::new(self.columns.iter().map(|col| col.data_cell(row)))
+            .height(row_height)
+            .style(title_style().add_modifier(Modifier::BOLD))
     }
 
     fn widths(&self) -> Vec<Constraint> {
@@ -154,8 +560,25 @@ impl LayoutSpec {
     }
 }
 
+/// Which sort key a column header represents, so the active one can carry an arrow.
+fn column_sort_key(header: &str, mode: ViewMode) -> Option<SortKey> {
+    match (header, mode) {
+        ("Name", _) => Some(SortKey::Name),
+        ("Share%", _) => Some(SortKey::Damage),
+        ("Crit%", _) => Some(SortKey::Crit),
+        ("Deaths", _) => Some(SortKey::Deaths),
+        ("ENCDPS", ViewMode::Dps) | ("Taken", ViewMode::Tank) => Some(SortKey::Metric),
+        _ => None,
+    }
+}
+
 fn name_style(row: &CombatantRow) -> Style {
-    Style::default().fg(job_color(&row.job))
+    let style = Style::default().fg(job_color(&row.job));
+    if row.is_self {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
 }
 
 fn value_name(row: &CombatantRow) -> String {
@@ -174,6 +597,58 @@ fn value_encdps(row: &CombatantRow) -> String {
     row.encdps_str.clone()
 }
 
+fn value_rolling_dps(row: &CombatantRow) -> String {
+    row.rolling_dps
+        .map(|dps| format!("{dps:.0}"))
+        .unwrap_or_default()
+}
+
+fn value_rolling_dps_short(row: &CombatantRow) -> String {
+    row.rolling_dps.map(abbreviate_number).unwrap_or_default()
+}
+
+/// Blank for rows without a job, such as the party total.
+fn value_role_glyph(row: &CombatantRow) -> String {
+    if row.job.is_empty() {
+        return String::new();
+    }
+    theme::role_glyph(theme::job_role(&row.job)).to_string()
+}
+
+fn role_glyph_style(row: &CombatantRow) -> Style {
+    Style::default().fg(theme::role_color(theme::job_role(&row.job)))
+}
+
+fn value_trend(row: &CombatantRow) -> String {
+    match row.trend {
+        Some(Ordering::Greater) => "▲".to_string(),
+        Some(Ordering::Less) => "▼".to_string(),
+        _ => String::new(),
+    }
+}
+
+fn trend_style(row: &CombatantRow) -> Style {
+    match row.trend {
+        Some(Ordering::Less) => Style::default().fg(theme::trend_down()),
+        _ => Style::default().fg(theme::trend_up()),
+    }
+}
+
+fn value_quality(row: &CombatantRow) -> String {
+    row.quality_pct()
+        .map(|q| format!("{q:.1}"))
+        .unwrap_or_default()
+}
+
+fn quality_style(row: &CombatantRow) -> Style {
+    match row.quality_pct() {
+        Some(q) if q >= QUALITY_GOOD => Style::default().fg(theme::quality_good()),
+        Some(q) if q >= QUALITY_FAIR => Style::default().fg(theme::quality_fair()),
+        Some(_) => Style::default().fg(theme::quality_poor()),
+        None => Style::default(),
+    }
+}
+
 fn value_enchps(row: &CombatantRow) -> String {
     row.enchps_str.clone()
 }
@@ -190,14 +665,62 @@ fn value_dh(row: &CombatantRow) -> String {
     row.dh.clone()
 }
 
+fn value_cdh(row: &CombatantRow) -> String {
+    row.cdh.clone()
+}
+
+fn value_active(row: &CombatantRow) -> String {
+    row.duration.clone()
+}
+
 fn value_deaths(row: &CombatantRow) -> String {
     row.deaths.clone()
 }
 
+fn value_max_hit(row: &CombatantRow) -> String {
+    row.max_hit_value().to_string()
+}
+
+fn value_damage(row: &CombatantRow) -> String {
+    row.damage_str.clone()
+}
+
+fn value_damage_short(row: &CombatantRow) -> String {
+    abbreviate_number(row.damage)
+}
+
+fn value_healed(row: &CombatantRow) -> String {
+    row.healed_str.clone()
+}
+
+fn value_healed_short(row: &CombatantRow) -> String {
+    abbreviate_number(row.healed)
+}
+
 fn value_overheal(row: &CombatantRow) -> String {
     row.overheal_pct.clone()
 }
 
+fn value_overheal_amount(row: &CombatantRow) -> String {
+    row.overheal_str.clone()
+}
+
+fn value_overheal_amount_short(row: &CombatantRow) -> String {
+    display_total(&row.overheal_str, true)
+}
+
+fn value_damage_taken(row: &CombatantRow) -> String {
+    row.damage_taken_str.clone()
+}
+
+fn value_damage_taken_short(row: &CombatantRow) -> String {
+    abbreviate_number(row.damage_taken)
+}
+
+fn value_name_with_damage_taken_short(row: &CombatantRow) -> String {
+    format!("{}  [{}]", row.name, abbreviate_number(row.damage_taken))
+}
+
 fn value_name_with_share(row: &CombatantRow) -> String {
     format!("{}  [{}]", row.name, row.share_str)
 }
@@ -206,6 +729,10 @@ fn value_name_with_heal_share(row: &CombatantRow) -> String {
     format!("{}  [{}]", row.name, row.heal_share_str)
 }
 
+fn value_name_with_damage_taken(row: &CombatantRow) -> String {
+    format!("{}  [{}]", row.name, row.damage_taken_str)
+}
+
 fn name_column(width: Constraint) -> ColumnSpec {
     ColumnSpec {
         header: "Name",
@@ -246,77 +773,262 @@ fn left_column(
     }
 }
 
-fn layout_for(mode: ViewMode, variant: TableVariant) -> LayoutSpec {
+/// Columns of each built-in layout, with the width each one gets there (a percentage for Name).
+fn builtin_columns(mode: ViewMode, variant: TableVariant) -> &'static [(TableColumn, u16)] {
+    use TableColumn::*;
     match (mode, variant) {
-        (ViewMode::Dps, TableVariant::Full) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(34)),
-            right_column("Share%", 7, Constraint::Length(7), value_share),
-            right_column("ENCDPS", 10, Constraint::Length(10), value_encdps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Crit%", 8, Constraint::Length(8), value_crit),
-            right_column("DH%", 8, Constraint::Length(8), value_dh),
-            right_column("Deaths", 8, Constraint::Length(8), value_deaths),
-        ]),
-        (ViewMode::Heal, TableVariant::Full) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(34)),
-            right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
-            right_column("ENCHPS", 10, Constraint::Length(10), value_enchps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Overheal%", 10, Constraint::Length(10), value_overheal),
-            right_column("Deaths", 8, Constraint::Length(8), value_deaths),
-        ]),
-        (ViewMode::Dps, TableVariant::NoDeaths) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(38)),
-            right_column("Share%", 7, Constraint::Length(7), value_share),
-            right_column("ENCDPS", 9, Constraint::Length(9), value_encdps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Crit%", 6, Constraint::Length(6), value_crit),
-            right_column("DH%", 6, Constraint::Length(6), value_dh),
-        ]),
-        (ViewMode::Heal, TableVariant::NoDeaths) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(44)),
-            right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
-            right_column("ENCHPS", 9, Constraint::Length(9), value_enchps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-            right_column("Overheal%", 9, Constraint::Length(9), value_overheal),
-        ]),
-        (ViewMode::Dps, TableVariant::NoDhDeaths) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(54)),
-            right_column("Share%", 7, Constraint::Length(7), value_share),
-            right_column("ENCDPS", 9, Constraint::Length(9), value_encdps),
-            right_column("Crit%", 6, Constraint::Length(6), value_crit),
-        ]),
-        (ViewMode::Heal, TableVariant::NoDhDeaths) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(58)),
-            right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
-            right_column("ENCHPS", 9, Constraint::Length(9), value_enchps),
-            right_column("Job", 5, Constraint::Length(5), value_job),
-        ]),
-        (ViewMode::Dps, TableVariant::Minimal) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(64)),
-            right_column("Share%", 6, Constraint::Length(6), value_share),
-            right_column("ENCDPS", 9, Constraint::Length(9), value_encdps),
-        ]),
-        (ViewMode::Heal, TableVariant::Minimal) => LayoutSpec::new(vec![
-            name_column(Constraint::Percentage(64)),
-            right_column("Heal%", 6, Constraint::Length(6), value_heal_share),
-            right_column("ENCHPS", 9, Constraint::Length(9), value_enchps),
-        ]),
-        (ViewMode::Dps, TableVariant::NameOnly) => LayoutSpec::new(vec![left_column(
-            "Name (Share%)",
-            Constraint::Percentage(100),
-            value_name_with_share,
-            Some(name_style),
-        )])
-        .with_spacing(0),
-        (ViewMode::Heal, TableVariant::NameOnly) => LayoutSpec::new(vec![left_column(
-            "Name (Heal%)",
-            Constraint::Percentage(100),
-            value_name_with_heal_share,
-            Some(name_style),
-        )])
-        .with_spacing(0),
+        (ViewMode::Dps, TableVariant::Wide) => &[
+            (Name, 32),
+            (Share, 7),
+            (Encdps, 10),
+            (Job, 5),
+            (Crit, 8),
+            (Dh, 8),
+            (MaxHit, 9),
+            (Deaths, 8),
+        ],
+        (ViewMode::Heal | ViewMode::Tank, TableVariant::Wide) => {
+            builtin_columns(mode, TableVariant::Full)
+        }
+        (ViewMode::Dps, TableVariant::Full) => &[
+            (Name, 34),
+            (Share, 7),
+            (Encdps, 10),
+            (Job, 5),
+            (Crit, 8),
+            (Dh, 8),
+            (Deaths, 8),
+        ],
+        (ViewMode::Heal, TableVariant::Full) => &[
+            (Name, 34),
+            (HealShare, 7),
+            (Enchps, 10),
+            (Job, 5),
+            (Overheal, 10),
+            (Deaths, 8),
+        ],
+        (ViewMode::Tank, TableVariant::Full) => {
+            &[(Name, 34), (Taken, 12), (Job, 5), (Enchps, 10), (Deaths, 8)]
+        }
+        (ViewMode::Dps, TableVariant::NoDeaths) => &[
+            (Name, 38),
+            (Share, 7),
+            (Encdps, 9),
+            (Job, 5),
+            (Crit, 6),
+            (Dh, 6),
+        ],
+        (ViewMode::Heal, TableVariant::NoDeaths) => &[
+            (Name, 44),
+            (HealShare, 7),
+            (Enchps, 9),
+            (Job, 5),
+            (Overheal, 9),
+        ],
+        (ViewMode::Tank, TableVariant::NoDeaths) => {
+            &[(Name, 44), (Taken, 11), (Job, 5), (Enchps, 9)]
+        }
+        (ViewMode::Dps, TableVariant::NoDhDeaths) => {
+            &[(Name, 54), (Share, 7), (Encdps, 9), (Crit, 6)]
+        }
+        (ViewMode::Heal, TableVariant::NoDhDeaths) => {
+            &[(Name, 58), (HealShare, 7), (Enchps, 9), (Job, 5)]
+        }
+        (ViewMode::Tank, TableVariant::NoDhDeaths) => &[(Name, 58), (Taken, 11), (Job, 5)],
+        (ViewMode::Dps, TableVariant::Minimal) => &[(Name, 64), (Share, 6), (Encdps, 9)],
+        (ViewMode::Heal, TableVariant::Minimal) => &[(Name, 64), (HealShare, 6), (Enchps, 9)],
+        (ViewMode::Tank, TableVariant::Minimal) => &[(Name, 64), (Taken, 10)],
+        // Drawn as one combined column; see `layout_for`.
+        (_, TableVariant::NameOnly) => &[],
+    }
+}
+
+fn layout_for(mode: ViewMode, variant: TableVariant, abbreviate: bool) -> LayoutSpec {
+    if variant != TableVariant::NameOnly {
+        return LayoutSpec::new(
+            builtin_columns(mode, variant)
+                .iter()
+                .map(|&(column, width)| configured_column(column, abbreviate).sized(width))
+                .collect(),
+        );
+    }
+    let (header, value): (&'static str, CellValue) = match (mode, abbreviate) {
+        (ViewMode::Dps, _) => ("Name (Share%)", value_name_with_share),
+        (ViewMode::Heal, _) => ("Name (Heal%)", value_name_with_heal_share),
+        (ViewMode::Tank, false) => ("Name (Taken)", value_name_with_damage_taken),
+        (ViewMode::Tank, true) => ("Name (Taken)", value_name_with_damage_taken_short),
+    };
+    LayoutSpec::new(vec![left_column(
+        header,
+        Constraint::Percentage(100),
+        value,
+        Some(name_style),
+    )])
+    .with_spacing(0)
+}
+
+/// The one definition of each table column, sized like the widest built-in layout; the built-in
+/// layouts resize these through `ColumnSpec::sized`.
+fn configured_column(column: TableColumn, abbreviate: bool) -> ColumnSpec {
+    let total = Constraint::Length(TOTAL_COLUMN_WIDTH as u16);
+    match column {
+        TableColumn::Name => name_column(Constraint::Min(CONFIGURED_NAME_MIN_WIDTH as u16)),
+        TableColumn::Job => right_column("Job", 5, Constraint::Length(5), value_job),
+        TableColumn::Share => right_column("Share%", 7, Constraint::Length(7), value_share),
+        TableColumn::HealShare => right_column("Heal%", 7, Constraint::Length(7), value_heal_share),
+        TableColumn::Encdps => right_column("ENCDPS", 10, Constraint::Length(10), value_encdps),
+        TableColumn::Enchps => right_column("ENCHPS", 10, Constraint::Length(10), value_enchps),
+        TableColumn::Crit => right_column("Crit%", 8, Constraint::Length(8), value_crit),
+        TableColumn::Dh => right_column("DH%", 8, Constraint::Length(8), value_dh),
+        TableColumn::Quality => ColumnSpec {
+            header: "Q%",
+            align: Align::Right {
+                width: QUALITY_COLUMN_WIDTH,
+            },
+            width: Constraint::Length(QUALITY_COLUMN_WIDTH as u16),
+            value: value_quality,
+            style: Some(quality_style),
+        },
+        TableColumn::Cdh => right_column(
+            "CDH%",
+            CDH_COLUMN_WIDTH,
+            Constraint::Length(CDH_COLUMN_WIDTH as u16),
+            value_cdh,
+        ),
+        TableColumn::Active => right_column(
+            "Active",
+            ACTIVE_COLUMN_WIDTH,
+            Constraint::Length(ACTIVE_COLUMN_WIDTH as u16),
+            value_active,
+        ),
+        TableColumn::Deaths => right_column("Deaths", 8, Constraint::Length(8), value_deaths),
+        TableColumn::MaxHit => right_column("MaxHit", 9, Constraint::Length(9), value_max_hit),
+        TableColumn::Overheal => {
+            right_column("Overheal%", 10, Constraint::Length(10), value_overheal)
+        }
+        TableColumn::OverhealAmount if abbreviate => right_column(
+            "Overheal",
+            OVERHEAL_COLUMN_WIDTH,
+            Constraint::Length(OVERHEAL_COLUMN_WIDTH as u16),
+            value_overheal_amount_short,
+        ),
+        TableColumn::OverhealAmount => right_column(
+            "Overheal",
+            OVERHEAL_COLUMN_WIDTH,
+            Constraint::Length(OVERHEAL_COLUMN_WIDTH as u16),
+            value_overheal_amount,
+        ),
+        TableColumn::Taken if abbreviate => right_column(
+            "Taken",
+            12,
+            Constraint::Length(12),
+            value_damage_taken_short,
+        ),
+        TableColumn::Taken => right_column("Taken", 12, Constraint::Length(12), value_damage_taken),
+        TableColumn::Damage if abbreviate => {
+            right_column("Damage", TOTAL_COLUMN_WIDTH, total, value_damage_short)
+        }
+        TableColumn::Damage => right_column("Damage", TOTAL_COLUMN_WIDTH, total, value_damage),
+        TableColumn::Healed if abbreviate => {
+            right_column("Healed", TOTAL_COLUMN_WIDTH, total, value_healed_short)
+        }
+        TableColumn::Healed => right_column("Healed", TOTAL_COLUMN_WIDTH, total, value_healed),
+        TableColumn::RollingDps if abbreviate => right_column(
+            "rDPS",
+            ROLLING_DPS_COLUMN_WIDTH,
+            Constraint::Length(ROLLING_DPS_COLUMN_WIDTH as u16),
+            value_rolling_dps_short,
+        ),
+        TableColumn::RollingDps => right_column(
+            "rDPS",
+            ROLLING_DPS_COLUMN_WIDTH,
+            Constraint::Length(ROLLING_DPS_COLUMN_WIDTH as u16),
+            value_rolling_dps,
+        ),
+    }
+}
+
+/// Builds the table from a configured column list, dropping columns from the end of the list
+/// until the rest fit in `width` (the first column always stays).
+fn configured_layout(columns: &[TableColumn], width: usize, abbreviate: bool) -> LayoutSpec {
+    let column_width = |column: &TableColumn| match configured_column(*column, abbreviate).width {
+        Constraint::Length(len) => len as usize,
+        _ => CONFIGURED_NAME_MIN_WIDTH,
+    };
+    let mut keep = columns.len();
+    while keep > 1 {
+        let needed: usize = columns[..keep].iter().map(column_width).sum::<usize>() + keep - 1;
+        if needed <= width {
+            break;
+        }
+        keep -= 1;
+    }
+    LayoutSpec::new(
+        columns[..keep]
+            .iter()
+            .map(|column| configured_column(*column, abbreviate))
+            .collect(),
+    )
+}
+
+/// The built-in layout for the terminal width, plus the optional Damage/Healed, rDPS, Q%, CDH%,
+/// Overheal and Active columns when they fit.
+fn builtin_layout(s: &AppSnapshot, mode: ViewMode, width: usize) -> LayoutSpec {
+    let variant = TableVariant::from_width(width);
+    let mut layout = layout_for(mode, variant, s.settings.abbreviate_numbers);
+    let mut spare = width;
+    if s.settings.show_totals
+        && matches!(
+            variant,
+            TableVariant::Wide | TableVariant::Full | TableVariant::NoDeaths
+        )
+        && variant.fits_extra_column(spare, TOTAL_COLUMN_WIDTH)
+    {
+        layout = layout.with_total_column(mode, s.settings.abbreviate_numbers);
+        spare -= TOTAL_COLUMN_WIDTH + 1;
     }
+    if s.settings.show_rolling_dps
+        && mode == ViewMode::Dps
+        && matches!(
+            variant,
+            TableVariant::Wide | TableVariant::Full | TableVariant::NoDeaths
+        )
+        && variant.fits_extra_column(spare, ROLLING_DPS_COLUMN_WIDTH)
+    {
+        layout = layout.with_rolling_dps_column(s.settings.abbreviate_numbers);
+        spare -= ROLLING_DPS_COLUMN_WIDTH + 1;
+    }
+    if s.settings.show_quality
+        && mode == ViewMode::Dps
+        && matches!(variant, TableVariant::Wide | TableVariant::Full)
+        && variant.fits_extra_column(spare, QUALITY_COLUMN_WIDTH)
+    {
+        layout = layout.with_quality_column();
+        spare -= QUALITY_COLUMN_WIDTH + 1;
+    }
+    if s.settings.show_cdh
+        && mode == ViewMode::Dps
+        && matches!(variant, TableVariant::Wide | TableVariant::Full)
+        && variant.fits_extra_column(spare, CDH_COLUMN_WIDTH)
+    {
+        layout = layout.with_cdh_column();
+        spare -= CDH_COLUMN_WIDTH + 1;
+    }
+    if s.settings.show_overheal
+        && mode == ViewMode::Heal
+        && matches!(variant, TableVariant::Wide | TableVariant::Full)
+        && variant.fits_extra_column(spare, OVERHEAL_COLUMN_WIDTH)
+    {
+        layout = layout.with_overheal_column(s.settings.abbreviate_numbers);
+        spare -= OVERHEAL_COLUMN_WIDTH + 1;
+    }
+    if s.settings.show_active
+        && matches!(variant, TableVariant::Wide | TableVariant::Full)
+        && variant.fits_extra_column(spare, ACTIVE_COLUMN_WIDTH)
+    {
+        layout = layout.with_active_column();
+    }
+    layout
 }
 
 // inline name underline removed; inline mode now uses background meters only
@@ -327,31 +1039,47 @@ fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
 
     let line_top = if let Some(enc) = &s.encounter {
         // Top header now excludes Encounter/Zone; show compact metrics (DPS or HEAL mode)
-        let (metric_label, metric_val, total_label, total_val) = match s.mode {
+        let (metric_label, metric_val, total_label, total_raw) = match s.mode {
             ViewMode::Dps => ("ENCDPS", enc.encdps.as_str(), "Damage", enc.damage.as_str()),
             ViewMode::Heal => ("ENCHPS", enc.enchps.as_str(), "Healed", enc.healed.as_str()),
+            ViewMode::Tank => (
+                "ENCHPS",
+                enc.enchps.as_str(),
+                "Taken",
+                enc.damage_taken.as_str(),
+            ),
+        };
+        let total_val = display_total(total_raw, s.settings.abbreviate_numbers);
+        let duration = match s.combat_elapsed {
+            Some(secs) => format_clock(secs),
+            None => enc.duration.clone(),
         };
         if w >= 56 {
-            Line::from(vec![
+            let mut spans = vec![
                 Span::styled("Dur:", header_style()),
-                Span::styled(format!(" {} ", enc.duration), value_style()),
+                Span::styled(format!(" {} ", duration), value_style()),
                 Span::raw("| "),
                 Span::styled(format!("{}:", metric_label), header_style()),
                 Span::styled(format!(" {} ", metric_val), value_style()),
                 Span::raw("| "),
                 Span::styled(format!("{}:", total_label), header_style()),
                 Span::styled(format!(" {}", total_val), value_style()),
-            ])
+            ];
+            if w >= 72 && !s.rows.is_empty() {
+                spans.push(Span::raw(" | "));
+                spans.extend(role_badge(&s.rows));
+            }
+            Line::from(spans)
         } else if w >= 40 {
             Line::from(vec![
                 Span::styled("Dur:", header_style()),
-                Span::styled(format!(" {} ", enc.duration), value_style()),
+                Span::styled(format!(" {} ", duration), value_style()),
                 Span::styled(format!("{}:", metric_label), header_style()),
                 Span::styled(format!(" {}", metric_val), value_style()),
             ])
         } else if w >= 28 {
             Line::from(vec![
-                Span::styled(enc.duration.as_str(), value_style()),
+                Span::styled(duration, value_style()),
                 Span::raw("  "),
                 Span::styled(metric_val, value_style()),
             ])
@@ -401,36 +1129,116 @@ fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         ])
         .split(area);
 
+    // Third line: ENCDPS per role, only in DPS mode and when there is room for it
+    if w >= 72 && s.mode == ViewMode::Dps && !s.rows.is_empty() && head[2].height > 0 {
+        let roles = Paragraph::new(Line::from(role_totals_spans(s)))
+            .block(block.clone())
+            .alignment(Alignment::Left);
+        f.render_widget(roles, head[2]);
+    }
+
     // Swap: show Encounter/Zone on top, and Dur/ENCDPS/Damage below
     let widget_top = Paragraph::new(line_bottom)
         .block(block.clone())
-        .style(Style::default().fg(TEXT))
+        .style(Style::default().fg(theme::text()))
         .alignment(Alignment::Left);
     f.render_widget(widget_top, head[0]);
 
     let widget_bottom = Paragraph::new(line_top)
         .block(block)
-        .style(Style::default().fg(TEXT))
+        .style(Style::default().fg(theme::text()))
         .alignment(Alignment::Left);
     f.render_widget(widget_bottom, head[1]);
 }
 
-fn draw_table(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+/// Columns for one table at width `w`: the configured set, or the built-in layout for the mode.
+fn table_layout(s: &AppSnapshot, mode: ViewMode, w: usize) -> LayoutSpec {
+    let configured = s.table_columns.for_mode(mode);
+    let mut layout = if configured.is_empty() {
+        builtin_layout(s, mode, w)
+    } else {
+        // Leave room for the trend arrow and role glyph columns inserted below.
+        let reserved = 2 * (s.settings.show_trends as usize + s.settings.role_glyphs as usize);
+        configured_layout(
+            configured,
+            w.saturating_sub(reserved),
+            s.settings.abbreviate_numbers,
+        )
+    };
+    if s.settings.show_trends {
+        layout = layout.with_trend_column();
+    }
+    if s.settings.role_glyphs {
+        layout = layout.with_role_glyphs();
+    }
+    layout
+}
+
+/// First row to draw when `capacity` rows fit: `scroll`, stopped at the last full page.
+fn table_offset(rows: usize, capacity: usize, scroll: usize) -> usize {
+    scroll.min(rows.saturating_sub(capacity))
+}
+
+/// Returns how many rows fit.
+fn draw_table(f: &mut Frame, area: Rect, s: &AppSnapshot, mode: ViewMode) -> usize {
     f.render_widget(Clear, area);
-    let w = area.width as usize;
+    let full_area = area;
     let row_h = s.decoration.row_height();
-    let variant = TableVariant::from_width(w);
-    let layout = layout_for(s.mode, variant);
+    let mut layout = table_layout(s, mode, area.width as usize);
+
+    // The party-total line sits right under the last row, or pinned to the bottom when the rows
+    // overflow; the rows give up space for it rather than the other way round.
+    let total = s
+        .total_row
+        .as_ref()
+        .filter(|_| s.settings.show_total_row && !s.rows.is_empty())
+        .filter(|_| full_area.height > layout.header_height + row_h);
+    let total_h = if total.is_some() { row_h } else { 0 };
+
+    // Rows that don't fit scroll; the scrollbar then takes the rightmost column.
+    let capacity = (full_area
+        .height
+        .saturating_sub(layout.header_height + total_h)
+        / row_h) as usize;
+    let scrollable = capacity > 0 && s.rows.len() > capacity && full_area.width > 1;
+    let offset = if scrollable {
+        table_offset(s.rows.len(), capacity, s.table_scroll)
+    } else {
+        0
+    };
+    let rows = &s.rows[offset..];
+    let full_area = if scrollable {
+        layout = table_layout(s, mode, full_area.width as usize - 1);
+        Rect {
+            width: full_area.width - 1,
+            ..full_area
+        }
+    } else {
+        full_area
+    };
+
+    let area = match total {
+        Some(_) => {
+            let rows_height = layout.header_height as usize + rows.len() * row_h as usize;
+            Rect {
+                height: rows_height.min((full_area.height - row_h) as usize) as u16,
+                ..full_area
+            }
+        }
+        None => full_area,
+    };
 
     if matches!(s.decoration, Decoration::Background) {
-        draw_bg_meters(f, area, s, layout.header_height);
+        draw_bg_meters(f, area, s, mode, layout.header_height, offset);
     }
 
     let table = Table::new(
-        s.rows.iter().map(|r| layout.data_row(r, row_h)),
+        rows.iter().map(|r| layout.data_row(r, row_h)),
         layout.widths(),
     )
-    .header(layout.header_row())
+    // The other half of the split view lists the rows in the current mode's order, so only
+    // the current mode's table marks a sort column.
+    .header(layout.header_row(mode, (mode == s.mode).then_some(s.sort_key)))
     .block(Block::default().borders(Borders::NONE))
     .column_spacing(layout.column_spacing);
 
@@ -451,34 +1259,180 @@ fn draw_table(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             };
             let sep = Paragraph::new(Line::from(Span::styled(
                 line,
-                Style::default().fg(ratatui::style::Color::Rgb(170, 170, 180)),
+                Style::default().fg(theme::separator()),
             )));
             f.render_widget(sep, rect);
         }
     }
 
     if matches!(s.decoration, Decoration::Underline) {
-        draw_underlines(f, area, s, layout.header_height);
+        draw_underlines(f, area, s, mode, layout.header_height, offset);
+    }
+
+    if s.awaiting_combatants() {
+        draw_awaiting_combatants(f, area, layout.header_height);
+    }
+
+    if let Some(total) = total {
+        let rect = Rect {
+            y: area.bottom(),
+            height: row_h,
+            ..full_area
+        };
+        let table = Table::new([layout.total_row(total, row_h)], layout.widths())
+            .column_spacing(layout.column_spacing);
+        f.render_widget(table, rect);
     }
+
+    if scrollable {
+        let track = Rect {
+            x: full_area.right(),
+            y: full_area.y + layout.header_height,
+            width: 1,
+            height: (capacity as u16) * row_h,
+        };
+        // One scroll position per possible first row, so the thumb meets the bottom at the end.
+        let mut state = ScrollbarState::new(s.rows.len() - capacity + 1)
+            .position(offset)
+            .viewport_content_length(capacity);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None);
+        f.render_stateful_widget(scrollbar, track, &mut state);
+    }
+    capacity
 }
 
-fn draw_status(f: &mut Frame, area: Rect, s: &AppSnapshot) {
-    let (status_text, status_style) = if !s.connected {
+/// The live table, or DPS and HEAL tables side by side in the split layout when there's room.
+/// Returns how many rows fit.
+fn draw_tables(f: &mut Frame, area: Rect, s: &AppSnapshot) -> usize {
+    let split = s.settings.view_layout == ViewLayout::Split
+        && s.mode != ViewMode::Tank
+        && area.width >= SPLIT_MIN_WIDTH;
+    if !split {
+        return draw_table(f, area, s, s.mode);
+    }
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(50),
+            Constraint::Length(1),
+            Constraint::Percentage(50),
+        ])
+        .split(area);
+    let dps_page = draw_table(f, halves[0], s, ViewMode::Dps);
+    let divider = Paragraph::new(vec![
+        Line::from(Span::styled(
+            "│",
+            Style::default().fg(theme::separator()),
+        ));
+        halves[1].height as usize
+    ]);
+    f.render_widget(divider, halves[1]);
+    dps_page.min(draw_table(f, halves[2], s, ViewMode::Heal))
+}
+
+/// Placeholder below the header so an empty table doesn't look like a broken one.
+fn draw_awaiting_combatants(f: &mut Frame, area: Rect, header_height: u16) {
+    let body_y = area.y.saturating_add(header_height + 1);
+    if body_y >= area.y + area.height {
+        return;
+    }
+    let rect = Rect {
+        x: area.x,
+        y: body_y,
+        width: area.width,
+        height: 1,
+    };
+    let message = Paragraph::new(Line::from(Span::styled(
+        "Connected — waiting for combatants",
+        Style::default()
+            .fg(theme::text())
+            .add_modifier(Modifier::DIM),
+    )))
+    .alignment(Alignment::Center);
+    f.render_widget(message, rect);
+}
+
+fn connection_status(s: &AppSnapshot) -> (Cow<'static, str>, Style) {
+    if let Some(replay) = &s.replay {
         (
-            Cow::Borrowed("Disconnected"),
-            Style::default().fg(crate::theme::STATUS_DISCONNECTED),
+            Cow::Owned(format!("Replay {}x · {}", replay.speed, replay.title)),
+            Style::default().fg(theme::status_idle()),
         )
+    } else if !s.connected {
+        let text = match s.reconnect {
+            Some(reconnect) => Cow::Owned(format!(
+                "Reconnecting (attempt {}, retry in {}s)",
+                reconnect.attempt,
+                reconnect.retry_in_ms.div_ceil(1000)
+            )),
+            None => Cow::Borrowed("Disconnected"),
+        };
+        (text, Style::default().fg(theme::status_disconnected()))
     } else if s.is_idle {
         (
             Cow::Borrowed("Connected (idle)"),
-            Style::default().fg(crate::theme::STATUS_IDLE),
+            Style::default().fg(theme::status_idle()),
         )
     } else {
         (Cow::Borrowed("Connected"), value_style())
-    };
+    }
+}
+
+fn draw_status(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let (status_text, status_style) = connection_status(s);
     let status_span = Span::styled(status_text.clone(), status_style);
+    let stale_span = match s.stale_seconds() {
+        Some(secs) => Span::styled(
+            format!(" stale ({secs}s ago)"),
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
+        ),
+        None => Span::raw(""),
+    };
+    let rate_span = match s.update_rate.filter(|_| s.stale_seconds().is_none()) {
+        Some(rate) => Span::styled(
+            format!(" ~{rate:.1} updates/s"),
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
+        ),
+        None => Span::raw(""),
+    };
+    let history_span = if s.history_notice.is_some() {
+        Span::styled(
+            " history not saved",
+            Style::default().fg(theme::status_idle()),
+        )
+    } else {
+        Span::raw("")
+    };
+    let config_span = match &s.config_notice {
+        Some(notice) => Span::styled(
+            format!(" {notice}"),
+            Style::default().fg(theme::status_disconnected()),
+        ),
+        None => Span::raw(""),
+    };
+    let message_span = match &s.status_message {
+        Some(message) => Span::styled(format!(" · {message}"), value_style()),
+        None => Span::raw(""),
+    };
+    let paused_span = if s.paused {
+        Span::styled(
+            "PAUSED ",
+            Style::default()
+                .fg(theme::status_idle())
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::raw("")
+    };
     let decor_label = s.decoration.short_label().trim_start_matches("decor:");
     let mode_label = s.mode.short_label().trim_start_matches("mode:");
+    let sort_label = format!("sort:{}", s.sort_key.label());
     let history_style = if s.history.visible {
         header_style().add_modifier(Modifier::BOLD)
     } else {
@@ -504,9 +1458,21 @@ fn draw_status(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             Span::styled(" d ", title_style()),
             Span::styled(decor_label, header_style()),
             Span::raw(" | "),
+            Span::styled(" o ", title_style()),
+            Span::styled(sort_label, header_style()),
+            Span::raw(" | "),
+            Span::styled(" ? ", title_style()),
+            Span::styled("keys", header_style()),
+            Span::raw(" | "),
             Span::styled("status", header_style()),
             Span::raw(" "),
+            paused_span.clone(),
             status_span.clone(),
+            rate_span,
+            stale_span.clone(),
+            history_span.clone(),
+            config_span.clone(),
+            message_span.clone(),
         ])
     } else if w >= 60 {
         Line::from(vec![
@@ -525,7 +1491,12 @@ fn draw_status(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             Span::styled(" d ", title_style()),
             Span::styled(decor_label, header_style()),
             Span::raw(" | "),
+            paused_span.clone(),
             status_span.clone(),
+            stale_span.clone(),
+            history_span,
+            config_span,
+            message_span,
         ])
     } else if w >= 36 {
         Line::from(vec![
@@ -534,39 +1505,205 @@ fn draw_status(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             Span::styled(" s ", title_style()),
             Span::styled(" h ", title_style()),
             Span::styled(" d ", title_style()),
+            paused_span.clone(),
             status_span,
+            stale_span,
         ])
     } else {
-        Line::from(vec![Span::styled("qmshd", title_style())])
+        Line::from(vec![paused_span, Span::styled("qmshd", title_style())])
     };
 
+    let used = line.width();
     let widget = Paragraph::new(line)
         .block(Block::default().borders(Borders::NONE))
         .alignment(Alignment::Left);
     f.render_widget(widget, area);
+
+    // Wall clock and session uptime on the right, only when they fit beside the full footer
+    if w >= 90 {
+        let clock = format!(
+            "{} · up {} ",
+            Local::now().format("%H:%M:%S"),
+            format_clock(s.session_uptime_secs)
+        );
+        if used + clock.chars().count() + 2 <= w {
+            let widget = Paragraph::new(Line::from(Span::styled(
+                clock,
+                Style::default()
+                    .fg(theme::text())
+                    .add_modifier(Modifier::DIM),
+            )))
+            .alignment(Alignment::Right);
+            f.render_widget(widget, area);
+        }
+    }
+}
+
+/// Content lines of the settings pane, top to bottom; `None` marks titles, notes and spacers.
+/// Must stay in step with `draw_settings` so mouse clicks land on the right field; the tests
+/// render the pane and compare.
+const SETTINGS_ROWS: [Option<SettingsField>; 40] = [
+    None,
+    None,
+    Some(SettingsField::IdleTimeout),
+    None,
+    Some(SettingsField::IdleRotate),
+    Some(SettingsField::InputDelaysIdle),
+    Some(SettingsField::InputGrace),
+    Some(SettingsField::StaleThreshold),
+    None,
+    Some(SettingsField::DefaultDecoration),
+    Some(SettingsField::BarScale),
+    Some(SettingsField::BarMetric),
+    Some(SettingsField::DefaultMode),
+    Some(SettingsField::ViewLayout),
+    Some(SettingsField::DefaultSort),
+    Some(SettingsField::Theme),
+    None,
+    Some(SettingsField::AbbreviateNumbers),
+    Some(SettingsField::RoleGlyphs),
+    Some(SettingsField::ShowTrends),
+    Some(SettingsField::ShowTotals),
+    Some(SettingsField::ShowTotalRow),
+    Some(SettingsField::ShowQuality),
+    Some(SettingsField::ShowCdh),
+    Some(SettingsField::ShowOverheal),
+    Some(SettingsField::ShowRollingDps),
+    Some(SettingsField::ShowActive),
+    Some(SettingsField::LiveTimer),
+    Some(SettingsField::HidePets),
+    Some(SettingsField::PartyOnly),
+    Some(SettingsField::HideZeroRows),
+    Some(SettingsField::KeepSelfRow),
+    Some(SettingsField::JsonExportFrames),
+    Some(SettingsField::MinRecordSeconds),
+    Some(SettingsField::MinRecordDamage),
+    Some(SettingsField::RetentionDays),
+    Some(SettingsField::MaxFrames),
+    Some(SettingsField::StoreRawFrames),
+    None,
+    None,
+];
+
+/// Screen area of the settings modal for a terminal of size `screen`.
+pub fn settings_area(screen: Rect) -> Rect {
+    centered_rect(60, 80, screen)
+}
+
+/// First pane line shown in the modal `area`. When the pane is taller than the modal it scrolls
+/// to keep `cursor` near the middle.
+fn settings_scroll(area: Rect, cursor: SettingsField) -> usize {
+    let visible = area.height.saturating_sub(2) as usize;
+    let max = SETTINGS_ROWS.len().saturating_sub(visible);
+    let row = SETTINGS_ROWS
+        .iter()
+        .position(|field| *field == Some(cursor))
+        .unwrap_or(0);
+    row.saturating_sub(visible / 2).min(max)
+}
+
+/// The settings field drawn at terminal row `row` while `cursor` is selected, if any.
+pub fn settings_field_at(screen: Rect, row: u16, cursor: SettingsField) -> Option<SettingsField> {
+    let area = settings_area(screen);
+    let first_line = area.y + 1;
+    if row < first_line || row >= area.bottom().saturating_sub(1) {
+        return None;
+    }
+    SETTINGS_ROWS
+        .get((row - first_line) as usize + settings_scroll(area, cursor))
+        .copied()
+        .flatten()
 }
 
 fn draw_settings(f: &mut Frame, s: &AppSnapshot) {
-    let area = centered_rect(60, 50, f.size());
+    let area = settings_area(f.size());
     f.render_widget(Clear, area);
 
     let idle_selected = matches!(s.settings_cursor, SettingsField::IdleTimeout);
+    let rotate_selected = matches!(s.settings_cursor, SettingsField::IdleRotate);
+    let input_idle_selected = matches!(s.settings_cursor, SettingsField::InputDelaysIdle);
+    let input_grace_selected = matches!(s.settings_cursor, SettingsField::InputGrace);
+    let stale_selected = matches!(s.settings_cursor, SettingsField::StaleThreshold);
     let decor_selected = matches!(s.settings_cursor, SettingsField::DefaultDecoration);
+    let bar_scale_selected = matches!(s.settings_cursor, SettingsField::BarScale);
+    let bar_metric_selected = matches!(s.settings_cursor, SettingsField::BarMetric);
     let mode_selected = matches!(s.settings_cursor, SettingsField::DefaultMode);
+    let layout_selected = matches!(s.settings_cursor, SettingsField::ViewLayout);
+    let sort_selected = matches!(s.settings_cursor, SettingsField::DefaultSort);
+    let theme_selected = matches!(s.settings_cursor, SettingsField::Theme);
+    let abbreviate_selected = matches!(s.settings_cursor, SettingsField::AbbreviateNumbers);
+    let glyphs_selected = matches!(s.settings_cursor, SettingsField::RoleGlyphs);
+    let trends_selected = matches!(s.settings_cursor, SettingsField::ShowTrends);
+    let totals_selected = matches!(s.settings_cursor, SettingsField::ShowTotals);
+    let total_row_selected = matches!(s.settings_cursor, SettingsField::ShowTotalRow);
+    let quality_selected = matches!(s.settings_cursor, SettingsField::ShowQuality);
+    let cdh_selected = matches!(s.settings_cursor, SettingsField::ShowCdh);
+    let overheal_selected = matches!(s.settings_cursor, SettingsField::ShowOverheal);
+    let rolling_selected = matches!(s.settings_cursor, SettingsField::ShowRollingDps);
+    let active_selected = matches!(s.settings_cursor, SettingsField::ShowActive);
+    let timer_selected = matches!(s.settings_cursor, SettingsField::LiveTimer);
+    let pets_selected = matches!(s.settings_cursor, SettingsField::HidePets);
+    let party_selected = matches!(s.settings_cursor, SettingsField::PartyOnly);
+    let zero_rows_selected = matches!(s.settings_cursor, SettingsField::HideZeroRows);
+    let keep_self_selected = matches!(s.settings_cursor, SettingsField::KeepSelfRow);
+    let frames_selected = matches!(s.settings_cursor, SettingsField::JsonExportFrames);
+    let min_seconds_selected = matches!(s.settings_cursor, SettingsField::MinRecordSeconds);
+    let min_damage_selected = matches!(s.settings_cursor, SettingsField::MinRecordDamage);
+    let retention_selected = matches!(s.settings_cursor, SettingsField::RetentionDays);
+    let max_frames_selected = matches!(s.settings_cursor, SettingsField::MaxFrames);
+    let raw_frames_selected = matches!(s.settings_cursor, SettingsField::StoreRawFrames);
 
     let mut lines = Vec::new();
     lines.push(Line::from(vec![Span::styled("Settings", title_style())]));
     lines.push(Line::default());
 
-    lines.push(setting_line(
-        idle_selected,
-        "Idle timeout",
-        format!("{}s", s.settings.idle_seconds),
-    ));
+    let (idle_value, idle_note) = match s.settings_input.as_deref() {
+        Some(input) => (
+            format!("{input}_ s"),
+            "Type seconds · Enter saves · Esc cancels.",
+        ),
+        None => (
+            format!("{}s", s.settings.idle_seconds),
+            "Set to 0 to disable idle mode. Enter types a value, PgUp/PgDn ±10s.",
+        ),
+    };
+    lines.push(setting_line(idle_selected, "Idle timeout", idle_value));
     lines.push(Line::from(vec![
         Span::raw("   "),
-        Span::styled("Set to 0 to disable idle mode.", header_style()),
+        Span::styled(idle_note, header_style()),
     ]));
+    lines.push(setting_line(
+        rotate_selected,
+        "Idle scene rotation",
+        if s.settings.idle_rotate_seconds == 0 {
+            "off".to_string()
+        } else {
+            format!("every {}s", s.settings.idle_rotate_seconds)
+        },
+    ));
+    lines.push(setting_line(
+        input_idle_selected,
+        "Input delays idle",
+        if s.settings.input_delays_idle {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        input_grace_selected,
+        "Input grace",
+        format!("{}s", s.settings.input_grace_seconds),
+    ));
+    lines.push(setting_line(
+        stale_selected,
+        "Stale warning after",
+        if s.settings.stale_seconds == 0 {
+            "off".to_string()
+        } else {
+            format!("{}s", s.settings.stale_seconds)
+        },
+    ));
     lines.push(Line::default());
 
     lines.push(setting_line(
@@ -574,26 +1711,257 @@ fn draw_settings(f: &mut Frame, s: &AppSnapshot) {
         "Default decoration",
         s.settings.default_decoration.label().to_string(),
     ));
+    lines.push(setting_line(
+        bar_scale_selected,
+        "Bar length",
+        s.settings.bar_scale.label().to_string(),
+    ));
+    lines.push(setting_line(
+        bar_metric_selected,
+        "Bar metric",
+        s.settings.bar_metric.label().to_string(),
+    ));
     lines.push(setting_line(
         mode_selected,
         "Default mode",
         s.settings.default_mode.label().to_string(),
     ));
+    lines.push(setting_line(
+        layout_selected,
+        "Table layout",
+        s.settings.view_layout.label().to_string(),
+    ));
+    lines.push(setting_line(
+        sort_selected,
+        "Default sort",
+        s.settings.default_sort.label().to_string(),
+    ));
+    lines.push(setting_line(
+        theme_selected,
+        "Theme",
+        s.settings.theme.label().to_string(),
+    ));
+    lines.push(Line::from(vec![
+        Span::raw("   "),
+        Span::styled(
+            format!(
+                "Terminal colors: {} (override with --color).",
+                s.color_depth.label()
+            ),
+            header_style(),
+        ),
+    ]));
+    lines.push(setting_line(
+        abbreviate_selected,
+        "Abbreviate totals",
+        if s.settings.abbreviate_numbers {
+            "on (12.3M)".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        glyphs_selected,
+        "Role glyphs",
+        if s.settings.role_glyphs {
+            format!(
+                "on ({})",
+                [Role::Tank, Role::Healer, Role::Dps]
+                    .map(theme::role_glyph)
+                    .join(" ")
+            )
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        trends_selected,
+        "DPS trend arrows",
+        if s.settings.show_trends {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        totals_selected,
+        "Damage/Healed column",
+        if s.settings.show_totals {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        total_row_selected,
+        "Party total row",
+        if s.settings.show_total_row {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        quality_selected,
+        "Crit/DH Q% column",
+        if s.settings.show_quality {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        cdh_selected,
+        "CDH% column",
+        if s.settings.show_cdh {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        overheal_selected,
+        "Overheal column",
+        if s.settings.show_overheal {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        rolling_selected,
+        "Rolling DPS column",
+        if s.settings.show_rolling_dps {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        active_selected,
+        "Active time column",
+        if s.settings.show_active {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        timer_selected,
+        "Live combat timer",
+        if s.settings.live_timer {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        pets_selected,
+        "Hide pets/Limit Break",
+        if s.settings.hide_pets {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        party_selected,
+        "Party only",
+        if s.settings.party_only {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        zero_rows_selected,
+        "Hide zero rows",
+        if s.settings.hide_zero_rows {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        keep_self_selected,
+        "Keep own row",
+        if s.settings.keep_self_row {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        frames_selected,
+        "JSON export frames",
+        if s.settings.json_export_frames {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
+    lines.push(setting_line(
+        min_seconds_selected,
+        "Min recorded duration",
+        if s.settings.min_record_seconds == 0 {
+            "any length".to_string()
+        } else {
+            format!("{}s", s.settings.min_record_seconds)
+        },
+    ));
+    lines.push(setting_line(
+        min_damage_selected,
+        "Min recorded damage",
+        if s.settings.min_record_damage == 0 {
+            "any".to_string()
+        } else {
+            format!("{}k", s.settings.min_record_damage / 1_000)
+        },
+    ));
+    lines.push(setting_line(
+        retention_selected,
+        "Keep history for",
+        if s.settings.retention_days == 0 {
+            "forever".to_string()
+        } else {
+            format!("{} days", s.settings.retention_days)
+        },
+    ));
+    lines.push(setting_line(
+        max_frames_selected,
+        "Max frames per encounter",
+        if s.settings.max_frames == 0 {
+            "unlimited".to_string()
+        } else {
+            s.settings.max_frames.to_string()
+        },
+    ));
+    lines.push(setting_line(
+        raw_frames_selected,
+        "Raw JSON per frame",
+        if s.settings.store_raw_frames {
+            "on".to_string()
+        } else {
+            "off".to_string()
+        },
+    ));
     lines.push(Line::default());
 
     lines.push(Line::from(vec![Span::styled(
-        "Use ↑/↓ to select, ←/→ to adjust. Press 's' to close.",
+        "Use ↑/↓ or click to select, ←/→ or the wheel to adjust. Press 's' to close.",
         header_style(),
     )]));
+    debug_assert_eq!(lines.len(), SETTINGS_ROWS.len());
 
     let block = Block::default().title("Settings").borders(Borders::ALL);
     let widget = Paragraph::new(lines)
         .block(block)
-        .alignment(Alignment::Left);
+        .alignment(Alignment::Left)
+        .scroll((settings_scroll(area, s.settings_cursor) as u16, 0));
     f.render_widget(widget, area);
 }
 
-fn setting_line(selected: bool, label: &str, value: String) -> Line {
+fn setting_line(selected: bool, label: &str, value: String) -> Line<'_> {
     let marker = if selected { "▶" } else { " " };
     let label_style = if selected {
         title_style()
@@ -627,23 +1995,28 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     vertical[1]
 }
 
-fn draw_bg_meters(f: &mut Frame, area: Rect, s: &AppSnapshot, header_lines: u16) {
+/// Bars behind the rows from `offset` on; ratios still come from the whole table.
+fn draw_bg_meters(
+    f: &mut Frame,
+    area: Rect,
+    s: &AppSnapshot,
+    mode: ViewMode,
+    header_lines: u16,
+    offset: usize,
+) {
     if area.height <= header_lines {
         return;
     }
-    // Determine max ENCDPS to scale bars
-    let max_dps = s
-        .rows
-        .iter()
-        .map(|r| r.encdps)
-        .fold(0.0_f64, |a, b| a.max(b));
-    if max_dps <= 0.0 {
+    // Bar lengths follow the bar metric (by default ENCDPS, or damage taken in TANK mode),
+    // relative to the top row or as a share of the party total
+    let ratios = mode.bar_ratios(&s.rows, s.settings.bar_scale, s.settings.bar_metric);
+    if ratios.iter().all(|ratio| *ratio <= 0.0) {
         return;
     }
     let width = area.width as usize;
     let visible_rows = (area.height.saturating_sub(header_lines)) as usize;
-    for (i, r) in s.rows.iter().take(visible_rows).enumerate() {
-        let ratio = (r.encdps / max_dps).clamp(0.0, 1.0);
+    let rows = s.rows.iter().zip(ratios).skip(offset);
+    for (i, (r, ratio)) in rows.take(visible_rows).enumerate() {
         let filled = (ratio * width as f64).round() as usize;
         let y = area.y + header_lines + i as u16; // row text line
         if y >= area.y + area.height {
@@ -671,16 +2044,19 @@ fn draw_bg_meters(f: &mut Frame, area: Rect, s: &AppSnapshot, header_lines: u16)
 }
 
 #[allow(dead_code)]
-fn draw_underlines(f: &mut Frame, area: Rect, s: &AppSnapshot, header_lines: u16) {
+fn draw_underlines(
+    f: &mut Frame,
+    area: Rect,
+    s: &AppSnapshot,
+    mode: ViewMode,
+    header_lines: u16,
+    offset: usize,
+) {
     if area.height <= header_lines {
         return;
     }
-    let max_dps = s
-        .rows
-        .iter()
-        .map(|r| r.encdps)
-        .fold(0.0_f64, |a, b| if b > a { b } else { a });
-    if max_dps <= 0.0 {
+    let ratios = mode.bar_ratios(&s.rows, s.settings.bar_scale, s.settings.bar_metric);
+    if ratios.iter().all(|ratio| *ratio <= 0.0) {
         return;
     }
 
@@ -689,8 +2065,8 @@ fn draw_underlines(f: &mut Frame, area: Rect, s: &AppSnapshot, header_lines: u16
     let visible_rows = (usable_height / 2) as usize;
     let width = area.width as usize;
 
-    for (i, r) in s.rows.iter().take(visible_rows).enumerate() {
-        let ratio = (r.encdps / max_dps).clamp(0.0, 1.0);
+    let rows = s.rows.iter().zip(ratios).skip(offset);
+    for (i, (r, ratio)) in rows.take(visible_rows).enumerate() {
         let filled = (ratio * width as f64).round() as usize;
         let y = area.y + header_lines + (i as u16) * 2 + 1; // line directly under row
         if y >= area.y + area.height {
@@ -719,3 +2095,67 @@ fn draw_underlines(f: &mut Frame, area: Rect, s: &AppSnapshot, header_lines: u16
         f.render_widget(para, bar_rect);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::AppState;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    /// Draws the settings pane on a `width`×`height` terminal and returns the row of the cursor
+    /// marker, if it is on screen.
+    fn settings_marker_row(width: u16, height: u16, cursor: SettingsField) -> Option<u16> {
+        let mut state = AppState::default();
+        state.show_settings = true;
+        state.settings_cursor = cursor;
+        let snapshot = state.clone_snapshot();
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| draw_settings(f, &snapshot)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height).find(|&y| (0..width).any(|x| buffer.get(x, y).symbol() == "▶"))
+    }
+
+    #[test]
+    fn settings_rows_match_the_drawn_pane() {
+        let screen = Rect::new(0, 0, 100, 60);
+        let area = settings_area(screen);
+        assert!(
+            area.height as usize - 2 >= SETTINGS_ROWS.len(),
+            "pane must fit unscrolled"
+        );
+        let first_line = area.y + 1;
+        for (idx, field) in SETTINGS_ROWS.iter().enumerate() {
+            if let Some(field) = *field {
+                assert_eq!(
+                    settings_marker_row(screen.width, screen.height, field),
+                    Some(first_line + idx as u16),
+                    "{field:?}"
+                );
+            }
+        }
+
+        let snapshot = AppState::default().clone_snapshot();
+        let mut terminal = Terminal::new(TestBackend::new(screen.width, screen.height)).unwrap();
+        terminal.draw(|f| draw_settings(f, &snapshot)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (area.x + 1..area.right() - 1)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect()
+        };
+        // The closing hint is the last entry, with nothing drawn below it.
+        let last = first_line + SETTINGS_ROWS.len() as u16 - 1;
+        assert!(line(last).starts_with("Use ↑/↓"), "{:?}", line(last));
+        assert!(line(last + 1).trim().is_empty());
+    }
+
+    #[test]
+    fn settings_cursor_stays_visible_and_clickable_on_small_terminals() {
+        let screen = Rect::new(0, 0, 80, 24);
+        for field in SETTINGS_ROWS.iter().flatten().copied() {
+            let row = settings_marker_row(screen.width, screen.height, field)
+                .unwrap_or_else(|| panic!("{field:?} is not drawn at 80x24"));
+            assert_eq!(settings_field_at(screen, row, field), Some(field));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::model::{Confirm, PendingAction, CLEAR_HISTORY_CONFIRMATION};
+use crate::theme::{self, header_style, title_style};
+
+const CONFIRM_WIDTH: u16 = 60;
+const CONFIRM_HEIGHT: u16 = 6;
+
+/// Centered yes/no modal for a pending destructive action, drawn over whatever is on screen.
+pub fn draw_confirm(f: &mut Frame, confirm: &Confirm) {
+    let area = confirm_area(f.size());
+    f.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            confirm.message.clone(),
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::default(),
+    ];
+    match &confirm.typed {
+        Some(typed) => {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("Type \"{CLEAR_HISTORY_CONFIRMATION}\": "),
+                    header_style(),
+                ),
+                Span::styled(format!("{typed}_"), title_style()),
+            ]));
+            let mut hints = vec![
+                Span::styled("Enter", title_style()),
+                Span::styled(" confirm · ", header_style()),
+            ];
+            if matches!(confirm.action, PendingAction::ClearAll { .. }) {
+                hints.push(Span::styled("Tab", title_style()));
+                hints.push(Span::styled(" keep pinned · ", header_style()));
+            }
+            hints.push(Span::styled("Esc", title_style()));
+            hints.push(Span::styled(" cancel", header_style()));
+            lines.push(Line::from(hints));
+        }
+        None => lines.push(Line::from(vec![
+            Span::styled("y", title_style()),
+            Span::styled("/", header_style()),
+            Span::styled("Enter", title_style()),
+            Span::styled(" confirm · ", header_style()),
+            Span::styled("n", title_style()),
+            Span::styled("/", header_style()),
+            Span::styled("Esc", title_style()),
+            Span::styled(" cancel", header_style()),
+        ])),
+    }
+    let block = Block::default()
+        .title("Confirm")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::status_disconnected()));
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(widget, area);
+}
+
+fn confirm_area(screen: Rect) -> Rect {
+    let width = CONFIRM_WIDTH.min(screen.width);
+    let height = CONFIRM_HEIGHT.min(screen.height);
+    Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    }
+}
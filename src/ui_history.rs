@@ -1,13 +1,19 @@
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
-    Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table,
+    Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Scrollbar,
+    ScrollbarOrientation, ScrollbarState, Sparkline, Table,
 };
 use ratatui::Frame;
 
-use crate::model::{AppSnapshot, HistoryPanelLevel};
-use crate::theme::{header_style, job_color, title_style, value_style, TEXT};
+use crate::history::{
+    millis_to_local, note_tags, parse_duration_secs, EncounterRecord, HistoryEncounterItem,
+};
+use crate::model::{anonymize_rows, AppSnapshot, CombatantRow, HistoryPanelLevel};
+use crate::parse::mark_self;
+use crate::theme::{self, header_style, job_color, title_style, value_style};
+use crate::util::{abbreviate_number, display_total, format_clock};
 
 pub fn draw_history(f: &mut Frame, s: &AppSnapshot) {
     let area = f.size();
@@ -24,16 +30,54 @@ pub fn draw_history(f: &mut Frame, s: &AppSnapshot) {
 }
 
 fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
-    let subtitle = if s.history.loading {
+    let note_prompt = s.history.note_draft.as_ref().map(|draft| {
+        format!(
+            "Note: {}_ · Enter saves · Esc cancels · Ctrl-U clears · #tag for tags",
+            draft.text
+        )
+    });
+    let search_prompt = s
+        .history
+        .note_search
+        .as_ref()
+        .filter(|search| search.editing && s.history.level == HistoryPanelLevel::NoteSearch)
+        .map(|search| {
+            format!(
+                "Search notes: {}_ · Enter searches · Esc cancels · #tag matches a tag",
+                search.query
+            )
+        });
+    let subtitle = if let Some(prompt) = note_prompt.as_deref() {
+        prompt
+    } else if let Some(prompt) = search_prompt.as_deref() {
+        prompt
+    } else if s.history.loading {
         "Loading history…"
     } else if let Some(err) = &s.history.error {
         err.as_str()
+    } else if let Some(message) = &s.status_message {
+        message.as_str()
+    } else if let Some(notice) = &s.history_notice {
+        notice.as_str()
     } else {
         match s.history.level {
-            HistoryPanelLevel::Dates => "Enter/Click ▸ view encounters · ↑/↓ scroll · q/Esc quits",
-            HistoryPanelLevel::Encounters => "← dates · ↑/↓ scroll · Enter view details",
+            HistoryPanelLevel::Dates if s.history.filter.is_some() => {
+                "Type to filter · Backspace edits · Esc clears filter · Tab searches notes · Enter view encounters"
+            }
+            HistoryPanelLevel::Dates => {
+                "Enter/Click ▸ view encounters · ↑/↓ scroll · a day summary · t job stats · L recent · / filter · z zone · P prune · X clear all · q/Esc quits"
+            }
+            HistoryPanelLevel::Encounters => {
+                "← dates · ↑/↓ scroll · Enter view details · p pin · d delete · z zone · / search notes"
+            }
             HistoryPanelLevel::EncounterDetail => {
-                "← encounters · ↑/↓ switch encounter · h/Esc closes"
+                "← encounters · ↑/↓ switch encounter · n note · e CSV · E frames CSV · j JSON · y copy · w link · r replay · h/Esc closes"
+            }
+            HistoryPanelLevel::DaySummary => "← dates · ↑/↓ switch date · h/Esc closes",
+            HistoryPanelLevel::JobStats => "← dates · o sort · h/Esc closes",
+            HistoryPanelLevel::Recent => "← dates · ↑/↓ scroll · Enter view details · / search notes · h/Esc closes",
+            HistoryPanelLevel::NoteSearch => {
+                "← dates · ↑/↓ scroll · Enter view details · / edit search · h/Esc closes"
             }
         }
     };
@@ -44,7 +88,10 @@ fn draw_header(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD),
     )]);
-    let subtitle_line = Line::from(vec![Span::styled(subtitle, Style::default().fg(TEXT))]);
+    let subtitle_line = Line::from(vec![Span::styled(
+        subtitle,
+        Style::default().fg(theme::text()),
+    )]);
 
     let block = Paragraph::new(vec![title_line, subtitle_line])
         .alignment(ratatui::layout::Alignment::Left)
@@ -80,6 +127,10 @@ fn draw_body(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         HistoryPanelLevel::Dates => draw_dates(f, area, s),
         HistoryPanelLevel::Encounters => draw_encounters(f, area, s),
         HistoryPanelLevel::EncounterDetail => draw_encounter_detail(f, area, s),
+        HistoryPanelLevel::DaySummary => draw_day_summary(f, area, s),
+        HistoryPanelLevel::JobStats => draw_job_stats(f, area, s),
+        HistoryPanelLevel::Recent => draw_recent(f, area, s),
+        HistoryPanelLevel::NoteSearch => draw_note_search(f, area, s),
     }
 
     if is_loading {
@@ -96,18 +147,36 @@ fn draw_dates(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         return;
     }
 
-    let items: Vec<ListItem> = s
-        .history
-        .days
+    let visible = s.history.visible_day_indices();
+    let mut title = match &s.history.filter {
+        Some(query) => format!("Dates · /{query}▏"),
+        None => "Dates".to_string(),
+    };
+    if let Some(label) = zone_filter_label(s) {
+        title.push_str(&label);
+    }
+
+    if visible.is_empty() {
+        let block = Paragraph::new("No dates match the filter.")
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(block, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = visible
         .iter()
-        .map(|day| ListItem::new(day.label.clone()))
+        .map(|&idx| ListItem::new(s.history.days[idx].label.clone()))
         .collect();
 
+    let selected = visible
+        .iter()
+        .position(|&idx| idx == s.history.selected_day);
     let mut state = ListState::default();
-    state.select(Some(s.history.selected_day));
+    state.select(selected);
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Dates"))
+        .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(
             Style::default()
                 .fg(Color::Black)
@@ -116,6 +185,7 @@ fn draw_dates(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         );
 
     f.render_stateful_widget(list, area, &mut state);
+    render_list_scrollbar(f, area, visible.len(), selected.unwrap_or(0));
 }
 
 fn draw_encounters(f: &mut Frame, area: Rect, s: &AppSnapshot) {
@@ -147,15 +217,34 @@ fn draw_encounters(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         .encounters
         .iter()
         .map(|enc| {
-            let text = format!("{}  [{}]", enc.display_title, enc.time_label);
-            ListItem::new(text)
+            let pin = if enc.pinned { "★ " } else { "" };
+            let mut spans = vec![Span::raw(format!(
+                "{pin}{}  [{}]",
+                enc.display_title, enc.time_label
+            ))];
+            if enc.personal_best {
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(
+                    " PB ",
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            spans.extend(note_span(enc));
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let mut state = ListState::default();
     state.select(Some(s.history.selected_encounter));
 
-    let title = format!("Encounters · {}", day.label);
+    let title = format!(
+        "Encounters · {}{}",
+        day.label,
+        zone_filter_label(s).unwrap_or_default()
+    );
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(
@@ -166,18 +255,147 @@ fn draw_encounters(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         );
 
     f.render_stateful_widget(list, area, &mut state);
+    render_list_scrollbar(f, area, day.encounters.len(), s.history.selected_encounter);
 }
 
-fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
-    let Some(day) = s.history.current_day() else {
-        let block = Paragraph::new("No date selected.")
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(block, area);
+fn draw_recent(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let recent = match s.history.recent.as_deref() {
+        Some(recent) if !recent.is_empty() => recent,
+        Some(_) => {
+            let block = Paragraph::new("No encounters recorded yet.")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(block, area);
+            return;
+        }
+        None => {
+            let block = Paragraph::new("Loading recent encounters…")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(block, area);
+            return;
+        }
+    };
+
+    let items: Vec<ListItem> = recent.iter().map(dated_list_item).collect();
+
+    let mut state = ListState::default();
+    state.select(Some(s.history.selected_recent));
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Recent encounters · newest {}", recent.len())),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut state);
+    render_list_scrollbar(f, area, recent.len(), s.history.selected_recent);
+}
+
+/// Encounters whose note matches the query, newest first, across every date.
+fn draw_note_search(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let Some(search) = s.history.note_search.as_ref() else {
         return;
     };
+    let query = search.query.trim();
+    let results = match search.results.as_deref() {
+        Some(results) if !results.is_empty() => results,
+        Some(_) => {
+            let block = Paragraph::new(format!("No notes match \"{query}\"."))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).title("Note search"));
+            f.render_widget(block, area);
+            return;
+        }
+        None => {
+            let message = if search.editing {
+                "Type words or #tags from encounter notes, then Enter."
+            } else {
+                "Searching notes…"
+            };
+            let block = Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).title("Note search"));
+            f.render_widget(block, area);
+            return;
+        }
+    };
+
+    let items: Vec<ListItem> = results.iter().map(dated_list_item).collect();
 
-    let Some(encounter) = day.encounters.get(s.history.selected_encounter) else {
+    let mut state = ListState::default();
+    state.select(Some(search.selected));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Note search · \"{query}\" · {} matches",
+            results.len()
+        )))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_stateful_widget(list, area, &mut state);
+    render_list_scrollbar(f, area, results.len(), search.selected);
+}
+
+/// Entry for lists that span several dates: pin, title and full timestamp, then the note.
+fn dated_list_item(enc: &HistoryEncounterItem) -> ListItem<'static> {
+    let pin = if enc.pinned { "★ " } else { "" };
+    let mut spans = vec![Span::raw(format!(
+        "{pin}{}  [{}]",
+        enc.base_title, enc.timestamp_label
+    ))];
+    spans.extend(note_span(enc));
+    ListItem::new(Line::from(spans))
+}
+
+/// The encounter's note after its list entry, dimmed so titles stay easy to scan.
+fn note_span(enc: &HistoryEncounterItem) -> Option<Span<'static>> {
+    let note = enc.note.as_deref()?;
+    Some(Span::styled(
+        format!("  — {note}"),
+        Style::default().add_modifier(Modifier::DIM),
+    ))
+}
+
+/// Title suffix naming the active zone filter, e.g. ` · zone: Sastasha (12)`.
+fn zone_filter_label(s: &AppSnapshot) -> Option<String> {
+    s.history.zone_filter.as_ref().map(|filter| {
+        let zone = if filter.zone.is_empty() {
+            "unknown"
+        } else {
+            filter.zone.as_str()
+        };
+        format!(" · zone: {zone} ({})", filter.encounter_count)
+    })
+}
+
+/// Draws a vertical scrollbar on the right border of a bordered list, following the selection.
+/// Nothing is drawn when every item fits.
+fn render_list_scrollbar(f: &mut Frame, area: Rect, len: usize, selected: usize) {
+    let track = area.inner(&Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    if len <= track.height as usize {
+        return;
+    }
+    let mut state = ScrollbarState::new(len).position(selected);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    f.render_stateful_widget(scrollbar, track, &mut state);
+}
+
+fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let Some(encounter) = s.history.current_encounter() else {
         let block = Paragraph::new("No encounter selected.")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
@@ -200,6 +418,7 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         return;
     };
 
+    let wall_secs = record.wall_duration_secs();
     let basic_metrics = [
         (
             "Encounter",
@@ -217,15 +436,41 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
                 record.encounter.zone.clone()
             },
         ),
-        ("Duration", record.encounter.duration.clone()),
+        (
+            "Duration",
+            annotated_duration(&record.encounter.duration, wall_secs),
+        ),
         ("ENCDPS", record.encounter.encdps.clone()),
-        ("Damage", record.encounter.damage.clone()),
+        (
+            "Damage",
+            display_total(&record.encounter.damage, s.settings.abbreviate_numbers),
+        ),
+        (
+            "Note",
+            encounter
+                .note
+                .clone()
+                .unwrap_or_else(|| "none (n to add)".to_string()),
+        ),
     ];
 
     let technical_metrics = [
         ("Snapshots", record.snapshots.to_string()),
         ("Frames", record.frames.len().to_string()),
+        ("Wall clock", format_clock(wall_secs)),
+        ("Started", format_started(record.first_seen_ms)),
         ("Last seen", encounter.timestamp_label.clone()),
+        (
+            "Tags",
+            match encounter.note.as_deref().map(note_tags) {
+                Some(tags) if !tags.is_empty() => tags
+                    .iter()
+                    .map(|tag| format!("#{tag}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                _ => "none".to_string(),
+            },
+        ),
     ];
 
     let summary_lines: Vec<Line> = basic_metrics
@@ -248,95 +493,172 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
         })
         .collect();
 
-    let max_summary_rows = summary_lines.len().max(technical_lines.len());
-    let mut summary_height = max_summary_rows.saturating_add(2) as u16;
-    let max_height = area.height.max(1u16);
-    if summary_height > max_height {
-        summary_height = max_height;
-    }
-    let min_required = 3u16.min(max_height);
-    if summary_height < min_required {
-        summary_height = min_required;
-    }
+    // On short terminals the summary boxes and the hint give way to one summary line, so the
+    // combatants table keeps its minimum height; the panel header still lists the keys.
+    let full_summary_height = summary_lines.len().max(technical_lines.len()) as u16 + 2;
+    let compact = area.height < full_summary_height + DETAIL_TABLE_MIN_HEIGHT + 1;
+    let (summary_height, hint_height) = if compact {
+        (1, 0)
+    } else {
+        (full_summary_height, 1)
+    };
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(summary_height),
-            Constraint::Min(4),
-            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(hint_height),
         ])
         .split(area);
 
+    if compact {
+        let spans: Vec<Span> = basic_metrics
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, (label, value))| {
+                let separator = if idx == 0 { "" } else { " · " };
+                [
+                    Span::styled(format!("{separator}{label}: "), header_style()),
+                    Span::styled(value.clone(), value_style()),
+                ]
+            })
+            .collect();
+        f.render_widget(Paragraph::new(Line::from(spans)), layout[0]);
+    } else {
+        draw_detail_summary(
+            f,
+            layout[0],
+            &encounter.display_title,
+            summary_lines,
+            technical_lines,
+            record,
+        );
+    }
+
+    if layout[1].height > 0 {
+        draw_detail_combatants(f, layout[1], s, record);
+    }
+
+    if hint_height > 0 {
+        let hint = Paragraph::new(
+            "← back · ↑/↓ switch encounter · n note · e CSV · j JSON · y copy · w link · r replay · Enter re-open",
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::NONE));
+        f.render_widget(hint, layout[2]);
+    }
+}
+
+/// The Details and Technical Details boxes, with the DPS sparkline under the technical lines.
+fn draw_detail_summary(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    summary_lines: Vec<Line>,
+    technical_lines: Vec<Line>,
+    record: &EncounterRecord,
+) {
     let summary_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(layout[0]);
+        .split(area);
 
     let summary = Paragraph::new(summary_lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(Line::from(vec![Span::styled(
-                    format!("Details · {}", encounter.display_title),
+                    format!("Details · {title}"),
                     title_style(),
                 )])),
         )
         .alignment(Alignment::Left);
     f.render_widget(summary, summary_chunks[0]);
 
-    let technical = Paragraph::new(technical_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(Line::from(vec![Span::styled(
-                    "Technical Details".to_string(),
-                    title_style(),
-                )])),
-        )
-        .alignment(Alignment::Left);
-    f.render_widget(technical, summary_chunks[1]);
+    let technical_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Line::from(vec![Span::styled(
+            "Technical Details".to_string(),
+            title_style(),
+        )]));
+    let technical_inner = technical_block.inner(summary_chunks[1]);
+    f.render_widget(technical_block, summary_chunks[1]);
+
+    let technical_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(technical_lines.len() as u16),
+            Constraint::Min(0),
+        ])
+        .split(technical_inner);
 
+    let technical = Paragraph::new(technical_lines).alignment(Alignment::Left);
+    f.render_widget(technical, technical_chunks[0]);
+
+    if technical_chunks[1].height > 0 {
+        let timeline = record.dps_timeline();
+        // A single point is not a curve; leave the sparkline empty in that case.
+        let data = if timeline.len() > 1 {
+            sample_timeline(&timeline, technical_chunks[1].width as usize)
+        } else {
+            Vec::new()
+        };
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, technical_chunks[1]);
+    }
+}
+
+fn draw_detail_combatants(f: &mut Frame, area: Rect, s: &AppSnapshot, record: &EncounterRecord) {
     if record.rows.is_empty() {
         let block = Paragraph::new("No combatants recorded.")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
-        f.render_widget(block, layout[1]);
+        f.render_widget(block, area);
     } else {
-        let widths = [
-            Constraint::Length(18),
-            Constraint::Length(6),
-            Constraint::Length(8),
-            Constraint::Length(8),
-            Constraint::Length(10),
-            Constraint::Length(6),
-            Constraint::Length(6),
-            Constraint::Length(6),
-        ];
-
-        let header = Row::new(vec![
-            Cell::from("Name"),
-            Cell::from("Job"),
-            Cell::from("ENCDPS"),
-            Cell::from("Share"),
-            Cell::from("Damage"),
-            Cell::from("Crit%"),
-            Cell::from("DH%"),
-            Cell::from("Deaths"),
-        ])
-        .style(header_style());
-
-        let rows = record.rows.iter().map(|row| {
-            Row::new(vec![
-                Cell::from(row.name.clone()).style(Style::default().fg(job_color(&row.job))),
-                Cell::from(row.job.clone()),
-                Cell::from(row.encdps_str.clone()),
-                Cell::from(row.share_str.clone()),
-                Cell::from(row.damage_str.clone()),
-                Cell::from(row.crit.clone()),
-                Cell::from(row.dh.clone()),
-                Cell::from(row.deaths.clone()),
-            ])
+        // CDH% only joins once the fixed columns and a readable Max hit still fit.
+        let show_cdh = area.width >= DETAIL_CDH_MIN_WIDTH;
+        let show_active = area.width >= DETAIL_ACTIVE_MIN_WIDTH;
+        let columns: Vec<DetailColumn> = DETAIL_COLUMNS
+            .iter()
+            .copied()
+            .filter(|column| match column {
+                DetailColumn::Cdh => show_cdh,
+                DetailColumn::Active => show_active,
+                _ => true,
+            })
+            .collect();
+        let widths: Vec<Constraint> = columns.iter().map(|column| column.width()).collect();
+        let header_cells: Vec<Cell> = columns
+            .iter()
+            .map(|column| Cell::from(column.header()))
+            .collect();
+        let header = Row::new(header_cells).style(header_style());
+
+        let mut combatants = record.rows.clone();
+        // Records from builds that didn't parse ACT's duration fall back to the stored frames.
+        if show_active && combatants.iter().any(|row| row.duration.is_empty()) {
+            let frame_secs = record.frame_active_secs();
+            for row in combatants.iter_mut().filter(|row| row.duration.is_empty()) {
+                if let Some(secs) = frame_secs.get(&row.name) {
+                    row.duration = format_clock(*secs);
+                }
+            }
+        }
+        mark_self(&mut combatants, &s.settings.character_name);
+        if s.settings.anonymize {
+            anonymize_rows(&mut combatants);
+        }
+        let abbreviate = s.settings.abbreviate_numbers;
+        let rows = combatants.iter().map(|row| {
+            Row::new(
+                columns
+                    .iter()
+                    .map(|column| column.cell(row, abbreviate))
+                    .collect::<Vec<_>>(),
+            )
         });
 
         let table = Table::new(rows, widths)
@@ -345,13 +667,284 @@ fn draw_encounter_detail(f: &mut Frame, area: Rect, s: &AppSnapshot) {
             .column_spacing(1)
             .highlight_style(Style::default());
 
-        f.render_widget(table, layout[1]);
+        f.render_widget(table, area);
     }
+}
 
-    let hint = Paragraph::new("← back · ↑/↓ switch encounter · Enter re-open")
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(hint, layout[2]);
+fn draw_day_summary(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let Some(day) = s.history.current_day() else {
+        let block = Paragraph::new("No date selected.")
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(block, area);
+        return;
+    };
+
+    let title = Line::from(vec![Span::styled(
+        format!("Day summary · {}", day.label),
+        title_style(),
+    )]);
+    let Some(aggregate) = day.aggregate.as_ref() else {
+        let block = Paragraph::new("Aggregating encounters…")
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(block, area);
+        return;
+    };
+
+    let top_encdps = if aggregate.encounter_count == 0 {
+        "—".to_string()
+    } else {
+        format!(
+            "{:.0} ({})",
+            aggregate.top_encdps, aggregate.top_encdps_title
+        )
+    };
+    let metrics = [
+        ("Encounters", aggregate.encounter_count.to_string()),
+        ("Combat time", format_clock(aggregate.total_combat_secs)),
+        ("Top ENCDPS", top_encdps),
+        ("Players", aggregate.players.len().to_string()),
+    ];
+    let lines: Vec<Line> = metrics
+        .iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{label}: "), header_style()),
+                Span::styled(value.clone(), value_style()),
+            ])
+        })
+        .collect();
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length((lines.len() as u16).saturating_add(2)),
+            Constraint::Min(3),
+        ])
+        .split(area);
+
+    let summary = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .alignment(Alignment::Left);
+    f.render_widget(summary, layout[0]);
+
+    if aggregate.players.is_empty() {
+        let block = Paragraph::new("No combatants recorded.")
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(block, layout[1]);
+        return;
+    }
+
+    let widths = [
+        Constraint::Length(18),
+        Constraint::Length(6),
+        Constraint::Length(11),
+    ];
+    let header = Row::new(vec![
+        Cell::from("Name"),
+        Cell::from("Job"),
+        Cell::from("Appearances"),
+    ])
+    .style(header_style());
+    let rows = aggregate.players.iter().map(|player| {
+        Row::new(vec![
+            Cell::from(player.name.clone()).style(Style::default().fg(job_color(&player.job))),
+            Cell::from(player.job.clone()),
+            Cell::from(player.appearances.to_string()),
+        ])
+    });
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title("Players"))
+        .column_spacing(1);
+    f.render_widget(table, layout[1]);
+}
+
+fn draw_job_stats(f: &mut Frame, area: Rect, s: &AppSnapshot) {
+    let title = Line::from(vec![Span::styled(
+        format!("Job stats · sorted by {}", s.history.job_stats_sort.label()),
+        title_style(),
+    )]);
+    let stats = match s.history.job_stats.as_deref() {
+        Some(stats) if !stats.is_empty() => stats,
+        loaded => {
+            let message = if loaded.is_none() {
+                "Reading all encounters…"
+            } else {
+                "No encounters with your character recorded yet."
+            };
+            let block = Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).title(title));
+            f.render_widget(block, area);
+            return;
+        }
+    };
+
+    let widths = [
+        Constraint::Length(6),
+        Constraint::Length(10),
+        Constraint::Length(11),
+        Constraint::Length(11),
+    ];
+    let header = Row::new(vec![
+        Cell::from("Job"),
+        Cell::from("Encounters"),
+        Cell::from("Avg ENCDPS"),
+        Cell::from("Best ENCDPS"),
+    ])
+    .style(header_style());
+    let rows = stats.iter().map(|stat| {
+        Row::new(vec![
+            Cell::from(stat.job.clone()).style(Style::default().fg(job_color(&stat.job))),
+            Cell::from(stat.encounters.to_string()),
+            Cell::from(format!("{:.0}", stat.average_encdps)).style(value_style()),
+            Cell::from(format!("{:.0}", stat.best_encdps)).style(value_style()),
+        ])
+    });
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .column_spacing(1);
+    f.render_widget(table, area);
+}
+
+/// Rows the detail view keeps for the combatants table (borders, header and two rows) before
+/// the summary boxes collapse into one line.
+const DETAIL_TABLE_MIN_HEIGHT: u16 = 5;
+
+/// Narrowest detail table that also gets the CDH% column: the other columns at their fixed
+/// widths, the 12-wide Max hit, spacing and borders.
+const DETAIL_CDH_MIN_WIDTH: u16 = 108;
+
+/// Narrowest detail table that also gets the Active column, on top of CDH%.
+const DETAIL_ACTIVE_MIN_WIDTH: u16 = 115;
+
+/// Columns of the encounter detail table, in display order.
+#[derive(Clone, Copy)]
+enum DetailColumn {
+    Name,
+    Job,
+    Encdps,
+    Share,
+    Damage,
+    Overheal,
+    Crit,
+    Dh,
+    Cdh,
+    Active,
+    Deaths,
+    MaxHit,
+}
+
+const DETAIL_COLUMNS: [DetailColumn; 12] = [
+    DetailColumn::Name,
+    DetailColumn::Job,
+    DetailColumn::Encdps,
+    DetailColumn::Share,
+    DetailColumn::Damage,
+    DetailColumn::Overheal,
+    DetailColumn::Crit,
+    DetailColumn::Dh,
+    DetailColumn::Cdh,
+    DetailColumn::Active,
+    DetailColumn::Deaths,
+    DetailColumn::MaxHit,
+];
+
+impl DetailColumn {
+    fn header(self) -> &'static str {
+        match self {
+            DetailColumn::Name => "Name",
+            DetailColumn::Job => "Job",
+            DetailColumn::Encdps => "ENCDPS",
+            DetailColumn::Share => "Share",
+            DetailColumn::Damage => "Damage",
+            DetailColumn::Overheal => "Overheal",
+            DetailColumn::Crit => "Crit%",
+            DetailColumn::Dh => "DH%",
+            DetailColumn::Cdh => "CDH%",
+            DetailColumn::Active => "Active",
+            DetailColumn::Deaths => "Deaths",
+            DetailColumn::MaxHit => "Max hit",
+        }
+    }
+
+    fn width(self) -> Constraint {
+        match self {
+            DetailColumn::Name => Constraint::Length(18),
+            DetailColumn::Encdps | DetailColumn::Share => Constraint::Length(8),
+            DetailColumn::Damage | DetailColumn::Overheal => Constraint::Length(10),
+            DetailColumn::MaxHit => Constraint::Min(12),
+            _ => Constraint::Length(6),
+        }
+    }
+
+    fn cell(self, row: &CombatantRow, abbreviate: bool) -> Cell<'static> {
+        match self {
+            DetailColumn::Name => {
+                let style = Style::default().fg(job_color(&row.job));
+                Cell::from(row.name.clone()).style(if row.is_self {
+                    style.add_modifier(Modifier::BOLD)
+                } else {
+                    style
+                })
+            }
+            DetailColumn::Job => Cell::from(row.job.clone()),
+            DetailColumn::Encdps => Cell::from(row.encdps_str.clone()),
+            DetailColumn::Share => Cell::from(row.share_str.clone()),
+            DetailColumn::Damage => Cell::from(if abbreviate {
+                abbreviate_number(row.damage)
+            } else {
+                row.damage_str.clone()
+            }),
+            DetailColumn::Overheal => Cell::from(display_total(&row.overheal_str, abbreviate)),
+            DetailColumn::Crit => Cell::from(row.crit.clone()),
+            DetailColumn::Dh => Cell::from(row.dh.clone()),
+            DetailColumn::Cdh => Cell::from(row.cdh.clone()),
+            DetailColumn::Active => Cell::from(row.duration.clone()),
+            DetailColumn::Deaths => Cell::from(row.deaths.clone()),
+            DetailColumn::MaxHit => Cell::from(row.max_hit_str.clone()),
+        }
+    }
+}
+
+/// ACT's duration and the wall clock may differ by this much before we flag it.
+const DURATION_DRIFT_TOLERANCE_SECS: u64 = 3;
+
+/// ACT's duration string, annotated with the wall-clock duration when the two drift apart.
+fn annotated_duration(act_duration: &str, wall_secs: u64) -> String {
+    match parse_duration_secs(act_duration) {
+        Some(act_secs) if act_secs.abs_diff(wall_secs) > DURATION_DRIFT_TOLERANCE_SECS => {
+            format!("{act_duration} (wall {})", format_clock(wall_secs))
+        }
+        _ => act_duration.to_string(),
+    }
+}
+
+/// Formats the encounter's first-seen time like the "Last seen" label.
+fn format_started(first_seen_ms: u64) -> String {
+    millis_to_local(first_seen_ms)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Downsamples the DPS timeline so it fits within `width` sparkline columns.
+pub fn sample_timeline(timeline: &[(u64, f64)], width: usize) -> Vec<u64> {
+    if width == 0 || timeline.is_empty() {
+        return Vec::new();
+    }
+    let values = timeline.iter().map(|(_, dps)| dps.max(0.0).round() as u64);
+    if timeline.len() <= width {
+        return values.collect();
+    }
+    let step = timeline.len() as f64 / width as f64;
+    let values: Vec<u64> = values.collect();
+    (0..width)
+        .map(|i| values[((i as f64 * step) as usize).min(values.len() - 1)])
+        .collect()
 }
 
 fn render_loading_overlay(f: &mut Frame, area: Rect, message: &str) {
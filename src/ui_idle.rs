@@ -1,14 +1,17 @@
+use chrono::{Local, TimeZone};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Sparkline};
 use ratatui::Frame;
 
-use crate::model::{AppSnapshot, IdleScene};
-use crate::theme::{header_style, title_style, value_style, TEXT};
+use crate::model::{AppSnapshot, CritLeader, IdleHighlights, IdleScene};
+use crate::theme::{self, header_style, job_color, title_style, value_style};
+use crate::ui_history::sample_timeline;
 
-/// Default order new idle widgets should rotate through once rotation logic lands.
-#[allow(dead_code)]
+const CRIT_BAR_WIDTH: usize = 20;
+
+/// Order the idle scenes rotate through after the Status scene.
 pub const DEFAULT_ROTATION: [IdleScene; 4] = [
     IdleScene::TopCritChain,
     IdleScene::TipOfTheDay,
@@ -16,6 +19,24 @@ pub const DEFAULT_ROTATION: [IdleScene; 4] = [
     IdleScene::AchievementTicker,
 ];
 
+/// Steps through Status followed by `DEFAULT_ROTATION`, wrapping at either end.
+pub fn rotate_scene(current: IdleScene, forward: bool) -> IdleScene {
+    let cycle: Vec<IdleScene> = std::iter::once(IdleScene::Status)
+        .chain(DEFAULT_ROTATION)
+        .collect();
+    let len = cycle.len();
+    let idx = cycle
+        .iter()
+        .position(|scene| *scene == current)
+        .unwrap_or(0);
+    let next = if forward {
+        (idx + 1) % len
+    } else {
+        (idx + len - 1) % len
+    };
+    cycle[next]
+}
+
 pub fn draw_idle(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
     f.render_widget(Clear, area);
 
@@ -42,17 +63,22 @@ fn render_header(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
 
     let description = Line::from(vec![Span::styled(
         snapshot.idle_scene.description(),
-        Style::default().fg(TEXT).add_modifier(Modifier::DIM),
+        Style::default()
+            .fg(theme::text())
+            .add_modifier(Modifier::DIM),
     )]);
 
     let block = Block::default().borders(Borders::NONE);
-    let mut lines = vec![title, description];
-    if snapshot.idle_scene == IdleScene::Status {
-        lines.push(Line::from(vec![Span::styled(
-            "press 'i' to toggle idle window",
-            Style::default().fg(TEXT).add_modifier(Modifier::DIM),
-        )]));
-    }
+    let lines = vec![
+        title,
+        description,
+        Line::from(vec![Span::styled(
+            "press 'i' to toggle idle window · '[' / ']' or Tab to cycle scenes",
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
+        )]),
+    ];
 
     let widget = Paragraph::new(lines)
         .block(block)
@@ -62,28 +88,91 @@ fn render_header(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
 }
 
 fn render_scene(f: &mut Frame, area: Rect, snapshot: &AppSnapshot) {
+    let title = match snapshot.idle_scene {
+        IdleScene::TopCritChain if has_crit_leaders(snapshot) => "Last encounter",
+        IdleScene::Status | IdleScene::TopCritChain => "Status",
+        _ => "Coming soon",
+    };
     let block = Block::default()
-        .title(Line::from(vec![Span::styled(
-            "Coming soon",
-            header_style(),
-        )]))
+        .title(Line::from(vec![Span::styled(title, header_style())]))
         .borders(Borders::ALL);
 
     let lines = scene_lines(snapshot);
-    let paragraph = Paragraph::new(lines)
-        .block(block)
+    if snapshot.idle_scene != IdleScene::Status {
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .alignment(Alignment::Center);
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(lines.len() as u16 + 1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center),
+        chunks[0],
+    );
+    render_recent_dps(f, chunks[1], chunks[2], snapshot);
+}
+
+/// Party ENCDPS progression of the most recent stored encounter.
+fn render_recent_dps(f: &mut Frame, caption_area: Rect, graph_area: Rect, snapshot: &AppSnapshot) {
+    let timeline = snapshot
+        .idle_highlights
+        .as_ref()
+        .filter(|highlights| !highlights.dps_timeline.is_empty());
+    let Some(highlights) = timeline else {
+        let caption = Paragraph::new(Line::from(vec![Span::styled(
+            "No recent encounters",
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
+        )]))
         .alignment(Alignment::Center);
+        f.render_widget(caption, caption_area);
+        return;
+    };
+
+    let peak = highlights
+        .dps_timeline
+        .iter()
+        .map(|(_, dps)| *dps)
+        .fold(0.0_f64, f64::max);
+    let caption = Paragraph::new(Line::from(vec![
+        Span::styled(format!("{} · ENCDPS", highlights.title), header_style()),
+        Span::raw("  "),
+        Span::styled(format!("peak {:.0}", peak), value_style()),
+    ]))
+    .alignment(Alignment::Center);
+    f.render_widget(caption, caption_area);
 
-    f.render_widget(paragraph, area);
+    if graph_area.height == 0 {
+        return;
+    }
+    let data = sample_timeline(&highlights.dps_timeline, graph_area.width as usize);
+    let sparkline = Sparkline::default()
+        .data(&data)
+        .style(Style::default().fg(theme::accent_2()));
+    f.render_widget(sparkline, graph_area);
 }
 
 fn scene_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
     match snapshot.idle_scene {
         IdleScene::Status => status_lines(snapshot),
-        IdleScene::TopCritChain => placeholder(
-            "Top crit chain",
-            "This panel will highlight the largest crit sequences across the party.",
-        ),
+        IdleScene::TopCritChain => match &snapshot.idle_highlights {
+            Some(highlights) if has_crit_leaders(snapshot) => {
+                crit_lines(highlights, snapshot.settings.anonymize)
+            }
+            _ => status_lines(snapshot),
+        },
         IdleScene::AsciiArt => placeholder(
             "ASCII art rotation",
             "Drop .txt art here and the idle loop will cycle through it.",
@@ -99,6 +188,14 @@ fn scene_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
     }
 }
 
+/// Whether the crit scene has stored leaders to show; without them it falls back to the status.
+fn has_crit_leaders(snapshot: &AppSnapshot) -> bool {
+    snapshot
+        .idle_highlights
+        .as_ref()
+        .is_some_and(|highlights| !highlights.crit_leaders.is_empty())
+}
+
 fn status_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
     let connection = if snapshot.connected {
         if snapshot.is_idle {
@@ -123,10 +220,104 @@ fn status_lines(snapshot: &AppSnapshot) -> Vec<Line<'static>> {
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| "No active encounter".to_string());
 
-    vec![
+    let mut lines = vec![
         Line::from(vec![Span::styled(connection, value_style())]),
         Line::from(vec![Span::styled(encounter_label, value_style())]),
-    ]
+    ];
+    if let Some(line) = feed_rate_line(snapshot) {
+        lines.push(line);
+    }
+    if !snapshot.recent_deaths.is_empty() {
+        let mut spans = vec![Span::styled("Recent deaths: ", header_style())];
+        for (idx, death) in snapshot.recent_deaths.iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::raw(" · "));
+            }
+            spans.push(Span::styled(death.name.clone(), value_style()));
+            if let Some(time) = Local.timestamp_millis_opt(death.ms as i64).single() {
+                spans.push(Span::styled(
+                    format!(" {}", time.format("%H:%M:%S")),
+                    Style::default()
+                        .fg(theme::text())
+                        .add_modifier(Modifier::DIM),
+                ));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Update rate and first-frame latency, to tell a laggy feed apart from a dead one.
+fn feed_rate_line(snapshot: &AppSnapshot) -> Option<Line<'static>> {
+    if !snapshot.connected {
+        return None;
+    }
+    let rate = match snapshot.update_rate {
+        Some(rate) => format!("~{rate:.1} updates/sec"),
+        None if snapshot.first_frame_ms.is_some() => "Measuring update rate…".to_string(),
+        None => "Waiting for first update".to_string(),
+    };
+    let mut spans = vec![Span::styled(rate, value_style())];
+    if let Some(ms) = snapshot.first_frame_ms {
+        spans.push(Span::styled(
+            format!(" · first frame after {ms} ms"),
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
+        ));
+    }
+    Some(Line::from(spans))
+}
+
+fn crit_lines(highlights: &IdleHighlights, anonymize: bool) -> Vec<Line<'static>> {
+    let max_crit = highlights
+        .crit_leaders
+        .iter()
+        .map(|leader| leader.crit)
+        .fold(0.0_f64, f64::max);
+    let name = |leader: &CritLeader| {
+        if anonymize {
+            leader.placeholder.clone()
+        } else {
+            leader.name.clone()
+        }
+    };
+    let name_width = highlights
+        .crit_leaders
+        .iter()
+        .map(|leader| name(leader).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = vec![
+        Line::from(vec![Span::styled(
+            format!("Top crit · {}", highlights.title),
+            value_style(),
+        )]),
+        Line::default(),
+    ];
+    for (idx, leader) in highlights.crit_leaders.iter().enumerate() {
+        let ratio = if max_crit > 0.0 {
+            (leader.crit / max_crit).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let filled = (ratio * CRIT_BAR_WIDTH as f64).round() as usize;
+        let color = job_color(&leader.job);
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}. ", idx + 1), header_style()),
+            Span::styled(
+                format!("{:<name_width$} ", name(leader)),
+                Style::default().fg(color),
+            ),
+            Span::styled(format!("{:<4}", leader.job), header_style()),
+            Span::styled(format!("{:>7} ", leader.crit_str), value_style()),
+            Span::styled("█".repeat(filled), Style::default().fg(color)),
+            Span::raw(" ".repeat(CRIT_BAR_WIDTH - filled)),
+        ]));
+    }
+    lines
 }
 
 fn placeholder(title: &str, caption: &str) -> Vec<Line<'static>> {
@@ -134,8 +325,10 @@ fn placeholder(title: &str, caption: &str) -> Vec<Line<'static>> {
         Line::from(vec![Span::styled(title.to_string(), value_style())]),
         Line::from(vec![Span::styled(caption.to_string(), header_style())]),
         Line::from(vec![Span::styled(
-            "Rotate scenes via DEFAULT_ROTATION or update AppState::idle_scene.",
-            Style::default().fg(TEXT).add_modifier(Modifier::DIM),
+            "Press '[' / ']' or Tab to switch scenes.",
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
         )]),
     ]
 }
@@ -0,0 +1,70 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::model::AppSnapshot;
+use crate::theme::{self, header_style, value_style};
+
+/// Recent log lines in a centered pane, newest at the bottom; `log_scroll` moves the window up.
+pub fn draw_logs(f: &mut Frame, s: &AppSnapshot) {
+    let area = logs_area(f.size());
+    f.render_widget(Clear, area);
+
+    let inner_height = area.height.saturating_sub(3) as usize;
+    let end = s.logs.len().saturating_sub(s.log_scroll);
+    let start = end.saturating_sub(inner_height);
+    let mut lines: Vec<Line> = if s.logs.is_empty() {
+        vec![Line::from(Span::styled(
+            " Nothing logged yet.",
+            Style::default()
+                .fg(theme::text())
+                .add_modifier(Modifier::DIM),
+        ))]
+    } else {
+        s.logs[start..end]
+            .iter()
+            .map(|line| Line::from(Span::styled(line.clone(), log_style(line))))
+            .collect()
+    };
+    while lines.len() < inner_height {
+        lines.insert(0, Line::default());
+    }
+    let position = if s.log_scroll > 0 {
+        format!(" · {} lines up", s.log_scroll)
+    } else {
+        String::new()
+    };
+    lines.push(Line::from(Span::styled(
+        format!(" ↑/↓ PgUp/PgDn scroll · End newest · Esc closes{position}"),
+        header_style(),
+    )));
+
+    let block = Block::default()
+        .title(format!("Log ({} lines)", s.logs.len()))
+        .borders(Borders::ALL);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Warnings and errors stand out from the info lines.
+fn log_style(line: &str) -> Style {
+    if line.contains(" ERROR ") {
+        Style::default().fg(theme::status_disconnected())
+    } else if line.contains(" WARN ") {
+        Style::default().fg(theme::status_idle())
+    } else {
+        value_style()
+    }
+}
+
+fn logs_area(screen: Rect) -> Rect {
+    let width = screen.width.saturating_sub(4).max(screen.width.min(20));
+    let height = (screen.height * 7 / 10).max(screen.height.min(8));
+    Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    }
+}
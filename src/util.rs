@@ -0,0 +1,95 @@
+//! Small display helpers shared by the live and history views.
+
+use crate::parse::to_f64_any;
+
+/// Shortens large values to `1.2K` / `12.3M` / `4.5B`; values under a thousand are rounded.
+pub fn abbreviate_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e9 {
+        format!("{:.1}B", value / 1e9)
+    } else if magnitude >= 1e6 {
+        format!("{:.1}M", value / 1e6)
+    } else if magnitude >= 1e3 {
+        format!("{:.1}K", value / 1e3)
+    } else {
+        format!("{value:.0}")
+    }
+}
+
+/// Rounds to a whole number with comma thousands separators, matching ACT's `12,345`.
+pub fn group_thousands(value: f64) -> String {
+    let digits = format!("{:.0}", value.abs());
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if value < 0.0 && digits != "0" {
+        grouped.push('-');
+    }
+    for (idx, ch) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    grouped
+}
+
+/// Returns `raw` unchanged, or its parsed value abbreviated when `abbreviate` is on.
+/// Blank strings stay blank so missing fields don't turn into "0".
+pub fn display_total(raw: &str, abbreviate: bool) -> String {
+    if !abbreviate || raw.trim().is_empty() {
+        return raw.to_string();
+    }
+    abbreviate_number(to_f64_any(raw))
+}
+
+/// Percent-encodes `text` for use inside a URL query, keeping only RFC 3986 unreserved characters.
+pub fn encode_url_component(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Formats seconds as `M:SS`, or `H:MM:SS` from an hour up.
+pub fn format_clock(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abbreviates_by_magnitude() {
+        assert_eq!(abbreviate_number(999.4), "999");
+        assert_eq!(abbreviate_number(1_234.0), "1.2K");
+        assert_eq!(abbreviate_number(12_345_678.0), "12.3M");
+        assert_eq!(abbreviate_number(4_500_000_000.0), "4.5B");
+        assert_eq!(display_total("12,345,678", true), "12.3M");
+        assert_eq!(display_total("12,345,678", false), "12,345,678");
+        assert_eq!(display_total("", true), "");
+        assert_eq!(group_thousands(999.4), "999");
+        assert_eq!(group_thousands(21_000.0), "21,000");
+        assert_eq!(group_thousands(1_234_567.8), "1,234,568");
+        assert_eq!(group_thousands(-4_500.0), "-4,500");
+        assert_eq!(format_clock(75), "1:15");
+        assert_eq!(format_clock(3_725), "1:02:05");
+        assert_eq!(
+            encode_url_component("M4S: Wicked/Thunder"),
+            "M4S%3A%20Wicked%2FThunder"
+        );
+        assert_eq!(
+            encode_url_component("Ifrit (Extreme)"),
+            "Ifrit%20%28Extreme%29"
+        );
+    }
+}
@@ -1,40 +1,268 @@
-use std::time::Duration;
+use std::collections::hash_map::RandomState;
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use anyhow::{bail, Context, Result};
 use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
 use serde_json::Value;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::time::sleep;
 use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::{uri_mode, IntoClientRequest};
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::tungstenite::protocol::frame::CloseFrame;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{debug, info, warn};
 
+use crate::config::{self, AppConfig};
 use crate::history::RecorderHandle;
 use crate::model::AppEvent;
-use crate::parse::parse_combat_data;
+use crate::parse::{parse_combat_data, parse_encounter_boundary, parse_language, parse_log_line};
 
-pub async fn run(ws_url: String, tx: UnboundedSender<AppEvent>, history: RecorderHandle) {
-    // Simple reconnect loop
+/// Checks that `ws_url` is a well-formed `ws://` or `wss://` endpoint before we start dialing it.
+pub fn validate_url(ws_url: &str) -> Result<()> {
+    let request = ws_url
+        .into_client_request()
+        .with_context(|| format!("Invalid WebSocket URL `{ws_url}`"))?;
+    uri_mode(request.uri()).with_context(|| {
+        format!("Invalid WebSocket URL `{ws_url}` (expected a ws:// or wss:// scheme)")
+    })?;
+    Ok(())
+}
+
+/// Optional token for endpoints behind an authenticating reverse proxy.
+#[derive(Clone, Debug, Default)]
+pub struct WsAuth {
+    token: Option<String>,
+    /// Send the token as `?token=` instead of an `Authorization: Bearer` header.
+    query: bool,
+}
+
+impl WsAuth {
+    pub fn from_config(cfg: &AppConfig) -> Self {
+        Self {
+            token: cfg.ws_auth_token.clone().filter(|token| !token.is_empty()),
+            query: cfg.ws_auth_query,
+        }
+    }
+
+    /// Where the token goes, for logs; never the token itself.
+    fn describe(&self) -> &'static str {
+        match (&self.token, self.query) {
+            (None, _) => "none",
+            (Some(_), true) => "query",
+            (Some(_), false) => "header",
+        }
+    }
+}
+
+/// Builds the handshake request for `ws_url`, attaching the auth token when one is configured.
+pub fn handshake_request(ws_url: &str, auth: &WsAuth) -> Result<Request> {
+    let Some(token) = &auth.token else {
+        return ws_url
+            .into_client_request()
+            .with_context(|| format!("Invalid WebSocket URL `{ws_url}`"));
+    };
+    if auth.query {
+        let separator = if ws_url.contains('?') { '&' } else { '?' };
+        let url = format!("{ws_url}{separator}token={}", percent_encode(token));
+        return url
+            .into_client_request()
+            .with_context(|| format!("Invalid WebSocket URL `{ws_url}`"));
+    }
+    let mut request = ws_url
+        .into_client_request()
+        .with_context(|| format!("Invalid WebSocket URL `{ws_url}`"))?;
+    let value = HeaderValue::from_str(&format!("Bearer {token}"))
+        .context("ws_auth_token contains characters not allowed in an HTTP header")?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(request)
+}
+
+/// Escapes everything but RFC 3986 unreserved characters for use in a query value.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+/// A capture past this size is rotated to `<path>.1` (replacing an older one) and restarted.
+const CAPTURE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// `--capture <path>`: appends every received text frame as one line, in the format
+/// `--replay-file` reads back.
+pub struct CaptureWriter {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl CaptureWriter {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::with_limit(path, CAPTURE_MAX_BYTES)
+    }
+
+    fn with_limit(path: &Path, max_bytes: u64) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Unable to open capture file {}", path.display()))?;
+        let written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            written,
+            max_bytes,
+        })
+    }
+
+    /// Moves the writer onto its own thread so file writes never hold up the WebSocket reader.
+    pub fn spawn(mut self) -> Result<CaptureHandle> {
+        let (tx, rx) = std::sync::mpsc::channel::<String>();
+        std::thread::Builder::new()
+            .name("capture".to_string())
+            .spawn(move || {
+                for txt in rx {
+                    if let Err(err) = self.write_frame(&txt) {
+                        warn!(error = ?err, "failed to write capture; capture stopped");
+                        break;
+                    }
+                }
+            })
+            .context("Unable to start the capture writer")?;
+        Ok(CaptureHandle { tx })
+    }
+
+    fn write_frame(&mut self, txt: &str) -> Result<()> {
+        // One frame per line: a pretty-printed frame is re-serialized compactly
+        let line = if txt.contains(['\n', '\r']) {
+            match serde_json::from_str::<Value>(txt) {
+                Ok(val) => format!("{val}\n"),
+                Err(_) => {
+                    warn!("skipped a multi-line capture frame that is not JSON");
+                    return Ok(());
+                }
+            }
+        } else {
+            format!("{txt}\n")
+        };
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        std::fs::rename(&self.path, &rotated)?;
+        *self = Self::with_limit(&self.path, self.max_bytes)?;
+        Ok(())
+    }
+}
+
+/// Queues frames for a [`CaptureWriter`] running on its own thread.
+pub struct CaptureHandle {
+    tx: std::sync::mpsc::Sender<String>,
+}
+
+impl CaptureHandle {
+    /// False once the writer thread has stopped.
+    fn send(&self, txt: &str) -> bool {
+        self.tx.send(txt.to_string()).is_ok()
+    }
+}
+
+/// Pace of `--replay-file` playback, about ACT's own update rate.
+const CAPTURE_INTERVAL: Duration = Duration::from_secs(1);
+
+const BACKOFF_BASE_MS: u64 = 500;
+const BACKOFF_MAX_MS: u64 = 30_000;
+// Connections that stay up at least this long reset the backoff to its base delay.
+const STABLE_CONNECTION: Duration = Duration::from_secs(5);
+
+/// An OverlayPlugin API call sent as a text frame during the handshake.
+#[derive(Serialize)]
+struct OverlayCall<'a> {
+    call: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<&'a [String]>,
+}
+
+impl OverlayCall<'_> {
+    fn to_message(&self) -> String {
+        serde_json::to_string(self).expect("overlay call serializes")
+    }
+}
+
+fn get_language_message() -> String {
+    OverlayCall {
+        call: "getLanguage",
+        events: None,
+    }
+    .to_message()
+}
+
+/// The `subscribe` call for `events`, falling back to the default events when none are set.
+pub fn subscribe_message(events: &[String]) -> String {
+    let defaults = config::default_subscribe_events();
+    let events = if events.is_empty() { &defaults } else { events };
+    OverlayCall {
+        call: "subscribe",
+        events: Some(events),
+    }
+    .to_message()
+}
+
+pub async fn run(
+    ws_url: String,
+    auth: WsAuth,
+    subscribe_events: Vec<String>,
+    mut capture: Option<CaptureHandle>,
+    tx: UnboundedSender<AppEvent>,
+    history: RecorderHandle,
+) {
+    // Reconnect loop with exponential backoff
+    let mut attempt: u32 = 0;
     loop {
-        debug!(%ws_url, "websocket connect attempt");
-        match connect_async(&ws_url).await {
+        attempt = attempt.saturating_add(1);
+        debug!(%ws_url, attempt, auth = auth.describe(), "websocket connect attempt");
+        let request = match handshake_request(&ws_url, &auth) {
+            Ok(request) => request,
+            Err(err) => {
+                warn!(error = ?err, "unable to build websocket handshake request");
+                return;
+            }
+        };
+        match connect_async(request).await {
             Ok((ws_stream, resp)) => {
+                let connected_at = Instant::now();
                 let (mut write, mut read) = ws_stream.split();
                 info!(status = ?resp.status(), "websocket connected");
                 let _ = tx.send(AppEvent::Connected);
 
                 // Perform handshake: getLanguage, then subscribe
-                if let Err(err) = write
-                    .send(Message::Text("{\"call\":\"getLanguage\"}".to_string()))
-                    .await
-                {
+                if let Err(err) = write.send(Message::Text(get_language_message())).await {
                     warn!(error = ?err, "failed to send getLanguage call");
                 }
                 if let Err(err) = write
-                    .send(Message::Text(
-                        "{\"call\":\"subscribe\",\"events\":[\"CombatData\",\"LogLine\"]}"
-                            .to_string(),
-                    ))
+                    .send(Message::Text(subscribe_message(&subscribe_events)))
                     .await
                 {
                     warn!(error = ?err, "failed to send subscribe call");
@@ -43,33 +271,15 @@ pub async fn run(ws_url: String, tx: UnboundedSender<AppEvent>, history: Recorde
                 // Reader loop
                 while let Some(msg) = read.next().await {
                     match msg {
-                        Ok(Message::Text(txt)) => match serde_json::from_str::<Value>(&txt) {
-                            Ok(val) => {
-                                if let Some((enc, rows)) = parse_combat_data(&val) {
-                                    history.record_components(enc.clone(), rows.clone(), val);
-                                    if tx
-                                        .send(AppEvent::CombatData {
-                                            encounter: enc,
-                                            rows,
-                                        })
-                                        .is_err()
-                                    {
-                                        warn!("receiver dropped websocket updates");
-                                        break;
-                                    }
-                                } else {
-                                    let event_type = val
-                                        .get("type")
-                                        .and_then(|t| t.as_str())
-                                        .unwrap_or("unknown");
-                                    debug!(%event_type, "ignored websocket message");
-                                }
+                        Ok(Message::Text(txt)) => {
+                            if capture.as_ref().is_some_and(|writer| !writer.send(&txt)) {
+                                capture = None;
                             }
-                            Err(err) => {
-                                let snippet: String = txt.chars().take(128).collect();
-                                warn!(error = ?err, snippet, "failed to parse websocket text frame as JSON");
+                            if dispatch_text(&txt, &tx, &history) == Dispatched::Closed {
+                                warn!("receiver dropped websocket updates");
+                                break;
                             }
-                        },
+                        }
                         Ok(Message::Binary(_)) => {
                             debug!("ignored binary websocket frame");
                         }
@@ -95,6 +305,9 @@ pub async fn run(ws_url: String, tx: UnboundedSender<AppEvent>, history: Recorde
                     debug!("receiver dropped disconnected event");
                 }
                 info!("websocket loop exited, scheduling reconnect");
+                if connected_at.elapsed() >= STABLE_CONNECTION {
+                    attempt = 0;
+                }
             }
             Err(err) => {
                 warn!(error = ?err, "websocket connection failed");
@@ -106,8 +319,151 @@ pub async fn run(ws_url: String, tx: UnboundedSender<AppEvent>, history: Recorde
         }
 
         // Backoff before reconnect
-        sleep(Duration::from_secs(1)).await;
+        let delay = with_jitter(backoff_delay(attempt));
+        let delay_ms = delay.as_millis() as u64;
+        debug!(attempt, delay_ms, "websocket reconnect backoff");
+        let next_attempt = attempt.saturating_add(1);
+        if tx
+            .send(AppEvent::Reconnecting {
+                attempt: next_attempt,
+                delay_ms,
+            })
+            .is_err()
+        {
+            debug!("receiver dropped reconnecting event");
+        }
+        sleep(delay).await;
+    }
+}
+
+/// What [`dispatch_text`] did with a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Dispatched {
+    CombatData,
+    Other,
+    /// The UI side has hung up.
+    Closed,
+}
+
+/// Parses one JSON message and forwards it: CombatData is recorded to history and sent to
+/// the UI, encounter boundary log lines go to the recorder, and death log lines and the
+/// `getLanguage` answer go to the UI.
+fn dispatch_text(
+    txt: &str,
+    tx: &UnboundedSender<AppEvent>,
+    history: &RecorderHandle,
+) -> Dispatched {
+    let val = match serde_json::from_str::<Value>(txt) {
+        Ok(val) => val,
+        Err(err) => {
+            let snippet: String = txt.chars().take(128).collect();
+            warn!(error = ?err, snippet, "failed to parse websocket text frame as JSON");
+            return Dispatched::Other;
+        }
+    };
+    if let Some((enc, rows)) = parse_combat_data(&val) {
+        history.record_components(enc.clone(), rows.clone(), val);
+        let sent = tx.send(AppEvent::CombatData {
+            encounter: enc,
+            rows,
+        });
+        return if sent.is_ok() {
+            Dispatched::CombatData
+        } else {
+            Dispatched::Closed
+        };
+    }
+    let sent = if let Some(boundary) = parse_encounter_boundary(&val) {
+        debug!(?boundary, "encounter boundary logged");
+        history.mark_boundary(boundary);
+        true
+    } else if let Some((name, ms)) = parse_log_line(&val, unix_now_ms()) {
+        tx.send(AppEvent::DeathLogged { name, ms }).is_ok()
+    } else if let Some(language) = parse_language(&val) {
+        info!(%language, "game language reported");
+        tx.send(AppEvent::GameLanguage { language }).is_ok()
+    } else {
+        let event_type = val
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("unknown");
+        debug!(%event_type, "ignored websocket message");
+        true
+    };
+    if sent {
+        Dispatched::Other
+    } else {
+        Dispatched::Closed
+    }
+}
+
+/// Loads a capture for `--replay-file`: newline-delimited JSON messages, blank lines skipped.
+pub fn read_capture(path: &Path) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read replay file {}", path.display()))?;
+    let lines: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    if lines.is_empty() {
+        bail!("Replay file {} has no messages", path.display());
     }
+    Ok(lines)
+}
+
+/// Stands in for `run` with a capture file: feeds the messages as if they came from IINACT
+/// (recording to history as usual), waiting `CAPTURE_INTERVAL` after each CombatData frame so
+/// the log lines between updates arrive together, then leaves the app connected and idle.
+pub async fn run_capture(
+    lines: Vec<String>,
+    tx: UnboundedSender<AppEvent>,
+    history: RecorderHandle,
+) {
+    if tx.send(AppEvent::Connected).is_err() {
+        return;
+    }
+    for line in lines {
+        match dispatch_text(&line, &tx, &history) {
+            Dispatched::CombatData => sleep(CAPTURE_INTERVAL).await,
+            Dispatched::Other => {}
+            Dispatched::Closed => {
+                warn!("receiver dropped replay file updates");
+                break;
+            }
+        }
+    }
+    history.flush();
+    info!("replay file finished");
+}
+
+/// Exponential delay for the given consecutive attempt (0 or 1 → base), capped at the max.
+fn backoff_delay(attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let ms = BACKOFF_BASE_MS
+        .saturating_mul(1u64 << exponent)
+        .min(BACKOFF_MAX_MS);
+    Duration::from_millis(ms)
+}
+
+/// Spreads the delay by ±20% so several clients don't reconnect in lockstep.
+fn with_jitter(delay: Duration) -> Duration {
+    delay.mul_f64(jitter_factor())
+}
+
+/// A random factor in [0.8, 1.2]. Every `RandomState` is freshly keyed, so hashing nothing
+/// with one gives a new random number without pulling in an RNG crate.
+fn jitter_factor() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    0.8 + 0.4 * (random % 1_001) as f64 / 1_000.0
+}
+
+fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 fn log_close_frame(frame: Option<&CloseFrame<'_>>) {
@@ -121,3 +477,148 @@ fn log_close_frame(frame: Option<&CloseFrame<'_>>) {
         info!("websocket closed without frame");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_token_goes_in_header_or_query() {
+        let cfg = |token: Option<&str>, query: bool| AppConfig {
+            ws_auth_token: token.map(str::to_string),
+            ws_auth_query: query,
+            ..AppConfig::default()
+        };
+        let url = "ws://127.0.0.1:10501/ws";
+
+        let plain = handshake_request(url, &WsAuth::from_config(&cfg(None, false))).unwrap();
+        assert!(plain.headers().get(AUTHORIZATION).is_none());
+        let empty = handshake_request(url, &WsAuth::from_config(&cfg(Some(""), true))).unwrap();
+        assert_eq!(empty.uri().query(), None);
+
+        let header =
+            handshake_request(url, &WsAuth::from_config(&cfg(Some("s3cret"), false))).unwrap();
+        assert_eq!(header.headers()[AUTHORIZATION], "Bearer s3cret");
+        assert_eq!(header.uri().query(), None);
+
+        let query = handshake_request(
+            "ws://host/ws?lang=en",
+            &WsAuth::from_config(&cfg(Some("a b&c"), true)),
+        )
+        .unwrap();
+        assert_eq!(query.uri().query(), Some("lang=en&token=a%20b%26c"));
+        assert!(query.headers().get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn capture_files_skip_blank_lines_and_reject_empty_files() {
+        let path =
+            std::env::temp_dir().join(format!("iinact-tui-capture-{}.ndjson", std::process::id()));
+        std::fs::write(
+            &path,
+            "{\"type\":\"CombatData\"}\n\n  \n{\"type\":\"LogLine\"}\n",
+        )
+        .unwrap();
+        let lines = read_capture(&path).unwrap();
+        assert_eq!(
+            lines,
+            ["{\"type\":\"CombatData\"}", "{\"type\":\"LogLine\"}"]
+        );
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(read_capture(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(read_capture(&path).is_err());
+    }
+
+    #[test]
+    fn capture_rotates_past_size_limit() {
+        let path =
+            std::env::temp_dir().join(format!("iinact-tui-rotate-{}.ndjson", std::process::id()));
+        let rotated = path.with_extension("ndjson.1");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&rotated);
+
+        let mut writer = CaptureWriter::with_limit(&path, 16).unwrap();
+        writer.write_frame("{\"n\":1}").unwrap();
+        writer.write_frame("{\"n\":2}").unwrap();
+        writer.write_frame("{\"n\":3}").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"n\":3}\n");
+        assert_eq!(
+            std::fs::read_to_string(&rotated).unwrap(),
+            "{\"n\":1}\n{\"n\":2}\n"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn capture_keeps_pretty_printed_frames_on_one_valid_line() {
+        let path =
+            std::env::temp_dir().join(format!("iinact-tui-pretty-{}.ndjson", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut writer = CaptureWriter::open(&path).unwrap();
+        let frame = "{\n  \"type\": \"LogLine\",\n  \"line\": [\"00\", \"a\\nb\"]\n}";
+        writer.write_frame(frame).unwrap();
+        writer.write_frame("not\njson").unwrap();
+        writer.write_frame("{\"n\":1}").unwrap();
+
+        let lines = read_capture(&path).unwrap();
+        assert_eq!(lines.len(), 2);
+        let replayed: Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(replayed, serde_json::from_str::<Value>(frame).unwrap());
+        assert_eq!(lines[1], "{\"n\":1}");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn handshake_calls_serialize_to_expected_shape() {
+        assert_eq!(get_language_message(), r#"{"call":"getLanguage"}"#);
+        assert_eq!(
+            subscribe_message(&[]),
+            r#"{"call":"subscribe","events":["CombatData","LogLine"]}"#
+        );
+    }
+
+    #[test]
+    fn subscribe_message_escapes_and_defaults_events() {
+        let parse = |msg: String| serde_json::from_str::<Value>(&msg).unwrap();
+        assert_eq!(
+            parse(subscribe_message(&[])),
+            serde_json::json!({ "call": "subscribe", "events": ["CombatData", "LogLine"] })
+        );
+        let custom = vec!["CombatData".to_string(), "Odd\"Event".to_string()];
+        assert_eq!(
+            parse(subscribe_message(&custom))["events"],
+            serde_json::json!(["CombatData", "Odd\"Event"])
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        assert_eq!(backoff_delay(1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2), Duration::from_millis(1_000));
+        assert_eq!(backoff_delay(4), Duration::from_millis(4_000));
+        assert_eq!(backoff_delay(7), Duration::from_millis(30_000));
+        assert_eq!(backoff_delay(u32::MAX), Duration::from_millis(30_000));
+    }
+
+    #[test]
+    fn jitter_stays_within_twenty_percent() {
+        let base = Duration::from_millis(1_000);
+        for _ in 0..32 {
+            let jittered = with_jitter(base);
+            assert!(jittered >= Duration::from_millis(800));
+            assert!(jittered <= Duration::from_millis(1_200));
+        }
+    }
+
+    #[test]
+    fn jitter_factor_varies_between_calls() {
+        let factors: Vec<f64> = (0..32).map(|_| jitter_factor()).collect();
+        assert!(factors.iter().all(|f| (0.8..=1.2).contains(f)));
+        assert!(factors.iter().any(|f| *f != factors[0]));
+    }
+}